@@ -2,7 +2,9 @@ use std::collections::BTreeMap;
 
 use serde::Serialize;
 
-use crate::util::units::Second;
+use crate::bootstrap::ConfidenceInterval;
+use crate::timer::ResourceCounters;
+use crate::util::units::{Byte, Scalar, Second};
 
 /// Set of values that will be exported.
 // NOTE: `serde` is used for JSON serialization, but not for CSV serialization due to the
@@ -27,12 +29,50 @@ pub struct BenchmarkResult {
     /// Time spent in kernel mode
     pub system: Second,
 
+    /// Average peak memory usage (maximum resident set size) in bytes. Not available on all
+    /// platforms
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_mean: Option<Byte>,
+
+    /// Smallest peak memory usage of all runs in bytes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_min: Option<Byte>,
+
+    /// Largest peak memory usage of all runs in bytes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_max: Option<Byte>,
+
+    /// Average page faults, context switches and block I/O operations. Not available on all
+    /// platforms
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_usage: Option<ResourceUsage>,
+
     /// Minimum of all measured times
     pub min: Second,
 
     /// Maximum of all measured times
     pub max: Second,
 
+    /// Bootstrap confidence interval for the mean run time. Not available if only one run has
+    /// been performed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mean_ci: Option<ConfidenceInterval>,
+
+    /// Bootstrap confidence interval for the median run time. Not available if only one run has
+    /// been performed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub median_ci: Option<ConfidenceInterval>,
+
+    /// Bootstrap confidence interval for the relative speed (ratio of the mean run times) with
+    /// respect to the fastest command. Not available for the fastest command itself
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub relative_speed_ci: Option<ConfidenceInterval>,
+
+    /// p-value of Welch's t-test against the fastest command. Not available for the fastest
+    /// command itself
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub p_value: Option<Scalar>,
+
     /// All run time measurements
     #[serde(skip_serializing_if = "Option::is_none")]
     pub times: Option<Vec<Second>>,
@@ -40,7 +80,99 @@ pub struct BenchmarkResult {
     /// Exit codes of all command invocations
     pub exit_codes: Vec<Option<i32>>,
 
+    /// Exit codes that are considered successful, if different from just 0
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accepted_exit_codes: Option<String>,
+
+    /// Number of runs with an exit code that is not accepted (with `--ignore-failure`)
+    #[serde(skip_serializing_if = "is_zero")]
+    pub failed_runs: u64,
+
+    /// Number of runs that exceeded the timeout and have been excluded from the statistics
+    #[serde(skip_serializing_if = "is_zero")]
+    pub timeouts: u64,
+
+    /// Number of back-to-back invocations per run in batch mode. All times are given per
+    /// invocation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub batch_size: Option<u64>,
+
+    /// Hash of the output of all runs, if it is checked (see `--check-output`) and identical
+    /// for all runs
+    #[serde(skip)]
+    pub output_hash: Option<u64>,
+
     /// Parameter values for this benchmark
     #[serde(skip_serializing_if = "BTreeMap::is_empty")]
     pub parameters: BTreeMap<String, String>,
 }
+
+fn is_zero(n: &u64) -> bool {
+    *n == 0
+}
+
+/// Resource usage counters, averaged over all runs of a benchmark
+#[derive(Debug, Default, Clone, Copy, Serialize, PartialEq)]
+pub struct ResourceUsage {
+    /// Page faults serviced without any I/O activity
+    pub minor_page_faults: Scalar,
+
+    /// Page faults that required I/O activity
+    pub major_page_faults: Scalar,
+
+    /// Context switches because the process waited for a resource
+    pub voluntary_context_switches: Scalar,
+
+    /// Context switches because the process was preempted
+    pub involuntary_context_switches: Scalar,
+
+    /// Block input operations
+    pub block_input_operations: Scalar,
+
+    /// Block output operations
+    pub block_output_operations: Scalar,
+}
+
+impl ResourceUsage {
+    /// Average the counters of all runs. Returns `None` if there are no runs.
+    pub fn from_counters(counters: &[ResourceCounters]) -> Option<ResourceUsage> {
+        if counters.is_empty() {
+            return None;
+        }
+
+        let average = |f: fn(&ResourceCounters) -> u64| {
+            counters.iter().map(|c| f(c) as Scalar).sum::<Scalar>() / counters.len() as Scalar
+        };
+        Some(ResourceUsage {
+            minor_page_faults: average(|c| c.minor_page_faults),
+            major_page_faults: average(|c| c.major_page_faults),
+            voluntary_context_switches: average(|c| c.voluntary_context_switches),
+            involuntary_context_switches: average(|c| c.involuntary_context_switches),
+            block_input_operations: average(|c| c.block_input_operations),
+            block_output_operations: average(|c| c.block_output_operations),
+        })
+    }
+}
+
+#[test]
+fn test_resource_usage_from_counters() {
+    assert_eq!(None, ResourceUsage::from_counters(&[]));
+
+    let counters = [
+        ResourceCounters {
+            minor_page_faults: 100,
+            involuntary_context_switches: 1,
+            ..Default::default()
+        },
+        ResourceCounters {
+            minor_page_faults: 200,
+            block_output_operations: 8,
+            ..Default::default()
+        },
+    ];
+    let usage = ResourceUsage::from_counters(&counters).unwrap();
+    assert_eq!(150.0, usage.minor_page_faults);
+    assert_eq!(0.0, usage.major_page_faults);
+    assert_eq!(0.5, usage.involuntary_context_switches);
+    assert_eq!(4.0, usage.block_output_operations);
+}
@@ -0,0 +1,301 @@
+use anyhow::Result;
+
+use super::benchmark_result::BenchmarkResult;
+use super::observer::Observer;
+use crate::command::{Command, Commands};
+use crate::export::{ExportManager, ExportType};
+use crate::options::{
+    CmdFailureAction, CommandOutputPolicy, ExecutorKind, ExitCodes, Options, OutputStyleOption,
+    PerCommand, RunBounds, Shell,
+};
+use crate::output::terminal::TerminalObserver;
+use crate::parameter::combination::combinations;
+use crate::parameter::ParameterValue;
+
+/// Builder for running benchmarks from Rust code. In contrast to the command-line tool, nothing
+/// is printed to the terminal unless an output style is set with [`output_style`].
+///
+/// [`output_style`]: BenchmarkBuilder::output_style
+pub struct BenchmarkBuilder {
+    commands: Vec<(Option<String>, String)>,
+    parameters: Vec<(String, Vec<String>)>,
+    min_runs: Option<u64>,
+    max_runs: Option<u64>,
+    options: Options,
+    exports: Vec<(ExportType, String)>,
+    observers: Vec<Box<dyn Observer>>,
+}
+
+impl Default for BenchmarkBuilder {
+    fn default() -> Self {
+        BenchmarkBuilder {
+            commands: vec![],
+            parameters: vec![],
+            min_runs: None,
+            max_runs: None,
+            options: Options {
+                output_style: OutputStyleOption::Disabled,
+                ..Options::default()
+            },
+            exports: vec![],
+            observers: vec![],
+        }
+    }
+}
+
+impl BenchmarkBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a command to benchmark
+    pub fn command(mut self, command: impl Into<String>) -> Self {
+        self.commands.push((None, command.into()));
+        self
+    }
+
+    /// Add a command to benchmark, with the name that is used in the results
+    pub fn named_command(mut self, name: impl Into<String>, command: impl Into<String>) -> Self {
+        self.commands.push((Some(name.into()), command.into()));
+        self
+    }
+
+    /// Benchmark the commands for each value of the parameter, see `--parameter-list`. With
+    /// multiple parameters, all combinations of their values are benchmarked.
+    pub fn parameter_list<I, S>(mut self, name: impl Into<String>, values: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let values = values.into_iter().map(Into::into).collect();
+        self.parameters.push((name.into(), values));
+        self
+    }
+
+    /// Number of warmup runs before the timing runs of every command
+    pub fn warmup(mut self, count: u64) -> Self {
+        self.options.warmup_count = PerCommand::all(count);
+        self
+    }
+
+    /// Perform exactly the given number of timing runs
+    pub fn runs(mut self, count: u64) -> Self {
+        self.min_runs = Some(count);
+        self.max_runs = Some(count);
+        self
+    }
+
+    /// Perform at least the given number of timing runs
+    pub fn min_runs(mut self, count: u64) -> Self {
+        self.min_runs = Some(count);
+        self
+    }
+
+    /// Perform at most the given number of timing runs. Like with `--max-runs`, the default
+    /// minimum is lowered to this number if no minimum is given.
+    pub fn max_runs(mut self, count: u64) -> Self {
+        self.max_runs = Some(count);
+        self
+    }
+
+    /// Command that is run once before the timing runs of every command
+    pub fn setup(mut self, command: impl Into<String>) -> Self {
+        self.options.setup_command = Some(PerCommand::all(command.into()));
+        self
+    }
+
+    /// Command that is run before every timing run
+    pub fn prepare(mut self, command: impl Into<String>) -> Self {
+        self.options.preparation_command = Some(PerCommand::all(command.into()));
+        self
+    }
+
+    /// Command that is run after every timing run
+    pub fn conclude(mut self, command: impl Into<String>) -> Self {
+        self.options.conclusion_command = Some(PerCommand::all(command.into()));
+        self
+    }
+
+    /// Command that is run once after the timing runs of every command
+    pub fn cleanup(mut self, command: impl Into<String>) -> Self {
+        self.options.cleanup_command = Some(PerCommand::all(command.into()));
+        self
+    }
+
+    /// Run the commands with the given shell instead of the default one
+    pub fn shell(mut self, shell: Shell) -> Self {
+        self.options.executor_kind = PerCommand::all(ExecutorKind::Shell(shell));
+        self
+    }
+
+    /// Run the commands directly instead of in a shell, see `--shell=none`
+    pub fn without_shell(mut self) -> Self {
+        self.options.executor_kind = PerCommand::all(ExecutorKind::Raw);
+        self
+    }
+
+    /// Exit codes that are treated as success (only zero by default)
+    pub fn accept_exit_codes(mut self, exit_codes: ExitCodes) -> Self {
+        self.options.accepted_exit_codes = PerCommand::all(exit_codes);
+        self
+    }
+
+    /// Ignore failing commands instead of aborting the benchmark
+    pub fn ignore_failure(mut self) -> Self {
+        self.options.command_failure_action = CmdFailureAction::Ignore;
+        self
+    }
+
+    /// Show the output of the benchmarked commands on the terminal
+    pub fn show_output(mut self) -> Self {
+        self.options.command_output_policy = CommandOutputPolicy::Inherit;
+        self
+    }
+
+    /// Print the progress and results to the terminal, like the command-line tool
+    pub fn output_style(mut self, output_style: OutputStyleOption) -> Self {
+        self.options.output_style = output_style;
+        self
+    }
+
+    /// Export the results to the given file after every benchmark
+    pub fn export(mut self, export_type: ExportType, filename: impl Into<String>) -> Self {
+        self.exports.push((export_type, filename.into()));
+        self
+    }
+
+    /// Report the progress of the benchmarks to the given observer, in addition to the
+    /// terminal output
+    pub fn observer(mut self, observer: impl Observer + 'static) -> Self {
+        self.observers.push(Box::new(observer));
+        self
+    }
+
+    /// Run the benchmarks and return their results, in the order of the commands (and
+    /// parameter combinations)
+    pub fn run(mut self) -> Result<Vec<BenchmarkResult>> {
+        self.options.run_bounds = RunBounds::from_limits(self.min_runs, self.max_runs)?;
+
+        let parameters = self
+            .parameters
+            .iter()
+            .map(|(name, values)| {
+                let values = values.iter().cloned().map(ParameterValue::Text).collect();
+                (name.as_str(), values)
+            })
+            .collect();
+        let (combinations, _) = combinations(parameters, &[], &[])?;
+
+        let mut commands = vec![];
+        for parameters in combinations {
+            for (name, command) in &self.commands {
+                commands.push(Command::new_parametrized(
+                    name.as_deref(),
+                    command,
+                    parameters.clone(),
+                ));
+            }
+        }
+
+        let mut export_manager = ExportManager::default();
+        for (export_type, filename) in &self.exports {
+            export_manager.add_exporter(export_type.clone(), filename)?;
+        }
+
+        let mut observers: Vec<Box<dyn Observer + '_>> =
+            vec![Box::new(TerminalObserver::new(&self.options))];
+        observers.extend(self.observers);
+
+        crate::run_benchmarks(
+            &Commands::from(commands),
+            &self.options,
+            &export_manager,
+            &observers,
+        )
+    }
+}
+
+#[test]
+fn test_runs_all_commands_and_parameter_combinations() {
+    let mut builder = BenchmarkBuilder::new()
+        .named_command("a-{n}", "sleep 0.{n}")
+        .command("sleep 0.0{n}")
+        .parameter_list("n", ["1", "2"])
+        .runs(3);
+    builder.options.executor_kind = PerCommand::all(ExecutorKind::Mock(None));
+
+    let results = builder.run().unwrap();
+    let commands: Vec<_> = results.iter().map(|r| r.command.as_str()).collect();
+    assert_eq!(commands, ["a-1", "sleep 0.01", "a-2", "sleep 0.02"]);
+    assert!(results.iter().all(|r| r.times.as_ref().unwrap().len() == 3));
+    approx::assert_relative_eq!(results[2].mean, 0.2);
+}
+
+#[test]
+fn test_reports_progress_to_observers() {
+    use super::observer::Event;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    struct Recorder(Rc<RefCell<Vec<String>>>);
+
+    impl Observer for Recorder {
+        fn notify(&self, event: &Event) -> anyhow::Result<()> {
+            let event = match event {
+                Event::BenchmarkStarted { number, .. } => format!("started {}", number),
+                Event::PhaseStarted { phase, runs, .. } => format!("{:?} {}", phase, runs),
+                Event::WarmupRunFinished { number } => format!("warmup {}", number),
+                Event::SampleRecorded {
+                    number, iteration, ..
+                } => format!("sample {} {}", number, iteration),
+                Event::ProgressUpdated { .. } => return Ok(()),
+                Event::PhaseFinished { phase, .. } => format!("{:?} finished", phase),
+                Event::WarningRaised { .. } => "warning".into(),
+                Event::BenchmarkFinished { number, .. } => format!("finished {}", number),
+                Event::Notice { .. } => "notice".into(),
+                Event::ComparisonComputed { .. } => "comparison".into(),
+            };
+            self.0.borrow_mut().push(event);
+            Ok(())
+        }
+    }
+
+    let events = Rc::new(RefCell::new(vec![]));
+    let mut builder = BenchmarkBuilder::new()
+        .command("sleep 0.1")
+        .warmup(1)
+        .runs(2)
+        .observer(Recorder(events.clone()));
+    builder.options.executor_kind = PerCommand::all(ExecutorKind::Mock(None));
+    builder.run().unwrap();
+
+    assert_eq!(
+        *events.borrow(),
+        [
+            "started 0",
+            "Warmup 1",
+            "warmup 0",
+            "Warmup finished",
+            "Timing 2",
+            "sample 0 0",
+            "sample 0 1",
+            "Timing finished",
+            "finished 0"
+        ]
+    );
+}
+
+#[test]
+fn test_normalizes_run_bounds_like_the_command_line() {
+    let run = |builder: BenchmarkBuilder| {
+        let mut builder = builder.command("sleep 0.1");
+        builder.options.executor_kind = PerCommand::all(ExecutorKind::Mock(None));
+        builder.run()
+    };
+
+    // Without an explicit minimum, the default minimum is lowered to the maximum
+    let results = run(BenchmarkBuilder::new().max_runs(2)).unwrap();
+    assert_eq!(results[0].times.as_ref().unwrap().len(), 2);
+
+    assert!(run(BenchmarkBuilder::new().min_runs(5).max_runs(3)).is_err());
+}
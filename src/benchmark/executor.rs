@@ -1,9 +1,14 @@
-use std::process::{ExitStatus, Stdio};
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::process::{self, ExitStatus, Stdio};
 
 use crate::command::Command;
-use crate::options::{CmdFailureAction, CommandOutputPolicy, Options, OutputStyleOption, Shell};
+use crate::options::{
+    CommandOutputPolicy, Environment, ExitCodes, Options, OutputStyleOption, Shell,
+};
 use crate::output::progress_bar::get_progress_bar;
-use crate::timer::{execute_and_measure, TimerResult};
+use crate::timer::{execute_and_measure, Cgroup, OutputHasher, ResourceCounters, TimerResult};
+use crate::util::exit_code::extract_exit_code;
 use crate::util::randomized_environment_offset;
 use crate::util::units::Second;
 
@@ -12,12 +17,31 @@ use super::timing_result::TimingResult;
 use anyhow::{bail, Context, Result};
 use statistical::mean;
 
+/// Settings of the processes in which the commands of a benchmark are run
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExecutionContext<'a> {
+    /// Directory in which the commands are run, if not the current one
+    pub working_directory: Option<&'a Path>,
+
+    /// Changes of the environment of the commands
+    pub environment: &'a Environment,
+}
+
 pub trait Executor {
-    /// Run the given command and measure the execution time
+    /// Run the given command `batch_size` times back-to-back and measure the execution time.
+    /// The batch stops at the first failing invocation. The returned result contains the
+    /// average time of a single finished invocation and the hash of the output of the whole
+    /// batch. If a timeout is given, the batch is killed after that time and the result is
+    /// marked as timed out. If an input file is given, it is connected to the standard input of
+    /// every invocation. If accepted exit codes are given, an error is returned for any other
+    /// exit code.
     fn run_command_and_measure(
         &self,
         command: &Command<'_>,
-        command_failure_action: Option<CmdFailureAction>,
+        input: Option<&Path>,
+        accepted_exit_codes: Option<&ExitCodes>,
+        timeout: Option<Second>,
+        batch_size: u64,
     ) -> Result<(TimingResult, ExitStatus)>;
 
     /// Perform a calibration of this executor. For example,
@@ -33,44 +57,116 @@ pub trait Executor {
     fn time_overhead(&self) -> Second;
 }
 
+/// Open the standard input for a run of a benchmarked command
+fn open_input(input: Option<&Path>) -> Result<Stdio> {
+    Ok(match input {
+        Some(path) => File::open(path)
+            .with_context(|| format!("Failed to open the input file '{}'", path.to_string_lossy()))?
+            .into(),
+        None => Stdio::null(),
+    })
+}
+
+/// Create a hasher for the output of a batch, if the output is captured
+fn output_hasher(command_output_policy: &CommandOutputPolicy) -> Option<OutputHasher> {
+    match command_output_policy {
+        CommandOutputPolicy::Capture { .. } => Some(OutputHasher::new()),
+        _ => None,
+    }
+}
+
+/// Run the given command and measure its execution time. The standard input of the command
+/// has to be set up by the caller.
+#[allow(clippy::too_many_arguments)]
 fn run_command_and_measure_common(
     mut command: std::process::Command,
-    command_failure_action: CmdFailureAction,
+    accepted_exit_codes: Option<&ExitCodes>,
     command_output_policy: &CommandOutputPolicy,
+    output_hasher: Option<&mut OutputHasher>,
     command_name: &str,
+    cgroup: Option<&Cgroup>,
+    timeout: Option<Second>,
+    context: ExecutionContext<'_>,
 ) -> Result<TimerResult> {
     let (stdout, stderr) = command_output_policy.get_stdout_stderr()?;
-    command.stdin(Stdio::null()).stdout(stdout).stderr(stderr);
+    command.stdout(stdout).stderr(stderr);
+
+    if let Some(working_directory) = context.working_directory {
+        command.current_dir(working_directory);
+    }
+
+    context.environment.apply(&mut command);
 
     command.env(
         "HYPERFINE_RANDOMIZED_ENVIRONMENT_OFFSET",
         randomized_environment_offset::value(),
     );
 
-    let result = execute_and_measure(command)
+    let result = execute_and_measure(command, cgroup, timeout, output_hasher)
         .with_context(|| format!("Failed to run command '{}'", command_name))?;
 
-    if command_failure_action == CmdFailureAction::RaiseError && !result.status.success() {
-        bail!(
-            "{}. Use the '-i'/'--ignore-failure' option if you want to ignore this. \
-            Alternatively, use the '--show-output' option to debug what went wrong.",
-            result.status.code().map_or(
-                "The process has been terminated by a signal".into(),
-                |c| format!("Command terminated with non-zero exit code: {}", c)
-            )
-        );
+    if let Some(accepted_exit_codes) = accepted_exit_codes {
+        let exit_code = extract_exit_code(result.status);
+        if !result.timed_out && !accepted_exit_codes.contains(exit_code) {
+            bail!(
+                "{}. Use the '-i'/'--ignore-failure' option if you want to ignore this. \
+                Alternatively, use the '--show-output' option to debug what went wrong.",
+                match result.status.code() {
+                    None if accepted_exit_codes.is_default() =>
+                        "The process has been terminated by a signal".into(),
+                    Some(c) if accepted_exit_codes.is_default() =>
+                        format!("Command terminated with non-zero exit code: {}", c),
+                    _ => format!(
+                        "Command terminated with exit code {}, which is not one of the \
+                         accepted exit codes ({})",
+                        exit_code.unwrap_or_default(),
+                        accepted_exit_codes
+                    ),
+                }
+            );
+        }
     }
 
     Ok(result)
 }
 
+/// Compute the (rounded down) mean of the given resource counters. Returns `None` if the
+/// counters are not available for all runs.
+fn mean_counters(counters: &[Option<ResourceCounters>]) -> Option<ResourceCounters> {
+    let counters: Vec<ResourceCounters> = counters.iter().copied().collect::<Option<_>>()?;
+    let n = counters.len() as u64;
+    if n == 0 {
+        return None;
+    }
+
+    let average = |f: fn(&ResourceCounters) -> u64| counters.iter().map(f).sum::<u64>() / n;
+    Some(ResourceCounters {
+        minor_page_faults: average(|c| c.minor_page_faults),
+        major_page_faults: average(|c| c.major_page_faults),
+        voluntary_context_switches: average(|c| c.voluntary_context_switches),
+        involuntary_context_switches: average(|c| c.involuntary_context_switches),
+        block_input_operations: average(|c| c.block_input_operations),
+        block_output_operations: average(|c| c.block_output_operations),
+    })
+}
+
 pub struct RawExecutor<'a> {
     options: &'a Options,
+    cgroup: Option<&'a Cgroup>,
+    context: ExecutionContext<'a>,
 }
 
 impl<'a> RawExecutor<'a> {
-    pub fn new(options: &'a Options) -> Self {
-        RawExecutor { options }
+    pub fn new(
+        options: &'a Options,
+        cgroup: Option<&'a Cgroup>,
+        context: ExecutionContext<'a>,
+    ) -> Self {
+        RawExecutor {
+            options,
+            cgroup,
+            context,
+        }
     }
 }
 
@@ -78,23 +174,60 @@ impl<'a> Executor for RawExecutor<'a> {
     fn run_command_and_measure(
         &self,
         command: &Command<'_>,
-        command_failure_action: Option<CmdFailureAction>,
+        input: Option<&Path>,
+        accepted_exit_codes: Option<&ExitCodes>,
+        timeout: Option<Second>,
+        batch_size: u64,
     ) -> Result<(TimingResult, ExitStatus)> {
-        let result = run_command_and_measure_common(
-            command.get_command()?,
-            command_failure_action.unwrap_or(self.options.command_failure_action),
-            &self.options.command_output_policy,
-            &command.get_command_line(),
-        )?;
+        let mut batch = TimingResult::default();
+        let mut output_hasher = output_hasher(&self.options.command_output_policy);
+        let mut status = None;
+        let mut invocations = 0;
+
+        while invocations < batch_size {
+            // The timeout applies to the batch as a whole
+            let remaining_time = timeout.map(|t| (t - batch.time_real).max(1e-3));
+
+            let mut command_builder = command.get_command()?;
+            command_builder.stdin(open_input(input)?);
+
+            let result = run_command_and_measure_common(
+                command_builder,
+                accepted_exit_codes,
+                &self.options.command_output_policy,
+                output_hasher.as_mut(),
+                &command.get_command_line(),
+                self.cgroup,
+                remaining_time,
+                self.context,
+            )?;
+
+            batch.time_real += result.time_real;
+            batch.time_user += result.time_user;
+            batch.time_system += result.time_system;
+            batch.max_rss = batch.max_rss.max(result.max_rss);
+            batch.counters = match (status, batch.counters, result.counters) {
+                (None, _, counters) => counters,
+                (Some(_), Some(total), Some(counters)) => Some(total + counters),
+                _ => None,
+            };
+            batch.timed_out = result.timed_out;
+            status = Some(result.status);
+            invocations += 1;
+
+            // Like a batch run by a shell, the batch stops at the first failure
+            if result.timed_out || !result.status.success() {
+                break;
+            }
+        }
+        batch.output_hash = output_hasher.map(|hasher| hasher.finish());
 
-        Ok((
-            TimingResult {
-                time_real: result.time_real,
-                time_user: result.time_user,
-                time_system: result.time_system,
-            },
-            result.status,
-        ))
+        let invocations = if batch.timed_out {
+            batch_size
+        } else {
+            invocations
+        };
+        Ok((batch.per_invocation(invocations), status.unwrap()))
     }
 
     fn calibrate(&mut self) -> Result<()> {
@@ -106,17 +239,83 @@ impl<'a> Executor for RawExecutor<'a> {
     }
 }
 
+/// Temporary file to which a shell reports the number of finished invocations of a batch
+struct BatchCountFile {
+    path: PathBuf,
+}
+
+impl BatchCountFile {
+    fn create() -> Result<Self> {
+        let path = std::env::temp_dir().join(format!("hyperfine-{}-batch-count", process::id()));
+        File::create(&path).with_context(|| {
+            format!(
+                "Failed to create the temporary file '{}'",
+                path.to_string_lossy()
+            )
+        })?;
+        Ok(BatchCountFile { path })
+    }
+
+    /// Shell script that runs the given command line `batch_size` times, stops at the first
+    /// failing invocation and then writes the number of finished invocations to this file. If
+    /// the shell is left from within the command, the number is written by an exit trap and
+    /// marked as incomplete.
+    fn script(&self, command_line: &str, batch_size: u64) -> String {
+        format!(
+            "hyperfine_i=0\n\
+             hyperfine_status=0\n\
+             trap 'echo $hyperfine_i incomplete > {path}' EXIT\n\
+             while [ $hyperfine_i -lt {batch_size} ]; do\n\
+             {command_line}\n\
+             hyperfine_status=$?\n\
+             hyperfine_i=$((hyperfine_i + 1))\n\
+             if [ $hyperfine_status -ne 0 ]; then break; fi\n\
+             done\n\
+             trap - EXIT\n\
+             echo $hyperfine_i > {path}\n\
+             exit $hyperfine_status",
+            batch_size = batch_size,
+            command_line = command_line,
+            path = shell_words::quote(&self.path.to_string_lossy()),
+        )
+    }
+
+    /// Number of finished invocations, if reported by the shell, and whether the shell has
+    /// reached the end of the script
+    fn read(&self) -> Option<(u64, bool)> {
+        let content = fs::read_to_string(&self.path).ok()?;
+        let mut words = content.split_whitespace();
+        let invocations = words.next()?.parse().ok()?;
+        Some((invocations, words.next().is_none()))
+    }
+}
+
+impl Drop for BatchCountFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
 pub struct ShellExecutor<'a> {
     options: &'a Options,
     shell: &'a Shell,
+    cgroup: Option<&'a Cgroup>,
+    context: ExecutionContext<'a>,
     shell_spawning_time: Option<TimingResult>,
 }
 
 impl<'a> ShellExecutor<'a> {
-    pub fn new(shell: &'a Shell, options: &'a Options) -> Self {
+    pub fn new(
+        shell: &'a Shell,
+        options: &'a Options,
+        cgroup: Option<&'a Cgroup>,
+        context: ExecutionContext<'a>,
+    ) -> Self {
         ShellExecutor {
             shell,
             options,
+            cgroup,
+            context,
             shell_spawning_time: None,
         }
     }
@@ -126,41 +325,84 @@ impl<'a> Executor for ShellExecutor<'a> {
     fn run_command_and_measure(
         &self,
         command: &Command<'_>,
-        command_failure_action: Option<CmdFailureAction>,
+        input: Option<&Path>,
+        accepted_exit_codes: Option<&ExitCodes>,
+        timeout: Option<Second>,
+        batch_size: u64,
     ) -> Result<(TimingResult, ExitStatus)> {
+        let is_cmd_exe = cfg!(windows) && *self.shell == Shell::Default("cmd.exe");
+
+        // All invocations of a batch are run by a single shell, such that the shell spawning
+        // time is only spent once per batch. A POSIX shell runs them in a loop that reports the
+        // number of finished invocations, which detects commands that leave the shell early
+        // (e.g. through `exit` or `exec`). cmd.exe has no such loop, so the invocations are
+        // simply chained and only the status of the last one is seen.
+        let command_line = command.get_command_line();
+        let batch_count_file = if batch_size > 1 && !is_cmd_exe {
+            Some(BatchCountFile::create()?)
+        } else {
+            None
+        };
+        let batch_command_line = match &batch_count_file {
+            Some(file) => file.script(&command_line, batch_size),
+            None => vec![command_line.as_str(); batch_size as usize].join(" & "),
+        };
+        let mut output_hasher = output_hasher(&self.options.command_output_policy);
+
         let mut command_builder = self.shell.command();
         command_builder
-            .arg(
-                if cfg!(windows) && *self.shell == Shell::Default("cmd.exe") {
-                    "/C"
-                } else {
-                    "-c"
-                },
-            )
-            .arg(command.get_command_line());
+            .arg(if is_cmd_exe { "/C" } else { "-c" })
+            .arg(batch_command_line)
+            .stdin(open_input(input)?);
 
         let mut result = run_command_and_measure_common(
             command_builder,
-            command_failure_action.unwrap_or(self.options.command_failure_action),
+            accepted_exit_codes,
             &self.options.command_output_policy,
-            &command.get_command_line(),
+            output_hasher.as_mut(),
+            &command_line,
+            self.cgroup,
+            timeout,
+            self.context,
         )?;
 
+        let invocations = match &batch_count_file {
+            Some(file) if !result.timed_out => match file.read() {
+                Some((invocations, true)) => invocations,
+                incomplete => bail!(
+                    "Only {} of the {} invocations of the batch have finished, as the shell \
+                     has been left from within the command (e.g. through 'exit' or 'exec'). \
+                     Such commands can not be run in batches (see '--batch').",
+                    incomplete.map_or("some".into(), |(n, _)| n.to_string()),
+                    batch_size
+                ),
+            },
+            _ => batch_size,
+        };
+
         // Subtract shell spawning time
         if let Some(spawning_time) = self.shell_spawning_time {
             result.time_real = (result.time_real - spawning_time.time_real).max(0.0);
             result.time_user = (result.time_user - spawning_time.time_user).max(0.0);
             result.time_system = (result.time_system - spawning_time.time_system).max(0.0);
+            if let (Some(counters), Some(spawning_counters)) =
+                (result.counters, spawning_time.counters)
+            {
+                result.counters = Some(counters.saturating_sub(spawning_counters));
+            }
         }
 
-        Ok((
-            TimingResult {
-                time_real: result.time_real,
-                time_user: result.time_user,
-                time_system: result.time_system,
-            },
-            result.status,
-        ))
+        let batch = TimingResult {
+            time_real: result.time_real,
+            time_user: result.time_user,
+            time_system: result.time_system,
+            max_rss: result.max_rss,
+            counters: result.counters,
+            timed_out: result.timed_out,
+            output_hash: output_hasher.map(|hasher| hasher.finish()),
+        };
+
+        Ok((batch.per_invocation(invocations), result.status))
     }
 
     /// Measure the average shell spawning time
@@ -179,10 +421,17 @@ impl<'a> Executor for ShellExecutor<'a> {
         let mut times_real: Vec<Second> = vec![];
         let mut times_user: Vec<Second> = vec![];
         let mut times_system: Vec<Second> = vec![];
+        let mut counters: Vec<Option<ResourceCounters>> = vec![];
 
         for _ in 0..COUNT {
             // Just run the shell without any command
-            let res = self.run_command_and_measure(&Command::new(None, ""), None);
+            let res = self.run_command_and_measure(
+                &Command::new(None, ""),
+                None,
+                Some(&ExitCodes::default()),
+                None,
+                1,
+            );
 
             match res {
                 Err(_) => {
@@ -201,6 +450,7 @@ impl<'a> Executor for ShellExecutor<'a> {
                     times_real.push(r.time_real);
                     times_user.push(r.time_user);
                     times_system.push(r.time_system);
+                    counters.push(r.counters);
                 }
             }
 
@@ -217,6 +467,10 @@ impl<'a> Executor for ShellExecutor<'a> {
             time_real: mean(&times_real),
             time_user: mean(&times_user),
             time_system: mean(&times_system),
+            max_rss: None,
+            counters: mean_counters(&counters),
+            timed_out: false,
+            output_hash: None,
         });
 
         Ok(())
@@ -251,7 +505,10 @@ impl Executor for MockExecutor {
     fn run_command_and_measure(
         &self,
         command: &Command<'_>,
-        _command_failure_action: Option<CmdFailureAction>,
+        _input: Option<&Path>,
+        _accepted_exit_codes: Option<&ExitCodes>,
+        _timeout: Option<Second>,
+        _batch_size: u64,
     ) -> Result<(TimingResult, ExitStatus)> {
         #[cfg(unix)]
         let status = {
@@ -270,6 +527,10 @@ impl Executor for MockExecutor {
                 time_real: Self::extract_time(command.get_command_line()),
                 time_user: 0.0,
                 time_system: 0.0,
+                max_rss: None,
+                counters: None,
+                timed_out: false,
+                output_hash: None,
             },
             status,
         ))
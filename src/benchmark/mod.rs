@@ -1,38 +1,141 @@
 pub mod benchmark_result;
+pub mod builder;
 pub mod executor;
+pub mod observer;
 pub mod relative_speed;
 pub mod scheduler;
 pub mod timing_result;
 
+use std::cell::Cell;
 use std::cmp;
+use std::path::PathBuf;
+use std::time::Instant;
 
+use crate::bootstrap::{confidence_interval, Statistic};
 use crate::command::Command;
-use crate::options::{CmdFailureAction, ExecutorKind, Options, OutputStyleOption};
+use crate::options::{
+    BatchSize, CmdFailureAction, ExecutorKind, ExitCodes, Options, OutputMismatchAction,
+    TimeoutAction,
+};
 use crate::outlier_detection::{modified_zscores, OUTLIER_THRESHOLD};
-use crate::output::format::{format_duration, format_duration_unit};
-use crate::output::progress_bar::get_progress_bar;
+use crate::output::format::format_duration;
 use crate::output::warnings::{OutlierWarningOptions, Warnings};
-use crate::parameter::ParameterNameAndValue;
+use crate::significance::student_t_critical_value;
+use crate::timer::ResourceCounters;
 use crate::util::exit_code::extract_exit_code;
 use crate::util::min_max::{max, min};
-use crate::util::units::Second;
-use benchmark_result::BenchmarkResult;
+use crate::util::units::{Byte, Scalar, Second};
+use benchmark_result::{BenchmarkResult, ResourceUsage};
 use timing_result::TimingResult;
 
-use anyhow::{anyhow, Result};
-use colored::*;
+use anyhow::{anyhow, bail, Result};
 use statistical::{mean, median, standard_deviation};
 
 use self::executor::Executor;
+use self::observer::{Event, Observer, Phase, ProgressStatus};
 
 /// Threshold for warning about fast execution time
 pub const MIN_EXECUTION_TIME: Second = 5e-3;
 
+/// Minimum duration of a timing run with `--batch=auto`
+pub const BATCH_TARGET_TIME: Second = 50e-3;
+
+/// Upper limit for the number of invocations per timing run with `--batch=auto`
+pub const MAX_AUTO_BATCH_SIZE: u64 = 1000;
+
+/// Half-width of the confidence interval for the mean (based on Student's t-distribution),
+/// relative to the mean. Not available for less than two runs or a mean of zero.
+fn relative_precision(times: &[Second], confidence_level: Scalar) -> Option<Scalar> {
+    if times.len() < 2 {
+        return None;
+    }
+
+    let t_mean = mean(times);
+    if t_mean <= 0.0 {
+        return None;
+    }
+
+    let n = times.len() as Scalar;
+    let standard_error = standard_deviation(times, Some(t_mean)) / n.sqrt();
+    Some(student_t_critical_value(n - 1.0, confidence_level) * standard_error / t_mean)
+}
+
+/// Measurements of all timing runs of a single benchmark
+pub struct Measurements {
+    times_real: Vec<Second>,
+    times_user: Vec<Second>,
+    times_system: Vec<Second>,
+    memory_usage: Vec<Option<Byte>>,
+    resource_counters: Vec<Option<ResourceCounters>>,
+    exit_codes: Vec<Option<i32>>,
+    num_timeouts: u64,
+
+    /// Number of runs with an exit code that is not accepted
+    num_failed_runs: u64,
+
+    /// Hash of the output of the first run, if the output is checked
+    output_hash: Option<u64>,
+
+    /// Number of runs with a different output than the first one
+    num_output_mismatches: u64,
+
+    /// Total wall clock time of all timing runs, including the preparation and conclusion
+    /// commands
+    elapsed: Second,
+
+    /// Number of runs to perform if no precision target is given
+    target_count: u64,
+}
+
+impl Default for Measurements {
+    fn default() -> Self {
+        Measurements {
+            times_real: vec![],
+            times_user: vec![],
+            times_system: vec![],
+            memory_usage: vec![],
+            resource_counters: vec![],
+            exit_codes: vec![],
+            num_timeouts: 0,
+            num_failed_runs: 0,
+            output_hash: None,
+            num_output_mismatches: 0,
+            elapsed: 0.0,
+            target_count: 0,
+        }
+    }
+}
+
+impl Measurements {
+    /// Number of timing runs so far, including the ones that exceeded the timeout
+    pub fn num_runs(&self) -> u64 {
+        self.times_real.len() as u64 + self.num_timeouts
+    }
+}
+
+/// Whether a benchmark needs more timing runs
+pub struct Progress {
+    pub finished: bool,
+
+    /// Estimated total number of runs, if available
+    pub estimated_total: Option<u64>,
+
+    /// Status for the progress bar
+    pub status: ProgressStatus,
+}
+
 pub struct Benchmark<'a> {
     number: usize,
     command: &'a Command<'a>,
     options: &'a Options,
     executor: &'a dyn Executor,
+    observer: &'a dyn Observer,
+
+    /// File that is connected to the standard input of the benchmarked command, see `--input`
+    input: Option<PathBuf>,
+
+    /// Number of invocations of the command per timing run, see `--batch`
+    batch_size: Cell<u64>,
 }
 
 impl<'a> Benchmark<'a> {
@@ -41,12 +144,42 @@ impl<'a> Benchmark<'a> {
         command: &'a Command<'a>,
         options: &'a Options,
         executor: &'a dyn Executor,
+        observer: &'a dyn Observer,
     ) -> Self {
+        // Relative paths of input files refer to the working directory of the command
+        let input = options
+            .input
+            .as_ref()
+            .map(|input| input.get(number))
+            .filter(|input| input.as_str() != "null")
+            .map(|input| {
+                let parameters = command.get_parameters().iter().cloned();
+                let path = PathBuf::from(
+                    Command::new_parametrized(None, input, parameters).get_command_line(),
+                );
+                match &options.working_directory {
+                    Some(directories) => directories.get(number).join(path),
+                    None => path,
+                }
+            });
+
         Benchmark {
             number,
             command,
             options,
             executor,
+            observer,
+            input,
+            batch_size: Cell::new(1),
+        }
+    }
+
+    /// Exit codes of the benchmarked command that are accepted, or `None` if failures are
+    /// ignored
+    fn accepted_exit_codes(&self) -> Option<&ExitCodes> {
+        match self.options.command_failure_action {
+            CmdFailureAction::RaiseError => Some(self.options.accepted_exit_codes.get(self.number)),
+            CmdFailureAction::Ignore => None,
         }
     }
 
@@ -55,49 +188,89 @@ impl<'a> Benchmark<'a> {
         &self,
         command: &Command<'_>,
         error_output: &'static str,
+        kind: &'static str,
+        timeout: Option<Second>,
     ) -> Result<TimingResult> {
-        self.executor
-            .run_command_and_measure(command, Some(CmdFailureAction::RaiseError))
+        let result = self
+            .executor
+            .run_command_and_measure(command, None, Some(&ExitCodes::default()), timeout, 1)
             .map(|r| r.0)
-            .map_err(|_| anyhow!(error_output))
+            .map_err(|_| anyhow!(error_output))?;
+
+        if let (true, Some(timeout)) = (result.timed_out, timeout) {
+            bail!(
+                "The {} command has been killed because it exceeded the timeout of {}.",
+                kind,
+                format_duration(timeout, None)
+            );
+        }
+
+        Ok(result)
+    }
+
+    /// Check whether a run of the benchmarked command exceeded the timeout. Returns an error if
+    /// such runs should abort the benchmark, and otherwise whether the run has to be excluded.
+    fn check_timeout(&self, result: &TimingResult) -> Result<bool> {
+        match (result.timed_out, self.options.timeouts.benchmark) {
+            (true, Some(timeout)) => match self.options.timeout_action {
+                TimeoutAction::Abort => bail!(
+                    "Command '{}' has been killed because it exceeded the timeout of {}. Use \
+                     '--on-timeout=exclude' to exclude timed out runs from the statistics instead.",
+                    self.command.get_name(),
+                    format_duration(timeout, None)
+                ),
+                TimeoutAction::Exclude => Ok(true),
+            },
+            _ => Ok(false),
+        }
     }
 
     /// Run the command specified by `--setup`.
-    fn run_setup_command(
-        &self,
-        parameters: impl IntoIterator<Item = ParameterNameAndValue<'a>>,
-    ) -> Result<TimingResult> {
-        let command = self
-            .options
-            .setup_command
-            .as_ref()
-            .map(|setup_command| Command::new_parametrized(None, setup_command, parameters));
+    pub fn run_setup_command(&self) -> Result<TimingResult> {
+        let parameters = self.command.get_parameters().iter().cloned();
+        let command = self.options.setup_command.as_ref().map(|setup_command| {
+            Command::new_parametrized(None, setup_command.get(self.number), parameters)
+        });
 
         let error_output = "The setup command terminated with a non-zero exit code. \
                             Append ' || true' to the command if you are sure that this can be ignored.";
 
         Ok(command
-            .map(|cmd| self.run_intermediate_command(&cmd, error_output))
+            .map(|cmd| {
+                self.run_intermediate_command(
+                    &cmd,
+                    error_output,
+                    "setup",
+                    self.options.timeouts.setup,
+                )
+            })
             .transpose()?
             .unwrap_or_default())
     }
 
     /// Run the command specified by `--cleanup`.
-    fn run_cleanup_command(
-        &self,
-        parameters: impl IntoIterator<Item = ParameterNameAndValue<'a>>,
-    ) -> Result<TimingResult> {
+    pub fn run_cleanup_command(&self) -> Result<TimingResult> {
+        let parameters = self.command.get_parameters().iter().cloned();
         let command = self
             .options
             .cleanup_command
             .as_ref()
-            .map(|cleanup_command| Command::new_parametrized(None, cleanup_command, parameters));
+            .map(|cleanup_command| {
+                Command::new_parametrized(None, cleanup_command.get(self.number), parameters)
+            });
 
         let error_output = "The cleanup command terminated with a non-zero exit code. \
                             Append ' || true' to the command if you are sure that this can be ignored.";
 
         Ok(command
-            .map(|cmd| self.run_intermediate_command(&cmd, error_output))
+            .map(|cmd| {
+                self.run_intermediate_command(
+                    &cmd,
+                    error_output,
+                    "cleanup",
+                    self.options.timeouts.cleanup,
+                )
+            })
             .transpose()?
             .unwrap_or_default())
     }
@@ -107,159 +280,382 @@ impl<'a> Benchmark<'a> {
         let error_output = "The preparation command terminated with a non-zero exit code. \
                             Append ' || true' to the command if you are sure that this can be ignored.";
 
-        self.run_intermediate_command(command, error_output)
+        self.run_intermediate_command(
+            command,
+            error_output,
+            "preparation",
+            self.options.timeouts.prepare,
+        )
     }
 
-    /// Run the benchmark for a single command
-    pub fn run(&self) -> Result<BenchmarkResult> {
-        let command_name = self.command.get_name();
-        if self.options.output_style != OutputStyleOption::Disabled {
-            println!(
-                "{}{}: {}",
-                "Benchmark ".bold(),
-                (self.number + 1).to_string().bold(),
-                command_name,
-            );
-        }
-
-        let mut times_real: Vec<Second> = vec![];
-        let mut times_user: Vec<Second> = vec![];
-        let mut times_system: Vec<Second> = vec![];
-        let mut exit_codes: Vec<Option<i32>> = vec![];
-        let mut all_succeeded = true;
+    /// Run the preparation command for the next timing run, if any
+    fn run_preparation(&self) -> Result<Option<TimingResult>> {
+        self.options
+            .preparation_command
+            .as_ref()
+            .map(|preparation_command| {
+                let command = Command::new_parametrized(
+                    None,
+                    preparation_command.get(self.number),
+                    self.command.get_parameters().iter().cloned(),
+                );
+                self.run_preparation_command(&command)
+            })
+            .transpose()
+    }
 
-        let preparation_command = self.options.preparation_command.as_ref().map(|values| {
-            let preparation_command = if values.len() == 1 {
-                &values[0]
-            } else {
-                &values[self.number]
-            };
-            Command::new_parametrized(
-                None,
-                preparation_command,
-                self.command.get_parameters().iter().cloned(),
-            )
-        });
-        let run_preparation_command = || {
-            preparation_command
-                .as_ref()
-                .map(|cmd| self.run_preparation_command(cmd))
-                .transpose()
-        };
+    /// Run the command specified by `--conclude`.
+    fn run_conclusion_command(&self, command: &Command<'_>) -> Result<TimingResult> {
+        let error_output = "The conclusion command terminated with a non-zero exit code. \
+                            Append ' || true' to the command if you are sure that this can be ignored.";
 
-        self.run_setup_command(self.command.get_parameters().iter().cloned())?;
+        self.run_intermediate_command(
+            command,
+            error_output,
+            "conclusion",
+            self.options.timeouts.conclude,
+        )
+    }
 
-        // Warmup phase
-        if self.options.warmup_count > 0 {
-            let progress_bar = if self.options.output_style != OutputStyleOption::Disabled {
-                Some(get_progress_bar(
-                    self.options.warmup_count,
-                    "Performing warmup runs",
-                    self.options.output_style,
-                ))
-            } else {
-                None
-            };
+    /// Run the conclusion command after a timing run, if any
+    fn run_conclusion(&self) -> Result<Option<TimingResult>> {
+        self.options
+            .conclusion_command
+            .as_ref()
+            .map(|conclusion_command| {
+                let command = Command::new_parametrized(
+                    None,
+                    conclusion_command.get(self.number),
+                    self.command.get_parameters().iter().cloned(),
+                );
+                self.run_conclusion_command(&command)
+            })
+            .transpose()
+    }
 
-            for _ in 0..self.options.warmup_count {
-                let _ = run_preparation_command()?;
-                let _ = self.executor.run_command_and_measure(self.command, None)?;
-                if let Some(bar) = progress_bar.as_ref() {
-                    bar.inc(1)
+    /// Determine the number of invocations per timing run. With `--batch=auto`, the batch
+    /// size is increased by factors of ten until a batch takes long enough to extrapolate the
+    /// number of invocations that are needed to reach the target duration.
+    pub fn determine_batch_size(&self) -> Result<()> {
+        let batch_size = match self.options.batch_size {
+            None => 1,
+            Some(BatchSize::Fixed(size)) => size,
+            Some(BatchSize::Auto) => {
+                let mut batch_size = 1;
+                loop {
+                    self.run_preparation()?;
+                    let (res, _) = self.executor.run_command_and_measure(
+                        self.command,
+                        self.input.as_deref(),
+                        self.accepted_exit_codes(),
+                        self.options.timeouts.benchmark,
+                        batch_size,
+                    )?;
+                    let timed_out = self.check_timeout(&res)?;
+                    self.run_conclusion()?;
+                    if timed_out {
+                        break batch_size;
+                    }
+
+                    let batch_time = res.time_real * batch_size as Second;
+                    if batch_time >= BATCH_TARGET_TIME / 10.0 || batch_size >= MAX_AUTO_BATCH_SIZE {
+                        let size = (BATCH_TARGET_TIME / res.time_real).ceil();
+                        break if size.is_finite() {
+                            (size as u64).clamp(1, MAX_AUTO_BATCH_SIZE)
+                        } else {
+                            MAX_AUTO_BATCH_SIZE
+                        };
+                    }
+                    batch_size *= 10;
                 }
             }
-            if let Some(bar) = progress_bar.as_ref() {
-                bar.finish_and_clear()
-            }
-        }
-
-        // Set up progress bar (and spinner for initial measurement)
-        let progress_bar = if self.options.output_style != OutputStyleOption::Disabled {
-            Some(get_progress_bar(
-                self.options.run_bounds.min,
-                "Initial time measurement",
-                self.options.output_style,
-            ))
-        } else {
-            None
         };
 
-        let preparation_result = run_preparation_command()?;
-        let preparation_overhead =
-            preparation_result.map_or(0.0, |res| res.time_real + self.executor.time_overhead());
+        self.batch_size.set(batch_size);
+        Ok(())
+    }
 
-        // Initial timing run
-        let (res, status) = self.executor.run_command_and_measure(self.command, None)?;
-        let success = status.success();
+    /// Notify the observer that the benchmark has started
+    pub fn start(&self) -> Result<()> {
+        self.observer.notify(&Event::BenchmarkStarted {
+            number: self.number,
+            command: self.command,
+        })
+    }
 
-        // Determine number of benchmark runs
-        let runs_in_min_time = (self.options.min_benchmarking_time
-            / (res.time_real + self.executor.time_overhead() + preparation_overhead))
-            as u64;
+    /// Number of warmup runs for this command
+    pub fn warmup_count(&self) -> u64 {
+        *self.options.warmup_count.get(self.number)
+    }
 
-        let count = {
-            let min = cmp::max(runs_in_min_time, self.options.run_bounds.min);
+    /// Perform all warmup runs
+    fn run_warmup(&self) -> Result<()> {
+        if self.warmup_count() == 0 {
+            return Ok(());
+        }
 
-            self.options
-                .run_bounds
-                .max
-                .as_ref()
-                .map(|max| cmp::min(min, *max))
-                .unwrap_or(min)
-        };
+        self.observer.notify(&Event::PhaseStarted {
+            phase: Phase::Warmup,
+            number: Some(self.number),
+            runs: self.warmup_count(),
+        })?;
+        for _ in 0..self.warmup_count() {
+            self.warmup_run()?;
+        }
+        self.observer.notify(&Event::PhaseFinished {
+            phase: Phase::Warmup,
+            number: Some(self.number),
+        })?;
 
-        let count_remaining = count - 1;
+        Ok(())
+    }
+
+    /// Perform a single warmup run (including the preparation and conclusion commands)
+    pub fn warmup_run(&self) -> Result<()> {
+        self.run_preparation()?;
+        let (res, _) = self.executor.run_command_and_measure(
+            self.command,
+            self.input.as_deref(),
+            self.accepted_exit_codes(),
+            self.options.timeouts.benchmark,
+            self.batch_size.get(),
+        )?;
+        self.check_timeout(&res)?;
+        self.run_conclusion()?;
+
+        self.observer.notify(&Event::WarmupRunFinished {
+            number: self.number,
+        })?;
+        Ok(())
+    }
 
-        // Save the first result
-        times_real.push(res.time_real);
-        times_user.push(res.time_user);
-        times_system.push(res.time_system);
-        exit_codes.push(extract_exit_code(status));
+    /// Perform a single timing run (including the preparation and conclusion commands) and
+    /// record the result. The first run also determines the number of runs if no precision
+    /// target is given.
+    pub fn timing_run(&self, measurements: &mut Measurements) -> Result<()> {
+        let start = Instant::now();
+
+        let preparation_result = self.run_preparation()?;
+        let (res, status) = self.executor.run_command_and_measure(
+            self.command,
+            self.input.as_deref(),
+            self.accepted_exit_codes(),
+            self.options.timeouts.benchmark,
+            self.batch_size.get(),
+        )?;
+        let timed_out = self.check_timeout(&res)?;
+        let conclusion_result = self.run_conclusion()?;
+
+        measurements.elapsed += start.elapsed().as_secs_f64();
+
+        let exit_code = extract_exit_code(status);
+        self.observer.notify(&Event::SampleRecorded {
+            number: self.number,
+            iteration: measurements.num_runs(),
+            result: &res,
+            exit_code,
+        })?;
+
+        if measurements.num_runs() == 0 {
+            let overhead = |result: Option<TimingResult>| {
+                result.map_or(0.0, |res| res.time_real + self.executor.time_overhead())
+            };
+            let intermediate_overhead = overhead(preparation_result) + overhead(conclusion_result);
+
+            // Determine number of benchmark runs
+            let batch_time = res.time_real * self.batch_size.get() as Second;
+            let runs_in_min_time = (self.options.min_benchmarking_time
+                / (batch_time + self.executor.time_overhead() + intermediate_overhead))
+                as u64;
+
+            measurements.target_count = {
+                let min = cmp::max(runs_in_min_time, self.options.run_bounds.min);
+
+                self.options
+                    .run_bounds
+                    .max
+                    .as_ref()
+                    .map(|max| cmp::min(min, *max))
+                    .unwrap_or(min)
+            };
+        }
 
-        all_succeeded = all_succeeded && success;
+        if timed_out {
+            measurements.num_timeouts += 1;
+        } else {
+            measurements.times_real.push(res.time_real);
+            measurements.times_user.push(res.time_user);
+            measurements.times_system.push(res.time_system);
+            measurements.memory_usage.push(res.max_rss);
+            measurements.resource_counters.push(res.counters);
+            measurements.exit_codes.push(exit_code);
+
+            let accepted_exit_codes = self.options.accepted_exit_codes.get(self.number);
+            if !accepted_exit_codes.contains(exit_code) {
+                measurements.num_failed_runs += 1;
+            }
 
-        // Re-configure the progress bar
-        if let Some(bar) = progress_bar.as_ref() {
-            bar.set_length(count)
+            self.check_output(measurements, res.output_hash)?;
         }
-        if let Some(bar) = progress_bar.as_ref() {
-            bar.inc(1)
+
+        Ok(())
+    }
+
+    /// Compare the output of a timing run with the one of the first run, see `--check-output`
+    fn check_output(
+        &self,
+        measurements: &mut Measurements,
+        output_hash: Option<u64>,
+    ) -> Result<()> {
+        let (output_check, output_hash) = match (&self.options.output_check, output_hash) {
+            (Some(output_check), Some(output_hash)) => (output_check, output_hash),
+            _ => return Ok(()),
+        };
+
+        match measurements.output_hash {
+            None => measurements.output_hash = Some(output_hash),
+            Some(first) if first != output_hash => match output_check.mismatch_action {
+                OutputMismatchAction::Abort => bail!(
+                    "The output of command '{}' in run {} differs from the output of the first \
+                     run. Use '--on-output-mismatch=warn' to continue anyway.",
+                    self.command.get_name(),
+                    measurements.times_real.len()
+                ),
+                OutputMismatchAction::Warn => measurements.num_output_mismatches += 1,
+            },
+            Some(_) => {}
         }
 
-        // Gather statistics (perform the actual benchmark)
-        for _ in 0..count_remaining {
-            run_preparation_command()?;
+        Ok(())
+    }
+
+    /// Determine whether more timing runs are needed, and estimate the total number of runs
+    pub fn progress(&self, measurements: &Measurements) -> Progress {
+        let num_runs = measurements.num_runs();
+        let times_real = &measurements.times_real;
+
+        if let Some(target) = &self.options.precision_target {
+            let precision = relative_precision(times_real, self.options.confidence_level);
 
-            let msg = {
-                let mean = format_duration(mean(&times_real), self.options.time_unit);
-                format!("Current estimate: {}", mean.to_string().green())
+            let max_runs_reached = self
+                .options
+                .run_bounds
+                .max
+                .map_or(false, |max| num_runs >= max);
+            let min_runs_reached = num_runs >= self.options.run_bounds.min;
+            let target_reached = precision.map_or(false, |p| p <= target.relative_half_width);
+            let budget_exceeded = measurements.elapsed >= target.time_budget;
+
+            // The width of the confidence interval decreases with 1/sqrt(n), which allows us
+            // to estimate the total number of runs for the progress bar.
+            let estimated_total = precision.map(|precision| {
+                let estimate = (num_runs as Scalar
+                    * (precision / target.relative_half_width).powi(2))
+                .ceil() as u64;
+                let estimate = cmp::max(estimate, self.options.run_bounds.min);
+                let estimate = self
+                    .options
+                    .run_bounds
+                    .max
+                    .map_or(estimate, |max| cmp::min(estimate, max));
+                cmp::max(estimate, num_runs + 1)
+            });
+
+            Progress {
+                finished: max_runs_reached
+                    || (min_runs_reached && (target_reached || budget_exceeded)),
+                estimated_total,
+                status: ProgressStatus::Precision(precision),
+            }
+        } else {
+            let estimate = if times_real.is_empty() {
+                None
+            } else {
+                Some(mean(times_real))
             };
 
-            if let Some(bar) = progress_bar.as_ref() {
-                bar.set_message(msg.to_owned())
+            Progress {
+                finished: num_runs >= measurements.target_count,
+                estimated_total: Some(measurements.target_count),
+                status: ProgressStatus::MeanEstimate(estimate),
             }
+        }
+    }
 
-            let (res, status) = self.executor.run_command_and_measure(self.command, None)?;
-            let success = status.success();
+    /// Run the benchmark for a single command
+    pub fn run(&self) -> Result<BenchmarkResult> {
+        self.start()?;
 
-            times_real.push(res.time_real);
-            times_user.push(res.time_user);
-            times_system.push(res.time_system);
-            exit_codes.push(extract_exit_code(status));
+        self.run_setup_command()?;
+        self.determine_batch_size()?;
 
-            all_succeeded = all_succeeded && success;
+        // Warmup phase
+        self.run_warmup()?;
 
-            if let Some(bar) = progress_bar.as_ref() {
-                bar.inc(1)
+        // Gather statistics (perform the actual benchmark)
+        self.observer.notify(&Event::PhaseStarted {
+            phase: Phase::Timing,
+            number: Some(self.number),
+            runs: self.options.run_bounds.min,
+        })?;
+        let mut measurements = Measurements::default();
+        loop {
+            self.timing_run(&mut measurements)?;
+
+            let progress = self.progress(&measurements);
+            if progress.finished {
+                break;
             }
+
+            self.observer.notify(&Event::ProgressUpdated {
+                estimated_total: progress.estimated_total,
+                status: progress.status,
+            })?;
         }
+        self.observer.notify(&Event::PhaseFinished {
+            phase: Phase::Timing,
+            number: Some(self.number),
+        })?;
+
+        let result = self.summarize(measurements)?;
+
+        self.run_cleanup_command()?;
 
-        if let Some(bar) = progress_bar.as_ref() {
-            bar.finish_and_clear()
+        Ok(result)
+    }
+
+    /// Compute the statistics of all timing runs, create the benchmark result and raise the
+    /// warnings
+    pub fn summarize(&self, measurements: Measurements) -> Result<BenchmarkResult> {
+        let command_name = self.command.get_name();
+        let Measurements {
+            times_real,
+            times_user,
+            times_system,
+            memory_usage,
+            resource_counters,
+            exit_codes,
+            num_timeouts,
+            num_failed_runs,
+            output_hash,
+            num_output_mismatches,
+            ..
+        } = measurements;
+
+        if times_real.is_empty() {
+            bail!(
+                "All runs of command '{}' exceeded the timeout. No statistics can be computed.",
+                command_name
+            );
         }
 
+        let precision = self
+            .options
+            .precision_target
+            .as_ref()
+            .and_then(|_| relative_precision(&times_real, self.options.confidence_level));
+
         // Compute statistical quantities
-        let t_num = times_real.len();
         let t_mean = mean(&times_real);
         let t_stddev = if times_real.len() > 1 {
             Some(standard_deviation(&times_real, Some(t_mean)))
@@ -270,79 +666,81 @@ impl<'a> Benchmark<'a> {
         let t_min = min(&times_real);
         let t_max = max(&times_real);
 
+        let mut rng = rand::thread_rng();
+        let level = self.options.confidence_level;
+        let t_mean_ci = confidence_interval(&times_real, Statistic::Mean, level, &mut rng);
+        let t_median_ci = confidence_interval(&times_real, Statistic::Median, level, &mut rng);
+
         let user_mean = mean(&times_user);
         let system_mean = mean(&times_system);
 
-        // Formatting and console output
-        let (mean_str, time_unit) = format_duration_unit(t_mean, self.options.time_unit);
-        let min_str = format_duration(t_min, Some(time_unit));
-        let max_str = format_duration(t_max, Some(time_unit));
-        let num_str = format!("{} runs", t_num);
-
-        let user_str = format_duration(user_mean, Some(time_unit));
-        let system_str = format_duration(system_mean, Some(time_unit));
-
-        if self.options.output_style != OutputStyleOption::Disabled {
-            if times_real.len() == 1 {
-                println!(
-                    "  Time ({} ≡):        {:>8}  {:>8}     [User: {}, System: {}]",
-                    "abs".green().bold(),
-                    mean_str.green().bold(),
-                    "        ", // alignment
-                    user_str.blue(),
-                    system_str.blue()
-                );
-            } else {
-                let stddev_str = format_duration(t_stddev.unwrap(), Some(time_unit));
-
-                println!(
-                    "  Time ({} ± {}):     {:>8} ± {:>8}    [User: {}, System: {}]",
-                    "mean".green().bold(),
-                    "σ".green(),
-                    mean_str.green().bold(),
-                    stddev_str.green(),
-                    user_str.blue(),
-                    system_str.blue()
-                );
+        // Memory statistics are only available if they could be measured for every run
+        let memory_usage: Option<Vec<Byte>> = memory_usage.into_iter().collect();
+        let (memory_mean, memory_min, memory_max) = match memory_usage {
+            Some(ref memory) if !memory.is_empty() => (
+                Some(memory.iter().sum::<Byte>() / memory.len() as Byte),
+                memory.iter().min().copied(),
+                memory.iter().max().copied(),
+            ),
+            _ => (None, None, None),
+        };
 
-                println!(
-                    "  Range ({} … {}):   {:>8} … {:>8}    {}",
-                    "min".cyan(),
-                    "max".purple(),
-                    min_str.cyan(),
-                    max_str.purple(),
-                    num_str.dimmed()
-                );
-            }
-        }
+        let resource_usage = resource_counters
+            .into_iter()
+            .collect::<Option<Vec<_>>>()
+            .and_then(|counters| ResourceUsage::from_counters(&counters));
+
+        let batch_size = self.batch_size.get();
 
         // Warnings
         let mut warnings = vec![];
 
         // Check execution time
-        if matches!(self.options.executor_kind, ExecutorKind::Shell(_))
-            && times_real.iter().any(|&t| t < MIN_EXECUTION_TIME)
+        if matches!(
+            self.options.executor_kind.get(self.number),
+            ExecutorKind::Shell(_)
+        ) && times_real
+            .iter()
+            .any(|&t| t * (batch_size as Second) < MIN_EXECUTION_TIME)
         {
-            warnings.push(Warnings::FastExecutionTime);
+            warnings.push(Warnings::FastExecutionTime(batch_size > 1));
+        }
+
+        // Check if the requested precision has been reached
+        if let (Some(target), Some(precision)) = (&self.options.precision_target, precision) {
+            if precision > target.relative_half_width {
+                warnings.push(Warnings::PrecisionNotReached(
+                    precision,
+                    target.relative_half_width,
+                ));
+            }
+        }
+
+        // Check for runs that have been excluded because of the timeout
+        if let (true, Some(timeout)) = (num_timeouts > 0, self.options.timeouts.benchmark) {
+            warnings.push(Warnings::TimeoutsExcluded(num_timeouts, timeout));
+        }
+
+        // Check whether all runs produced the same output
+        if num_output_mismatches > 0 {
+            warnings.push(Warnings::InconsistentOutput(num_output_mismatches));
         }
 
         // Check programm exit codes
-        if !all_succeeded {
-            warnings.push(Warnings::NonZeroExitCode);
+        let accepted_exit_codes = self.options.accepted_exit_codes.get(self.number);
+        if num_failed_runs > 0 {
+            warnings.push(Warnings::UnacceptedExitCode(
+                num_failed_runs,
+                accepted_exit_codes.clone(),
+            ));
         }
 
         // Run outlier detection
         let scores = modified_zscores(&times_real);
 
         let outlier_warning_options = OutlierWarningOptions {
-            warmup_in_use: self.options.warmup_count > 0,
-            prepare_in_use: self
-                .options
-                .preparation_command
-                .as_ref()
-                .map(|v| v.len())
-                .unwrap_or(0)
-                > 0,
+            warmup_in_use: self.warmup_count() > 0,
+            prepare_in_use: self.options.preparation_command.is_some(),
         };
 
         if scores[0] > OUTLIER_THRESHOLD {
@@ -354,37 +752,50 @@ impl<'a> Benchmark<'a> {
             warnings.push(Warnings::OutliersDetected(outlier_warning_options));
         }
 
-        if !warnings.is_empty() {
-            eprintln!(" ");
-
-            for warning in &warnings {
-                eprintln!("  {}: {}", "Warning".yellow(), warning);
-            }
-        }
-
-        if self.options.output_style != OutputStyleOption::Disabled {
-            println!(" ");
+        for warning in &warnings {
+            self.observer.notify(&Event::WarningRaised {
+                number: Some(self.number),
+                warning,
+            })?;
         }
 
-        self.run_cleanup_command(self.command.get_parameters().iter().cloned())?;
-
-        Ok(BenchmarkResult {
+        let result = BenchmarkResult {
             command: command_name,
             mean: t_mean,
             stddev: t_stddev,
             median: t_median,
             user: user_mean,
             system: system_mean,
+            memory_mean,
+            memory_min,
+            memory_max,
+            resource_usage,
             min: t_min,
             max: t_max,
+            mean_ci: t_mean_ci,
+            median_ci: t_median_ci,
+            relative_speed_ci: None,
+            p_value: None,
             times: Some(times_real),
             exit_codes,
+            timeouts: num_timeouts,
+            accepted_exit_codes: Some(accepted_exit_codes.to_string())
+                .filter(|_| !accepted_exit_codes.is_default()),
+            failed_runs: num_failed_runs,
+            batch_size: self.options.batch_size.map(|_| batch_size),
+            output_hash: output_hash.filter(|_| num_output_mismatches == 0),
             parameters: self
                 .command
                 .get_parameters()
                 .iter()
                 .map(|(name, value)| (name.to_string(), value.to_string()))
                 .collect(),
-        })
+        };
+
+        self.observer.notify(&Event::BenchmarkFinished {
+            number: self.number,
+            result: &result,
+        })?;
+        Ok(result)
     }
 }
@@ -0,0 +1,117 @@
+use anyhow::Result;
+use serde::Serialize;
+
+use super::benchmark_result::BenchmarkResult;
+use crate::util::units::{Scalar, Second};
+
+// Types carried by the events
+pub use super::relative_speed::BenchmarkResultWithRelativeSpeed;
+pub use super::timing_result::TimingResult;
+pub use crate::command::Command;
+pub use crate::output::warnings::Warnings;
+
+/// Phases of a benchmark that consist of multiple runs of the benchmarked command
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    Warmup,
+    Timing,
+}
+
+/// Status of the current phase, shown next to the progress bar
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProgressStatus {
+    /// Relative half-width of the confidence interval of the mean (`--precision`), if there
+    /// are enough runs to compute it
+    Precision(Option<Scalar>),
+
+    /// Mean wall clock time of the timing runs so far, if there are any
+    MeanEstimate(Option<Second>),
+
+    /// Number of benchmarks that need more timing runs, with an interleaved execution order
+    CommandsRemaining(usize),
+}
+
+/// Progress events of the benchmarks. Benchmarks are identified by their number, in the order
+/// of the commands (starting at zero).
+pub enum Event<'a> {
+    /// A benchmark has started, before its setup command is run. With an interleaved execution
+    /// order (`--execution-order`), all benchmarks start before the first warmup run.
+    BenchmarkStarted {
+        number: usize,
+        command: &'a Command<'a>,
+    },
+
+    /// The warmup or timing runs of a benchmark have started, with an initial estimate of the
+    /// number of runs. With an interleaved execution order, the phase includes the runs of all
+    /// benchmarks and no benchmark number is given.
+    PhaseStarted {
+        phase: Phase,
+        number: Option<usize>,
+        runs: u64,
+    },
+
+    /// A warmup run (including the preparation and conclusion commands) has finished
+    WarmupRunFinished { number: usize },
+
+    /// A timing run has finished. The iteration counts the timing runs of the benchmark,
+    /// starting at zero. Timed out runs are recorded as well, see `TimingResult::timed_out`.
+    SampleRecorded {
+        number: usize,
+        iteration: u64,
+        result: &'a TimingResult,
+        exit_code: Option<i32>,
+    },
+
+    /// The estimated total number of runs of the current phase or its status has changed
+    ProgressUpdated {
+        estimated_total: Option<u64>,
+        status: ProgressStatus,
+    },
+
+    /// The warmup or timing runs have finished
+    PhaseFinished { phase: Phase, number: Option<usize> },
+
+    /// A problem with the measurements of a benchmark has been detected. Warnings are raised
+    /// before the benchmark finishes, except for the ones that compare the benchmark with
+    /// earlier ones. Problems that concern all benchmarks (e.g. with `--cgroup`) are raised
+    /// without a benchmark number, before the first benchmark starts.
+    WarningRaised {
+        number: Option<usize>,
+        warning: &'a Warnings,
+    },
+
+    /// An informational message about the benchmarks as a whole, e.g. that parameter
+    /// combinations have been skipped or which seed is used for a shuffled execution order
+    Notice { message: String },
+
+    /// The statistics of a benchmark have been computed (the cleanup command might still be
+    /// running)
+    BenchmarkFinished {
+        number: usize,
+        result: &'a BenchmarkResult,
+    },
+
+    /// The benchmarks have been compared after all of them have finished, if there are at
+    /// least two. The results are sorted by their mean time, starting with the fastest one.
+    /// `None` if the comparison can not be computed because some benchmark times are zero.
+    ComparisonComputed {
+        comparison: Option<&'a [BenchmarkResultWithRelativeSpeed<'a>]>,
+    },
+}
+
+/// Receiver of the progress events of the benchmarks. The terminal output is implemented as an
+/// observer, see `TerminalObserver`. Errors abort the benchmarks.
+pub trait Observer {
+    fn notify(&self, event: &Event) -> Result<()>;
+}
+
+/// Forward the events to multiple observers, in order
+impl Observer for Vec<Box<dyn Observer + '_>> {
+    fn notify(&self, event: &Event) -> Result<()> {
+        for observer in self {
+            observer.notify(event)?;
+        }
+        Ok(())
+    }
+}
@@ -1,6 +1,8 @@
 use std::cmp::Ordering;
 
 use super::benchmark_result::BenchmarkResult;
+use crate::bootstrap::{ratio_confidence_interval, ConfidenceInterval, Statistic};
+use crate::significance::{welch_ttest, WelchTTest};
 use crate::util::units::Scalar;
 
 #[derive(Debug)]
@@ -9,17 +11,57 @@ pub struct BenchmarkResultWithRelativeSpeed<'a> {
     pub relative_speed: Scalar,
     pub relative_speed_stddev: Option<Scalar>,
     pub is_fastest: bool,
+
+    /// Welch's t-test against the fastest command. Not available for the fastest command itself
+    /// or if the individual run times are missing.
+    pub ttest: Option<WelchTTest>,
 }
 
 pub fn compare_mean_time(l: &BenchmarkResult, r: &BenchmarkResult) -> Ordering {
     l.mean.partial_cmp(&r.mean).unwrap_or(Ordering::Equal)
 }
 
-pub fn compute(results: &[BenchmarkResult]) -> Option<Vec<BenchmarkResultWithRelativeSpeed>> {
-    let fastest: &BenchmarkResult = results
+/// Compute a bootstrap confidence interval for the relative speed (ratio of the mean run times)
+/// of `result` with respect to `fastest`. Requires the individual run times of both results.
+pub fn compute_confidence_interval(
+    result: &BenchmarkResult,
+    fastest: &BenchmarkResult,
+    level: Scalar,
+) -> Option<ConfidenceInterval> {
+    match (&result.times, &fastest.times) {
+        (Some(times), Some(fastest_times)) => ratio_confidence_interval(
+            times,
+            fastest_times,
+            Statistic::Mean,
+            level,
+            &mut rand::thread_rng(),
+        ),
+        _ => None,
+    }
+}
+
+/// Compute the p-value of Welch's t-test of `result` against `fastest`. Requires the individual
+/// run times of both results.
+pub fn compute_p_value(result: &BenchmarkResult, fastest: &BenchmarkResult) -> Option<Scalar> {
+    match (&result.times, &fastest.times) {
+        (Some(times), Some(fastest_times)) => {
+            welch_ttest(times, fastest_times).map(|ttest| ttest.p_value)
+        }
+        _ => None,
+    }
+}
+
+/// Index of the result with the smallest mean run time, if there are any results
+pub fn fastest(results: &[BenchmarkResult]) -> Option<usize> {
+    results
         .iter()
-        .min_by(|&l, &r| compare_mean_time(l, r))
-        .expect("at least one benchmark result");
+        .enumerate()
+        .min_by(|(_, l), (_, r)| compare_mean_time(l, r))
+        .map(|(i, _)| i)
+}
+
+pub fn compute(results: &[BenchmarkResult]) -> Option<Vec<BenchmarkResultWithRelativeSpeed<'_>>> {
+    let fastest = &results[fastest(results).expect("at least one benchmark result")];
 
     if fastest.mean == 0.0 {
         return None;
@@ -43,11 +85,21 @@ pub fn compute(results: &[BenchmarkResult]) -> Option<Vec<BenchmarkResultWithRel
                     _ => None,
                 };
 
+                let is_fastest = result == fastest;
+
+                let ttest = match (&result.times, &fastest.times) {
+                    (Some(times), Some(fastest_times)) if !is_fastest => {
+                        welch_ttest(times, fastest_times)
+                    }
+                    _ => None,
+                };
+
                 BenchmarkResultWithRelativeSpeed {
                     result,
                     relative_speed: ratio,
                     relative_speed_stddev: ratio_stddev,
-                    is_fastest: result == fastest,
+                    is_fastest,
+                    ttest,
                 }
             })
             .collect(),
@@ -65,10 +117,23 @@ fn create_result(name: &str, mean: Scalar) -> BenchmarkResult {
         median: mean,
         user: mean,
         system: 0.0,
+        memory_mean: None,
+        memory_min: None,
+        memory_max: None,
+        resource_usage: None,
         min: mean,
         max: mean,
+        mean_ci: None,
+        median_ci: None,
+        relative_speed_ci: None,
+        p_value: None,
         times: None,
         exit_codes: Vec::new(),
+        timeouts: 0,
+        accepted_exit_codes: None,
+        failed_runs: 0,
+        batch_size: None,
+        output_hash: None,
         parameters: BTreeMap::new(),
     }
 }
@@ -90,6 +155,25 @@ fn test_compute_relative_speed() {
     assert_relative_eq!(2.5, annotated_results[2].relative_speed);
 }
 
+#[test]
+fn test_compute_relative_speed_significance() {
+    let mut fast = create_result("fast", 1.0);
+    fast.times = Some(vec![0.9, 1.0, 1.1, 1.0]);
+    let mut slow = create_result("slow", 2.0);
+    slow.times = Some(vec![1.9, 2.0, 2.1, 2.0]);
+    let mut similar = create_result("similar", 1.05);
+    similar.times = Some(vec![0.8, 1.2, 1.0, 1.2]);
+    let no_times = create_result("no_times", 3.0);
+
+    let results = vec![fast, slow, similar, no_times];
+    let annotated_results = compute(&results).unwrap();
+
+    assert!(annotated_results[0].ttest.is_none());
+    assert!(annotated_results[1].ttest.unwrap().is_significant(0.05));
+    assert!(!annotated_results[2].ttest.unwrap().is_significant(0.05));
+    assert!(annotated_results[3].ttest.is_none());
+}
+
 #[test]
 fn test_compute_relative_speed_for_zero_times() {
     let results = vec![create_result("cmd1", 1.0), create_result("cmd2", 0.0)];
@@ -1,20 +1,28 @@
-use colored::*;
-
 use super::benchmark_result::BenchmarkResult;
-use super::executor::{Executor, MockExecutor, RawExecutor, ShellExecutor};
-use super::{relative_speed, Benchmark};
+use super::executor::{ExecutionContext, Executor, MockExecutor, RawExecutor, ShellExecutor};
+use super::observer::{Event, Observer, Phase, ProgressStatus};
+use super::{relative_speed, Benchmark, Measurements};
 
 use crate::command::Commands;
 use crate::export::ExportManager;
-use crate::options::{ExecutorKind, Options, OutputStyleOption};
+use crate::options::{Environment, ExecutionOrder, ExecutorKind, Options, OutputMismatchAction};
+use crate::output::warnings::Warnings;
+use crate::timer::Cgroup;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
 
 pub struct Scheduler<'a> {
     commands: &'a Commands<'a>,
     options: &'a Options,
     export_manager: &'a ExportManager,
+    observer: &'a dyn Observer,
     results: Vec<BenchmarkResult>,
+
+    /// Index of the fastest result, which the relative speed intervals refer to
+    fastest: Option<usize>,
 }
 
 impl<'a> Scheduler<'a> {
@@ -22,76 +30,362 @@ impl<'a> Scheduler<'a> {
         commands: &'a Commands,
         options: &'a Options,
         export_manager: &'a ExportManager,
+        observer: &'a dyn Observer,
     ) -> Self {
         Self {
             commands,
             options,
             export_manager,
+            observer,
             results: vec![],
+            fastest: None,
         }
     }
 
     pub fn run_benchmarks(&mut self) -> Result<()> {
-        let mut executor: Box<dyn Executor> = match self.options.executor_kind {
-            ExecutorKind::Raw => Box::new(RawExecutor::new(self.options)),
-            ExecutorKind::Mock(ref shell) => Box::new(MockExecutor::new(shell.clone())),
-            ExecutorKind::Shell(ref shell) => Box::new(ShellExecutor::new(shell, self.options)),
+        let cgroup = if self.options.use_cgroup {
+            self.create_cgroup()?
+        } else {
+            None
         };
 
-        executor.calibrate()?;
+        // Commands can be run with different shells, in different directories and with
+        // different environments. Create (and calibrate) one executor for every distinct
+        // combination.
+        let options = self.options;
+        let environments: Vec<Environment> = (0..self.commands.num_commands())
+            .map(|number| options.environment.for_benchmark(number))
+            .collect();
+        let mut executors: Vec<(&ExecutorKind, ExecutionContext, Box<dyn Executor>)> = vec![];
+        let mut executor_numbers = vec![];
+        for (number, environment) in environments.iter().enumerate() {
+            let kind = options.executor_kind.get(number);
+            let context = ExecutionContext {
+                working_directory: options
+                    .working_directory
+                    .as_ref()
+                    .map(|directories| directories.get(number).as_path()),
+                environment,
+            };
+
+            let position = executors
+                .iter()
+                .position(|(k, c, _)| *k == kind && *c == context);
+            let executor_number = match position {
+                Some(executor_number) => executor_number,
+                None => {
+                    let mut executor = create_executor(options, kind, context, cgroup.as_ref())?;
+                    executor.calibrate()?;
+                    executors.push((kind, context, executor));
+                    executors.len() - 1
+                }
+            };
+            executor_numbers.push(executor_number);
+        }
 
-        for (number, cmd) in self.commands.iter().enumerate() {
-            self.results
-                .push(Benchmark::new(number, cmd, self.options, &*executor).run()?);
+        let benchmarks: Vec<Benchmark> = self
+            .commands
+            .iter()
+            .zip(executor_numbers)
+            .enumerate()
+            .map(|(number, (cmd, executor_number))| {
+                let executor = &*executors[executor_number].2;
+                Benchmark::new(number, cmd, self.options, executor, self.observer)
+            })
+            .collect();
 
-            // We export (all results so far) after each individual benchmark, because
-            // we would risk losing all results if a later benchmark fails.
-            self.export_manager
-                .write_results(&self.results, self.options.time_unit)?;
+        let num_filtered = self.commands.num_filtered_combinations();
+        if num_filtered > 0 {
+            self.observer.notify(&Event::Notice {
+                message: format!(
+                    "Skipping {} parameter combination{} excluded by '--parameter-filter'.",
+                    num_filtered,
+                    if num_filtered == 1 { "" } else { "s" }
+                ),
+            })?;
+        }
+
+        match self.options.execution_order {
+            ExecutionOrder::Sequential => {
+                for benchmark in &benchmarks {
+                    let result = benchmark.run()?;
+                    self.add_result(result)?;
+                }
+            }
+            ExecutionOrder::RoundRobin => self.run_interleaved(&benchmarks, None)?,
+            ExecutionOrder::Shuffled { seed } => {
+                self.observer.notify(&Event::Notice {
+                    message: format!(
+                        "Shuffling the timing runs with seed {} (use '--seed={}' to reproduce).",
+                        seed, seed
+                    ),
+                })?;
+                self.run_interleaved(&benchmarks, Some(StdRng::seed_from_u64(seed)))?
+            }
         }
 
         Ok(())
     }
 
-    pub fn print_relative_speed_comparison(&self) {
-        if self.options.output_style == OutputStyleOption::Disabled {
-            return;
+    fn add_result(&mut self, result: BenchmarkResult) -> Result<()> {
+        self.results.push(result);
+        self.update_comparisons_with_fastest();
+
+        // We export (all results so far) after each individual benchmark, because
+        // we would risk losing all results if a later benchmark fails.
+        self.export_manager
+            .write_results(&self.results, self.options.time_unit)?;
+
+        self.compare_output()
+    }
+
+    /// Compute the confidence interval of the relative speed and the p-value of the comparison
+    /// with the fastest result for the latest result. If it is the fastest one so far, the
+    /// comparisons of all results are recomputed.
+    fn update_comparisons_with_fastest(&mut self) {
+        let fastest = match relative_speed::fastest(&self.results) {
+            Some(fastest) => fastest,
+            None => return,
+        };
+        let first = if self.fastest == Some(fastest) {
+            self.results.len() - 1
+        } else {
+            0
+        };
+        self.fastest = Some(fastest);
+
+        for i in first..self.results.len() {
+            let (relative_speed_ci, p_value) = if i == fastest {
+                (None, None)
+            } else {
+                (
+                    relative_speed::compute_confidence_interval(
+                        &self.results[i],
+                        &self.results[fastest],
+                        self.options.confidence_level,
+                    ),
+                    relative_speed::compute_p_value(&self.results[i], &self.results[fastest]),
+                )
+            };
+            self.results[i].relative_speed_ci = relative_speed_ci;
+            self.results[i].p_value = p_value;
         }
+    }
 
-        if self.results.len() < 2 {
-            return;
+    /// Compare the output of the latest benchmark with the output of the previous ones for the
+    /// same parameter values, see `--check-output`
+    fn compare_output(&self) -> Result<()> {
+        let (latest, previous) = match self.results.split_last() {
+            Some(results) => results,
+            None => return Ok(()),
+        };
+        let (output_check, output_hash) = match (&self.options.output_check, latest.output_hash) {
+            (Some(output_check), Some(output_hash)) => (output_check, output_hash),
+            _ => return Ok(()),
+        };
+
+        let different = previous.iter().find(|result| {
+            result.parameters == latest.parameters
+                && result.output_hash.map_or(false, |hash| hash != output_hash)
+        });
+
+        if let Some(different) = different {
+            match output_check.mismatch_action {
+                OutputMismatchAction::Abort => bail!(
+                    "The output of command '{}' differs from the output of command '{}'. Use \
+                     '--on-output-mismatch=warn' to continue anyway.",
+                    latest.command,
+                    different.command
+                ),
+                OutputMismatchAction::Warn => self.observer.notify(&Event::WarningRaised {
+                    number: Some(self.results.len() - 1),
+                    warning: &Warnings::DifferentOutput(different.command.clone()),
+                })?,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Run all benchmarks at once, performing one timing run of every command per round. If a
+    /// random number generator is given, the order of the commands is shuffled in every round.
+    fn run_interleaved(&mut self, benchmarks: &[Benchmark], mut rng: Option<StdRng>) -> Result<()> {
+        let mut next_round = |active: &[usize]| {
+            let mut round = active.to_vec();
+            if let Some(rng) = rng.as_mut() {
+                round.shuffle(rng);
+            }
+            round
+        };
+
+        for benchmark in benchmarks {
+            benchmark.start()?;
         }
 
-        if let Some(mut annotated_results) = relative_speed::compute(&self.results) {
-            annotated_results.sort_by(|l, r| relative_speed::compare_mean_time(l.result, r.result));
-
-            let fastest = &annotated_results[0];
-            let others = &annotated_results[1..];
-
-            println!("{}", "Summary".bold());
-            println!("  '{}' ran", fastest.result.command.cyan());
-
-            for item in others {
-                println!(
-                    "{}{} times faster than '{}'",
-                    format!("{:8.2}", item.relative_speed).bold().green(),
-                    if let Some(stddev) = item.relative_speed_stddev {
-                        format!(" ± {}", format!("{:.2}", stddev).green())
-                    } else {
-                        "".into()
-                    },
-                    &item.result.command.magenta()
-                );
+        // The setup command of a benchmark is run right before its first run, and its cleanup
+        // command right after its last run, such that they affect the runs of the other
+        // benchmarks as little as possible.
+        let mut set_up = vec![false; benchmarks.len()];
+        let mut set_up_once = |i: usize| -> Result<()> {
+            if !set_up[i] {
+                benchmarks[i].run_setup_command()?;
+                benchmarks[i].determine_batch_size()?;
+                set_up[i] = true;
             }
-        } else {
-            eprintln!(
-                "{}: The benchmark comparison could not be computed as some benchmark times are zero. \
-                 This could be caused by background interference during the initial calibration phase \
-                 of hyperfine, in combination with very fast commands (faster than a few milliseconds). \
-                 Try to re-run the benchmark on a quiet system. If it does not help, you command is \
-                 most likely too fast to be accurately benchmarked by hyperfine.",
-                 "Note".bold().red()
+            Ok(())
+        };
+
+        let all: Vec<usize> = (0..benchmarks.len()).collect();
+
+        // Warmup phase. Commands with fewer warmup runs drop out of the later rounds.
+        let total_warmup_count: u64 = benchmarks.iter().map(Benchmark::warmup_count).sum();
+        if total_warmup_count > 0 {
+            self.observer.notify(&Event::PhaseStarted {
+                phase: Phase::Warmup,
+                number: None,
+                runs: total_warmup_count,
+            })?;
+            let max_warmup_count = benchmarks.iter().map(Benchmark::warmup_count).max();
+            for round in 0..max_warmup_count.unwrap_or(0) {
+                let warming_up: Vec<usize> = all
+                    .iter()
+                    .copied()
+                    .filter(|&i| benchmarks[i].warmup_count() > round)
+                    .collect();
+                for i in next_round(&warming_up) {
+                    set_up_once(i)?;
+                    benchmarks[i].warmup_run()?;
+                }
+            }
+            self.observer.notify(&Event::PhaseFinished {
+                phase: Phase::Warmup,
+                number: None,
+            })?;
+        }
+
+        // Gather statistics (perform the actual benchmarks)
+        self.observer.notify(&Event::PhaseStarted {
+            phase: Phase::Timing,
+            number: None,
+            runs: self.options.run_bounds.min * benchmarks.len() as u64,
+        })?;
+        let mut measurements: Vec<Measurements> =
+            benchmarks.iter().map(|_| Measurements::default()).collect();
+        let mut finished = vec![false; benchmarks.len()];
+        let mut active = all;
+        while !active.is_empty() {
+            for i in next_round(&active) {
+                set_up_once(i)?;
+                benchmarks[i].timing_run(&mut measurements[i])?;
+
+                finished[i] = benchmarks[i].progress(&measurements[i]).finished;
+                if finished[i] {
+                    benchmarks[i].run_cleanup_command()?;
+                }
+            }
+
+            active.retain(|&i| !finished[i]);
+
+            if !active.is_empty() {
+                let estimated_total = benchmarks
+                    .iter()
+                    .zip(&measurements)
+                    .enumerate()
+                    .map(|(i, (benchmark, measurements))| {
+                        let num_runs = measurements.num_runs();
+                        if active.contains(&i) {
+                            benchmark
+                                .progress(measurements)
+                                .estimated_total
+                                .unwrap_or(num_runs + 1)
+                        } else {
+                            num_runs
+                        }
+                    })
+                    .sum();
+                self.observer.notify(&Event::ProgressUpdated {
+                    estimated_total: Some(estimated_total),
+                    status: ProgressStatus::CommandsRemaining(active.len()),
+                })?;
+            }
+        }
+        self.observer.notify(&Event::PhaseFinished {
+            phase: Phase::Timing,
+            number: None,
+        })?;
+
+        for (benchmark, measurements) in benchmarks.iter().zip(measurements) {
+            let result = benchmark.summarize(measurements)?;
+            self.add_result(result)?;
+        }
+
+        Ok(())
+    }
+
+    /// Create the cgroup for measuring whole process trees. Falls back to measuring the
+    /// benchmarked processes only (with a warning) if cgroups are not available.
+    fn create_cgroup(&self) -> Result<Option<Cgroup>> {
+        let warning = match Cgroup::new() {
+            Ok(cgroup) => {
+                let unavailable = cgroup.unavailable_controllers();
+                if !unavailable.is_empty() {
+                    self.observer.notify(&Event::WarningRaised {
+                        number: None,
+                        warning: &Warnings::CgroupControllersUnavailable(unavailable.to_vec()),
+                    })?;
+                }
+                return Ok(Some(cgroup));
+            }
+            Err(e) => Warnings::CgroupUnavailable(e.to_string()),
+        };
+
+        self.observer.notify(&Event::WarningRaised {
+            number: None,
+            warning: &warning,
+        })?;
+        Ok(None)
+    }
+
+    /// The results of all benchmarks that have been run so far
+    pub fn into_results(self) -> Vec<BenchmarkResult> {
+        self.results
+    }
+
+    /// Compare the benchmarks with each other and report the comparison to the observer
+    pub fn report_relative_speed_comparison(&self) -> Result<()> {
+        if self.results.len() < 2 {
+            return Ok(());
+        }
+
+        let annotated_results = relative_speed::compute(&self.results).map(|mut results| {
+            results.sort_by(|l, r| relative_speed::compare_mean_time(l.result, r.result));
+            results
+        });
+        self.observer.notify(&Event::ComparisonComputed {
+            comparison: annotated_results.as_deref(),
+        })
+    }
+}
+
+/// Create the executor for the given kind, which runs commands in the given context
+fn create_executor<'a>(
+    options: &'a Options,
+    kind: &'a ExecutorKind,
+    context: ExecutionContext<'a>,
+    cgroup: Option<&'a Cgroup>,
+) -> Result<Box<dyn Executor + 'a>> {
+    if let Some(directory) = context.working_directory {
+        if !directory.is_dir() {
+            bail!(
+                "The working directory '{}' does not exist",
+                directory.to_string_lossy()
             );
         }
     }
+
+    Ok(match kind {
+        ExecutorKind::Raw => Box::new(RawExecutor::new(options, cgroup, context)),
+        ExecutorKind::Mock(shell) => Box::new(MockExecutor::new(shell.clone())),
+        ExecutorKind::Shell(shell) => Box::new(ShellExecutor::new(shell, options, cgroup, context)),
+    })
 }
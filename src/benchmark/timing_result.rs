@@ -1,4 +1,5 @@
-use crate::util::units::Second;
+use crate::timer::ResourceCounters;
+use crate::util::units::{Byte, Second};
 
 /// Results from timing a single command
 #[derive(Debug, Default, Copy, Clone)]
@@ -11,4 +12,60 @@ pub struct TimingResult {
 
     /// Time spent in kernel mode
     pub time_system: Second,
+
+    /// Peak memory usage (maximum resident set size), if available
+    pub max_rss: Option<Byte>,
+
+    /// Page faults, context switches and block I/O operations, if available
+    pub counters: Option<ResourceCounters>,
+
+    /// Whether the command has been killed because it exceeded the timeout
+    pub timed_out: bool,
+
+    /// Hash of the output of the command, if it is captured (see `--check-output`)
+    pub output_hash: Option<u64>,
+}
+
+impl TimingResult {
+    /// Convert the result of a batch of back-to-back invocations into the average result of a
+    /// single invocation. The peak memory usage is left unchanged.
+    pub fn per_invocation(self, batch_size: u64) -> TimingResult {
+        let invocations = batch_size as Second;
+        TimingResult {
+            time_real: self.time_real / invocations,
+            time_user: self.time_user / invocations,
+            time_system: self.time_system / invocations,
+            counters: self.counters.map(|c| c.per_invocation(batch_size)),
+            ..self
+        }
+    }
+}
+
+#[test]
+fn test_per_invocation() {
+    use approx::assert_relative_eq;
+
+    let batch = TimingResult {
+        time_real: 1.0,
+        time_user: 0.5,
+        time_system: 0.25,
+        max_rss: Some(1024),
+        counters: Some(ResourceCounters {
+            minor_page_faults: 100,
+            voluntary_context_switches: 9,
+            ..ResourceCounters::default()
+        }),
+        timed_out: false,
+        output_hash: None,
+    };
+
+    let result = batch.per_invocation(4);
+    assert_relative_eq!(0.25, result.time_real);
+    assert_relative_eq!(0.125, result.time_user);
+    assert_relative_eq!(0.0625, result.time_system);
+    assert_eq!(Some(1024), result.max_rss);
+
+    let counters = result.counters.unwrap();
+    assert_eq!(25, counters.minor_page_faults);
+    assert_eq!(2, counters.voluntary_context_switches);
 }
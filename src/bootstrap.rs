@@ -0,0 +1,216 @@
+//! A module for computing confidence intervals by bootstrap resampling. In contrast to intervals
+//! derived from the standard deviation, bootstrap intervals do not assume normally distributed
+//! run times, which is rarely the case for real-world benchmarks (the distributions are often
+//! skewed towards long run times).
+//!
+//! References:
+//! - B. Efron and R. J. Tibshirani (1993), "An Introduction to the Bootstrap", Chapman & Hall.
+
+use rand::Rng;
+use serde::Serialize;
+use statistical::{mean, median};
+
+use crate::util::units::Scalar;
+
+/// Default confidence level for all confidence intervals
+pub const DEFAULT_CONFIDENCE_LEVEL: Scalar = 0.95;
+
+/// Number of bootstrap resamples used to estimate the sampling distribution
+pub const NUM_RESAMPLES: usize = 2_000;
+
+/// A (two-sided) confidence interval
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+pub struct ConfidenceInterval {
+    /// Confidence level of this interval, e.g. 0.95
+    pub level: Scalar,
+
+    /// Lower bound of the interval
+    pub lower: Scalar,
+
+    /// Upper bound of the interval
+    pub upper: Scalar,
+}
+
+/// A statistic that can be estimated via bootstrapping
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Statistic {
+    Mean,
+    Median,
+}
+
+impl Statistic {
+    fn compute(self, xs: &[Scalar]) -> Scalar {
+        match self {
+            Statistic::Mean => mean(xs),
+            Statistic::Median => median(xs),
+        }
+    }
+}
+
+/// Compute a percentile bootstrap confidence interval for the given statistic of a sample.
+/// Returns `None` if the sample has less than two elements or no valid estimates.
+pub fn confidence_interval<R: Rng>(
+    xs: &[Scalar],
+    statistic: Statistic,
+    level: Scalar,
+    rng: &mut R,
+) -> Option<ConfidenceInterval> {
+    if xs.len() < 2 {
+        return None;
+    }
+
+    let mut buffer = vec![0.0; xs.len()];
+    let estimates = (0..NUM_RESAMPLES)
+        .map(|_| {
+            resample(xs, &mut buffer, rng);
+            statistic.compute(&buffer)
+        })
+        .collect();
+
+    percentile_interval(estimates, level)
+}
+
+/// Compute a percentile bootstrap confidence interval for the ratio `statistic(xs) /
+/// statistic(ys)` of two independent samples. Returns `None` if one of the samples has less
+/// than two elements or if there are no valid estimates.
+pub fn ratio_confidence_interval<R: Rng>(
+    xs: &[Scalar],
+    ys: &[Scalar],
+    statistic: Statistic,
+    level: Scalar,
+    rng: &mut R,
+) -> Option<ConfidenceInterval> {
+    if xs.len() < 2 || ys.len() < 2 {
+        return None;
+    }
+
+    let mut buffer_x = vec![0.0; xs.len()];
+    let mut buffer_y = vec![0.0; ys.len()];
+    let estimates = (0..NUM_RESAMPLES)
+        .filter_map(|_| {
+            resample(xs, &mut buffer_x, rng);
+            resample(ys, &mut buffer_y, rng);
+            let denominator = statistic.compute(&buffer_y);
+            if denominator == 0.0 {
+                None
+            } else {
+                Some(statistic.compute(&buffer_x) / denominator)
+            }
+        })
+        .collect::<Vec<_>>();
+
+    percentile_interval(estimates, level)
+}
+
+/// Draw `buffer.len()` elements from `xs` with replacement
+fn resample<R: Rng>(xs: &[Scalar], buffer: &mut [Scalar], rng: &mut R) {
+    for x in buffer.iter_mut() {
+        *x = xs[rng.gen_range(0..xs.len())];
+    }
+}
+
+/// Determine the interval that contains the central `level` fraction of the estimates. NaN
+/// estimates are skipped. Returns `None` if no estimates are left.
+fn percentile_interval(mut estimates: Vec<Scalar>, level: Scalar) -> Option<ConfidenceInterval> {
+    estimates.retain(|estimate| !estimate.is_nan());
+    if estimates.is_empty() {
+        return None;
+    }
+    estimates.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    let alpha = 1.0 - level;
+    Some(ConfidenceInterval {
+        level,
+        lower: quantile(&estimates, alpha / 2.0),
+        upper: quantile(&estimates, 1.0 - alpha / 2.0),
+    })
+}
+
+/// Linearly interpolated quantile of a sorted, non-empty sample
+fn quantile(sorted: &[Scalar], q: Scalar) -> Scalar {
+    let position = q * (sorted.len() - 1) as Scalar;
+    let index = position.floor() as usize;
+    let fraction = position - index as Scalar;
+
+    match sorted.get(index + 1) {
+        Some(next) => sorted[index] + fraction * (next - sorted[index]),
+        None => sorted[index],
+    }
+}
+
+#[cfg(test)]
+fn test_rng() -> rand::rngs::StdRng {
+    use rand::SeedableRng;
+    rand::rngs::StdRng::seed_from_u64(42)
+}
+
+#[test]
+fn test_quantile() {
+    use approx::assert_relative_eq;
+
+    let xs = [1.0, 2.0, 3.0, 4.0, 5.0];
+    assert_relative_eq!(1.0, quantile(&xs, 0.0));
+    assert_relative_eq!(3.0, quantile(&xs, 0.5));
+    assert_relative_eq!(5.0, quantile(&xs, 1.0));
+    assert_relative_eq!(1.5, quantile(&xs, 0.125));
+
+    assert_relative_eq!(7.0, quantile(&[7.0], 0.3));
+}
+
+#[test]
+fn test_confidence_interval_contains_estimate() {
+    let xs: Vec<Scalar> = (1..=50).map(|i| 1.0 + 0.01 * (i as Scalar)).collect();
+    let sample_mean = mean(&xs);
+    let sample_median = median(&xs);
+
+    let mut rng = test_rng();
+
+    let ci = confidence_interval(&xs, Statistic::Mean, 0.95, &mut rng).unwrap();
+    assert_eq!(0.95, ci.level);
+    assert!(ci.lower < sample_mean && sample_mean < ci.upper);
+    assert!(ci.upper - ci.lower < 0.1);
+
+    let ci = confidence_interval(&xs, Statistic::Median, 0.95, &mut rng).unwrap();
+    assert!(ci.lower <= sample_median && sample_median <= ci.upper);
+
+    // A higher confidence level leads to a wider interval
+    let ci_90 = confidence_interval(&xs, Statistic::Mean, 0.90, &mut rng).unwrap();
+    let ci_99 = confidence_interval(&xs, Statistic::Mean, 0.99, &mut rng).unwrap();
+    assert!(ci_99.upper - ci_99.lower > ci_90.upper - ci_90.lower);
+}
+
+#[test]
+fn test_confidence_interval_constant_sample() {
+    let ci = confidence_interval(&[2.0, 2.0, 2.0], Statistic::Mean, 0.95, &mut test_rng()).unwrap();
+    assert_eq!(2.0, ci.lower);
+    assert_eq!(2.0, ci.upper);
+
+    assert!(confidence_interval(&[2.0], Statistic::Mean, 0.95, &mut test_rng()).is_none());
+}
+
+#[test]
+fn test_ratio_confidence_interval() {
+    let xs: Vec<Scalar> = (1..=30).map(|i| 2.0 + 0.01 * (i as Scalar)).collect();
+    let ys: Vec<Scalar> = (1..=30).map(|i| 1.0 + 0.01 * (i as Scalar)).collect();
+    let ratio = mean(&xs) / mean(&ys);
+
+    let ci = ratio_confidence_interval(&xs, &ys, Statistic::Mean, 0.95, &mut test_rng()).unwrap();
+    assert!(ci.lower < ratio && ratio < ci.upper);
+
+    assert!(
+        ratio_confidence_interval(&xs, &[1.0], Statistic::Mean, 0.95, &mut test_rng()).is_none()
+    );
+    assert!(
+        ratio_confidence_interval(&xs, &[0.0, 0.0], Statistic::Mean, 0.95, &mut test_rng())
+            .is_none()
+    );
+}
+
+#[test]
+fn test_percentile_interval_skips_nan() {
+    let ci = percentile_interval(vec![3.0, Scalar::NAN, 1.0, 2.0], 0.5).unwrap();
+    assert_eq!(ci.lower, 1.5);
+    assert_eq!(ci.upper, 2.5);
+
+    assert!(percentile_interval(vec![Scalar::NAN], 0.95).is_none());
+}
@@ -14,7 +14,7 @@ where
 }
 
 /// Build the clap command for parsing command line arguments
-fn build_command() -> Command {
+pub fn build_command() -> Command {
     Command::new("hyperfine")
         .version(crate_version!())
         .next_line_help(true)
@@ -28,19 +28,43 @@ fn build_command() -> Command {
                        The latter is only available if the shell is not explicitly disabled via \
                        '--shell=none'. If multiple commands are given, hyperfine will show a \
                        comparison of the respective runtimes.")
-                .required(true)
+                .required_unless_present("config")
                 .action(ArgAction::Append)
                 .value_parser(NonEmptyStringValueParser::new()),
         )
+        .arg(
+            Arg::new("config")
+                .long("config")
+                .action(ArgAction::Set)
+                .value_name("FILE")
+                .help("Read the commands and options of a benchmark suite from a TOML file. The \
+                       top-level keys are the long names of the options in this list (e.g. \
+                       'warmup = 3'). Commands are given as '[[command]]' tables with the keys \
+                       'command' and 'name', as well as 'setup', 'prepare', 'conclude', \
+                       'cleanup', 'warmup', 'shell', 'working-dir', 'input' and \
+                       'accept-exit-codes' to override the top-level values. Environment \
+                       variables are given as tables ('env = { LANG = \"C\" }'), both at the \
+                       top level and for single commands. Parameters are given in \
+                       '[[parameter-scan]]' tables (keys 'name', 'min', 'max' and one of 'step', \
+                       'factor', 'points') and '[[parameter-list]]' tables (keys 'name', \
+                       'values'), parameters that vary together as 'parameter-zip = [\"a\", \
+                       \"b\"]', filters as 'parameter-filter = \"a < b\"', and exports in an \
+                       '[export]' table (keys 'json', 'csv', 'markdown', 'asciidoc', 'orgmode', \
+                       'columns'). Commands on the command line are added to the ones in the \
+                       file, while options on the command line replace the ones in the file."),
+        )
         .arg(
             Arg::new("warmup")
                 .long("warmup")
                 .short('w')
                 .value_name("NUM")
-                .action(ArgAction::Set)
+                .action(ArgAction::Append)
+                .num_args(1)
                 .help(
                     "Perform NUM warmup runs before the actual benchmark. This can be used \
-                     to fill (disk) caches for I/O-heavy programs.",
+                     to fill (disk) caches for I/O-heavy programs.\nThe --warmup option can \
+                     be specified once for all commands or multiple times, once for each \
+                     command.",
                 ),
         )
         .arg(
@@ -62,24 +86,87 @@ fn build_command() -> Command {
         .arg(
             Arg::new("runs")
                 .long("runs")
-                .conflicts_with_all(&["max-runs", "min-runs"])
+                .conflicts_with_all(["max-runs", "min-runs"])
                 .short('r')
                 .action(ArgAction::Set)
                 .value_name("NUM")
                 .help("Perform exactly NUM runs for each command. If this option is not specified, \
                        hyperfine automatically determines the number of runs."),
         )
+        .arg(
+            Arg::new("precision")
+                .long("precision")
+                .action(ArgAction::Set)
+                .value_name("PERCENT")
+                .conflicts_with("runs")
+                .help("Instead of performing a fixed number of runs, keep benchmarking until the \
+                       confidence interval of the mean run time is narrower than PERCENT of the \
+                       mean (e.g. '1%'). The number of runs is still bounded by --min-runs and \
+                       --max-runs, and by the time limit set with --max-benchmarking-time."),
+        )
+        .arg(
+            Arg::new("max-benchmarking-time")
+                .long("max-benchmarking-time")
+                .action(ArgAction::Set)
+                .value_name("SECS")
+                .requires("precision")
+                .help("Set the maximum time (in seconds) to spend on the timing runs of a \
+                       single command when using --precision (default: 60). Benchmarking stops \
+                       at this limit even if the requested precision has not been reached."),
+        )
+        .arg(
+            Arg::new("execution-order")
+                .long("execution-order")
+                .action(ArgAction::Set)
+                .value_name("ORDER")
+                .value_parser(["sequential", "round-robin", "shuffled"])
+                .help("Set the order in which the timing runs of the different commands are \
+                       performed. 'sequential' (default) performs all runs of a command before \
+                       moving on to the next one. 'round-robin' performs one run of every \
+                       command in turn, and 'shuffled' does the same in a random order for every \
+                       round. Interleaving the runs reduces the bias from slow drifts of the \
+                       system performance (e.g. thermal throttling or background jobs). The \
+                       setup and cleanup commands of a command are run right before its first \
+                       and right after its last run, respectively."),
+        )
+        .arg(
+            Arg::new("seed")
+                .long("seed")
+                .action(ArgAction::Set)
+                .value_name("NUM")
+                .requires("execution-order")
+                .help("Seed for the random order of '--execution-order=shuffled'. If this \
+                       option is not specified, a random seed is used and reported, such that \
+                       the order can be reproduced."),
+        )
+        .arg(
+            Arg::new("batch")
+                .long("batch")
+                .action(ArgAction::Set)
+                .value_name("SIZE")
+                .help("Run the command SIZE times back-to-back in every timing run and report \
+                       the time per invocation. With 'auto', the batch size is chosen such that \
+                       a timing run takes at least 50 ms. This reduces the relative impact of \
+                       the shell spawning time and of the timer resolution for very fast \
+                       commands. When a shell is used, all invocations of a batch are run by \
+                       a single shell process and only the exit code of the last invocation is \
+                       checked. The preparation and conclusion commands are run once per batch, \
+                       and the '--timeout' applies to the batch as a whole."),
+        )
         .arg(
             Arg::new("setup")
                 .long("setup")
                 .short('s')
-                .action(ArgAction::Set)
+                .action(ArgAction::Append)
+                .num_args(1)
                 .value_name("CMD")
                 .help(
                     "Execute CMD before each set of timing runs. This is useful for \
                      compiling your software with the provided parameters, or to do any \
                      other work that should happen once before a series of benchmark runs, \
-                     not every time as would happen with the --prepare option."
+                     not every time as would happen with the --prepare option.\nThe --setup \
+                     option can be specified once for all commands or multiple times, once \
+                     for each command."
                 ),
         )
         .arg(
@@ -97,26 +184,96 @@ fn build_command() -> Command {
                      be run prior to the corresponding benchmark command.",
                 ),
         )
+        .arg(
+            Arg::new("conclude")
+                .long("conclude")
+                .short('C')
+                .action(ArgAction::Append)
+                .num_args(1)
+                .value_name("CMD")
+                .help(
+                    "Execute CMD after each timing run. This is useful for restoring the \
+                     state that a run has changed, like deleting output files or resetting a \
+                     database fixture. The conclusion command is not timed.\nThe --conclude \
+                     option can be specified once for all commands or multiple times, once for \
+                     each command. In the latter case, each conclusion command will be run \
+                     after the corresponding benchmark command.",
+                ),
+        )
         .arg(
             Arg::new("cleanup")
                 .long("cleanup")
                 .short('c')
-                .action(ArgAction::Set)
+                .action(ArgAction::Append)
+                .num_args(1)
                 .value_name("CMD")
                 .help(
                     "Execute CMD after the completion of all benchmarking \
                      runs for each individual command to be benchmarked. \
                      This is useful if the commands to be benchmarked produce \
-                     artifacts that need to be cleaned up."
+                     artifacts that need to be cleaned up.\nThe --cleanup option can be \
+                     specified once for all commands or multiple times, once for each command."
+                ),
+        )
+        .arg(
+            Arg::new("timeout")
+                .long("timeout")
+                .action(ArgAction::Set)
+                .value_name("SECS")
+                .help(
+                    "Kill the benchmarked command if a single (warmup or timing) run takes \
+                     longer than SECS seconds. On Unix systems, the whole process group of the \
+                     command is killed. See '--on-timeout' for how timed out runs are handled.",
+                ),
+        )
+        .arg(
+            Arg::new("on-timeout")
+                .long("on-timeout")
+                .action(ArgAction::Set)
+                .value_name("ACTION")
+                .value_parser(["abort", "exclude"])
+                .requires("timeout")
+                .help(
+                    "What to do if a run exceeds the '--timeout'. Possible values: 'abort' \
+                     (default) stops with an error, 'exclude' excludes the run from the \
+                     statistics and reports the number of timed out runs.",
                 ),
         )
+        .arg(
+            Arg::new("setup-timeout")
+                .long("setup-timeout")
+                .action(ArgAction::Set)
+                .value_name("SECS")
+                .help("Abort if the '--setup' command takes longer than SECS seconds."),
+        )
+        .arg(
+            Arg::new("prepare-timeout")
+                .long("prepare-timeout")
+                .action(ArgAction::Set)
+                .value_name("SECS")
+                .help("Abort if a '--prepare' command takes longer than SECS seconds."),
+        )
+        .arg(
+            Arg::new("conclude-timeout")
+                .long("conclude-timeout")
+                .action(ArgAction::Set)
+                .value_name("SECS")
+                .help("Abort if a '--conclude' command takes longer than SECS seconds."),
+        )
+        .arg(
+            Arg::new("cleanup-timeout")
+                .long("cleanup-timeout")
+                .action(ArgAction::Set)
+                .value_name("SECS")
+                .help("Abort if the '--cleanup' command takes longer than SECS seconds."),
+        )
         .arg(
             Arg::new("parameter-scan")
                 .long("parameter-scan")
                 .short('P')
-                .action(ArgAction::Set)
+                .action(ArgAction::Append)
                 .allow_hyphen_values(true)
-                .value_names(&["VAR", "MIN", "MAX"])
+                .value_names(["VAR", "MIN", "MAX"])
                 .help(
                     "Perform benchmark runs for each value in the range MIN..MAX. Replaces the \
                      string '{VAR}' in each command by the current parameter value.\n\n  \
@@ -125,21 +282,57 @@ fn build_command() -> Command {
                      To have the value increase following different patterns, use shell arithmetics.\n\n  \
                      Example: hyperfine -P size 0 3 'sleep $((2**{size}))'\n\n\
                      This performs benchmarks with power of 2 increases: 'sleep 1', 'sleep 2', 'sleep 4', …\n\
-                     The exact syntax may vary depending on your shell and OS."
+                     The exact syntax may vary depending on your shell and OS. Geometric and \
+                     logarithmic scans are also available via --parameter-factor and \
+                     --parameter-points.\n\n\
+                     The option can be specified multiple times and can be combined with \
+                     --parameter-list to run benchmarks for all possible parameter combinations."
                 ),
         )
         .arg(
             Arg::new("parameter-step-size")
                 .long("parameter-step-size")
                 .short('D')
-                .action(ArgAction::Set)
-                .value_names(&["DELTA"])
+                .action(ArgAction::Append)
+                .value_names(["DELTA"])
                 .requires("parameter-scan")
                 .help(
                     "This argument requires --parameter-scan to be specified as well. \
                      Traverse the range MIN..MAX in steps of DELTA.\n\n  \
                      Example:  hyperfine -P delay 0.3 0.7 -D 0.2 'sleep {delay}'\n\n\
-                     This performs benchmarks for 'sleep 0.3', 'sleep 0.5' and 'sleep 0.7'.",
+                     This performs benchmarks for 'sleep 0.3', 'sleep 0.5' and 'sleep 0.7'.\n\n\
+                     With multiple parameter scans, this option (as well as --parameter-factor \
+                     and --parameter-points) applies to the closest --parameter-scan in front \
+                     of it.",
+                ),
+        )
+        .arg(
+            Arg::new("parameter-factor")
+                .long("parameter-factor")
+                .action(ArgAction::Append)
+                .value_names(["FACTOR"])
+                .requires("parameter-scan")
+                .help(
+                    "This argument requires --parameter-scan to be specified as well. \
+                     Perform a geometric scan that multiplies the value by FACTOR after each \
+                     step, starting at MIN (which has to be positive).\n\n  \
+                     Example:  hyperfine -P size 1 1000000 --parameter-factor 10 'head -c {size} /dev/zero'\n\n\
+                     This performs benchmarks for the sizes 1, 10, 100, …, 1000000.",
+                ),
+        )
+        .arg(
+            Arg::new("parameter-points")
+                .long("parameter-points")
+                .action(ArgAction::Append)
+                .value_names(["NUM"])
+                .requires("parameter-scan")
+                .help(
+                    "This argument requires --parameter-scan to be specified as well. \
+                     Perform a logarithmic scan with NUM values that are evenly spaced on a \
+                     logarithmic scale between MIN and MAX (both have to be positive). For integer \
+                     bounds, the values are rounded to integers.\n\n  \
+                     Example:  hyperfine -P delay 0.001 1 --parameter-points 4 'sleep {delay}'\n\n\
+                     This performs benchmarks for 'sleep 0.001', 'sleep 0.01', 'sleep 0.1' and 'sleep 1'.",
                 ),
         )
         .arg(
@@ -148,15 +341,75 @@ fn build_command() -> Command {
                 .short('L')
                 .action(ArgAction::Append)
                 .allow_hyphen_values(true)
-                .value_names(&["VAR", "VALUES"])
-                .conflicts_with_all(&["parameter-scan", "parameter-step-size"])
+                .value_names(["VAR", "VALUES"])
                 .help(
                     "Perform benchmark runs for each value in the comma-separated list VALUES. \
                      Replaces the string '{VAR}' in each command by the current parameter value\
                      .\n\nExample:  hyperfine -L compiler gcc,clang '{compiler} -O2 main.cpp'\n\n\
                      This performs benchmarks for 'gcc -O2 main.cpp' and 'clang -O2 main.cpp'.\n\n\
-                     The option can be specified multiple times to run benchmarks for all \
-                     possible parameter combinations.\n"
+                     The option can be specified multiple times and can be combined with \
+                     --parameter-scan to run benchmarks for all possible parameter combinations.\n"
+                ),
+        )
+        .arg(
+            Arg::new("parameter-list-file")
+                .long("parameter-list-file")
+                .action(ArgAction::Append)
+                .allow_hyphen_values(true)
+                .value_names(["VAR", "FILE"])
+                .help(
+                    "Perform benchmark runs for each line in FILE. Empty lines are skipped. \
+                     Otherwise, this works like --parameter-list and can be combined with it.\n\n  \
+                     Example:  hyperfine --parameter-list-file input inputs.txt 'wc -l {input}'",
+                ),
+        )
+        .arg(
+            Arg::new("parameter-list-cmd")
+                .long("parameter-list-cmd")
+                .action(ArgAction::Append)
+                .allow_hyphen_values(true)
+                .value_names(["VAR", "CMD"])
+                .help(
+                    "Run CMD before the benchmarks and perform benchmark runs for each line of \
+                     its output. CMD is run in the (first) shell given by --shell, or in the \
+                     default shell. Empty lines are skipped. Otherwise, this \
+                     works like --parameter-list and can be combined with it.\n\n  \
+                     Example:  hyperfine --parameter-list-cmd tag 'git tag --list v*' \
+                     'git checkout {tag} && make'",
+                ),
+        )
+        .arg(
+            Arg::new("parameter-zip")
+                .long("parameter-zip")
+                .action(ArgAction::Append)
+                .value_name("VARS")
+                .help(
+                    "Vary the parameters in the comma-separated list VARS together instead of \
+                     running benchmarks for all of their combinations. The i-th benchmark uses \
+                     the i-th value of each of these parameters, so they need to have the same \
+                     number of values. The option can be specified multiple times for several \
+                     groups of parameters.\n\n  \
+                     Example:  hyperfine -L version 1.0,1.1 -L binary /opt/a,/opt/b \
+                     --parameter-zip version,binary '{binary} --version'\n\n\
+                     This performs benchmarks for '/opt/a --version' (version 1.0) and \
+                     '/opt/b --version' (version 1.1).",
+                ),
+        )
+        .arg(
+            Arg::new("parameter-filter")
+                .long("parameter-filter")
+                .action(ArgAction::Append)
+                .value_name("EXPR")
+                .help(
+                    "Skip all parameter combinations for which the expression EXPR is false. \
+                     Expressions compare parameters with each other or with values using \
+                     '==', '!=', '<', '<=', '>' and '>=', and can be combined with '&&', '||', \
+                     '!' and parentheses. Numbers are compared numerically, other values as \
+                     strings. String values have to be quoted. The option can be specified \
+                     multiple times to apply several filters.\n\n  \
+                     Example:  hyperfine -L threads 1,4,16 -L mode single,multi \
+                     --parameter-filter \"threads == 1 || mode != 'single'\" \
+                     'run --mode {mode} -j {threads}'",
                 ),
         )
         .arg(
@@ -178,24 +431,108 @@ fn build_command() -> Command {
             Arg::new("shell")
                 .long("shell")
                 .short('S')
-                .action(ArgAction::Set)
+                .action(ArgAction::Append)
+                .num_args(1)
                 .value_name("SHELL")
-                .overrides_with("shell")
                 .help("Set the shell to use for executing benchmarked commands. This can be the \
                        name or the path to the shell executable, or a full command line \
                        like \"bash --norc\". It can also be set to \"default\" to explicitly select \
                        the default shell on this platform. Finally, this can also be set to \
                        \"none\" to disable the shell. In this case, commands will be executed \
                        directly. They can still have arguments, but more complex things like \
-                       \"sleep 0.1; sleep 0.2\" are not possible without a shell.")
+                       \"sleep 0.1; sleep 0.2\" are not possible without a shell.\nThe --shell \
+                       option can be specified once for all commands or multiple times, once for \
+                       each command.")
+        )
+        .arg(
+            Arg::new("working-dir")
+                .long("working-dir")
+                .action(ArgAction::Append)
+                .num_args(1)
+                .value_name("DIR")
+                .help("Run the benchmarked commands, as well as the setup, preparation and \
+                       cleanup commands, in the directory DIR. The --working-dir option can be \
+                       specified once for all commands or multiple times, once for each \
+                       command.")
+        )
+        .arg(
+            Arg::new("input")
+                .long("input")
+                .action(ArgAction::Append)
+                .num_args(1)
+                .value_name("FILE")
+                .conflicts_with("batch")
+                .help("Connect FILE to the standard input of the benchmarked command. The file \
+                       is opened anew for every run, without a shell pipeline in between. \
+                       Relative paths refer to the --working-dir. Parameters like '{size}' are \
+                       substituted in the path. Use 'null' to read from the null device (the \
+                       default). The --input option can be specified once for all commands or \
+                       multiple times, once for each command."),
+        )
+        .arg(
+            Arg::new("env")
+                .long("env")
+                .action(ArgAction::Append)
+                .num_args(1)
+                .value_name("NAME=VALUE")
+                .help("Set the environment variable NAME to VALUE for the benchmarked commands, \
+                       as well as for the setup, preparation and cleanup commands. Prefix the \
+                       argument with the number of a benchmark (e.g. '--env 2:NAME=VALUE') to \
+                       set the variable for this benchmark only. This option can be specified \
+                       multiple times."),
+        )
+        .arg(
+            Arg::new("unset-env")
+                .long("unset-env")
+                .action(ArgAction::Append)
+                .num_args(1)
+                .value_delimiter(',')
+                .value_name("NAME")
+                .help("Remove the environment variable NAME from the environment of the \
+                       commands. Like with --env, a benchmark number can be given as a prefix \
+                       ('--unset-env 2:NAME'). Multiple names can be given as a comma-separated \
+                       list or by specifying this option multiple times."),
+        )
+        .arg(
+            Arg::new("clean-env")
+                .long("clean-env")
+                .action(ArgAction::SetTrue)
+                .help("Run the commands in an empty environment instead of the one of hyperfine, \
+                       such that the results do not depend on the environment of the machine. \
+                       Only the variables given by --keep-env and --env are set. Note that this \
+                       also removes PATH, unless it is kept explicitly."),
+        )
+        .arg(
+            Arg::new("keep-env")
+                .long("keep-env")
+                .action(ArgAction::Append)
+                .num_args(1)
+                .value_delimiter(',')
+                .value_name("NAME")
+                .requires("clean-env")
+                .help("Keep the environment variable NAME when using --clean-env, e.g. \
+                       '--keep-env PATH,HOME'. Multiple names can be given as a comma-separated \
+                       list or by specifying this option multiple times."),
         )
         .arg(
             Arg::new("no-shell")
                 .short('N')
                 .action(ArgAction::SetTrue)
-                .conflicts_with_all(&["shell", "debug-mode"])
+                .conflicts_with_all(["shell", "debug-mode"])
                 .help("An alias for '--shell=none'.")
         )
+        .arg(
+            Arg::new("cgroup")
+                .long("cgroup")
+                .action(ArgAction::SetTrue)
+                .help("Linux only: run every timed run in a transient cgroup (v2) and measure the \
+                       CPU time, peak memory usage and block I/O of the whole process tree, \
+                       including daemonized or detached child processes. This requires a \
+                       writable (delegated) cgroup, e.g. via 'systemd-run --user --scope \
+                       -p Delegate=yes'. The memory and I/O statistics additionally require the \
+                       'memory' and 'io' controllers. If no cgroup can be created, hyperfine \
+                       falls back to measuring the benchmarked processes only."),
+        )
         .arg(
             Arg::new("ignore-failure")
                 .long("ignore-failure")
@@ -203,6 +540,19 @@ fn build_command() -> Command {
                 .short('i')
                 .help("Ignore non-zero exit codes of the benchmarked programs."),
         )
+        .arg(
+            Arg::new("accept-exit-codes")
+                .long("accept-exit-codes")
+                .action(ArgAction::Append)
+                .num_args(1)
+                .value_name("CODES")
+                .help("Consider the given exit codes of the benchmarked programs as successful \
+                       instead of just 0, e.g. '0,1' for 'grep' or '0-2'. Other exit codes abort \
+                       the benchmark, unless '--ignore-failure' is used, in which case the \
+                       number of runs with other exit codes is reported. The \
+                       --accept-exit-codes option can be specified once for all commands or \
+                       multiple times, once for each command."),
+        )
         .arg(
             Arg::new("time-unit")
                 .long("time-unit")
@@ -214,6 +564,25 @@ fn build_command() -> Command {
                        If the option is not given, the time unit is determined automatically. \
                        This option affects the standard output as well as all export formats except for CSV and JSON."),
         )
+        .arg(
+            Arg::new("significance-level")
+                .long("significance-level")
+                .action(ArgAction::Set)
+                .value_name("ALPHA")
+                .help("Set the significance level α for the comparison of multiple commands \
+                       (default: 0.05). For each command, hyperfine performs Welch's t-test \
+                       against the fastest command and reports whether the difference in mean \
+                       run time is statistically significant at this level."),
+        )
+        .arg(
+            Arg::new("confidence-level")
+                .long("confidence-level")
+                .action(ArgAction::Set)
+                .value_name("LEVEL")
+                .help("Set the confidence level for the confidence intervals of the mean and \
+                       median run times as well as the relative speed (default: 0.95). The \
+                       intervals are computed by bootstrap resampling of the individual runs."),
+        )
         .arg(
             Arg::new("export-asciidoc")
                 .long("export-asciidoc")
@@ -255,6 +624,35 @@ fn build_command() -> Command {
                 .help("Export the timing summary statistics as a Emacs org-mode table to the given FILE. \
                        The output time unit can be changed using the --time-unit option."),
         )
+        .arg(
+            Arg::new("export-ndjson")
+                .long("export-ndjson")
+                .action(ArgAction::Set)
+                .value_name("FILE")
+                .help("Stream the timings of individual runs and the progress of the benchmarks as \
+                       newline-delimited JSON to the given FILE while the benchmarks are running. \
+                       Every line is a JSON object with an 'event' field ('benchmark_started', \
+                       'phase_started', 'run', 'phase_finished', 'warning', 'notice', \
+                       'benchmark_finished' or 'comparison'). Use '-' to write to stdout, which \
+                       disables the terminal output (like '--style=none'). The time unit is always \
+                       seconds."),
+        )
+        .arg(
+            Arg::new("export-columns")
+                .long("export-columns")
+                .action(ArgAction::Set)
+                .value_name("COLUMNS")
+                .value_delimiter(',')
+                .help(
+                    "Add extra columns with the average resource usage of the benchmarked \
+                     commands to the CSV, Markdown, AsciiDoc and org-mode exports. COLUMNS is a \
+                     comma-separated list of: minor-page-faults, major-page-faults, \
+                     voluntary-context-switches, involuntary-context-switches, \
+                     block-input-operations, block-output-operations. The resource usage is \
+                     only available on Unix systems; the columns are left empty otherwise. \
+                     The JSON export always contains all counters.",
+                ),
+        )
         .arg(
             Arg::new("show-output")
                 .long("show-output")
@@ -284,6 +682,37 @@ fn build_command() -> Command {
                      <FILE>: Write the output to the given file.",
                 ),
         )
+        .arg(
+            Arg::new("check-output")
+                .long("check-output")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["show-output", "output", "batch"])
+                .help("Check that the benchmarked commands produce the same output in every \
+                       run. The standard output is fed through a pipe and hashed. All runs of a \
+                       command have to produce the same output, and all commands have to \
+                       produce the same output for the same parameter values. This is useful to \
+                       confirm that alternative implementations compute the same result."),
+        )
+        .arg(
+            Arg::new("check-stderr")
+                .long("check-stderr")
+                .action(ArgAction::SetTrue)
+                .requires("check-output")
+                .help("Include the standard error in the comparison of '--check-output'."),
+        )
+        .arg(
+            Arg::new("on-output-mismatch")
+                .long("on-output-mismatch")
+                .action(ArgAction::Set)
+                .value_name("ACTION")
+                .value_parser(["abort", "warn"])
+                .requires("check-output")
+                .help(
+                    "What to do if '--check-output' finds differing outputs. Possible values: \
+                     'abort' (default) stops with an error, 'warn' shows a warning and \
+                     continues.",
+                ),
+        )
         .arg(
             Arg::new("command-name")
                 .long("command-name")
@@ -1,22 +1,23 @@
 use std::collections::BTreeMap;
 use std::fmt;
-use std::str::FromStr;
 
 use crate::{
-    error::{OptionsError, ParameterScanError},
+    error::OptionsError,
+    options::Shell,
     parameter::{
-        range_step::{Numeric, RangeStep},
-        ParameterNameAndValue,
+        combination::combinations,
+        filter::Filter,
+        scan::{scan_values, Spacing},
+        ParameterNameAndValue, ParameterNameAndValues,
     },
 };
 
-use clap::{parser::ValuesRef, ArgMatches};
+use clap::ArgMatches;
 
 use crate::parameter::tokenize::tokenize;
-use crate::parameter::ParameterValue;
+use crate::parameter::{source, ParameterValue};
 
 use anyhow::{bail, Context, Result};
-use rust_decimal::Decimal;
 
 /// A command that should be benchmarked.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -112,111 +113,69 @@ impl<'a> Command<'a> {
     }
 }
 
-/// A collection of commands that should be benchmarked
-pub struct Commands<'a>(Vec<Command<'a>>);
+/// Options that provide the values of a parameter. Every option adds one dimension to the
+/// cross product of parameter values.
+const PARAMETER_ARGS: [&str; 4] = [
+    "parameter-scan",
+    "parameter-list",
+    "parameter-list-file",
+    "parameter-list-cmd",
+];
+
+/// Options that determine the spacing of the values of a parameter scan
+const SPACING_ARGS: [&str; 3] = [
+    "parameter-step-size",
+    "parameter-factor",
+    "parameter-points",
+];
+
+fn text_values(values: Vec<String>) -> Vec<ParameterValue> {
+    values.into_iter().map(ParameterValue::Text).collect()
+}
+
+/// A collection of commands that should be benchmarked, together with the number of parameter
+/// combinations that have been excluded by `--parameter-filter`
+pub struct Commands<'a>(Vec<Command<'a>>, usize);
+
+impl<'a> From<Vec<Command<'a>>> for Commands<'a> {
+    fn from(commands: Vec<Command<'a>>) -> Self {
+        Self(commands, 0)
+    }
+}
 
 impl<'a> Commands<'a> {
-    pub fn from_cli_arguments(matches: &'a ArgMatches) -> Result<Commands> {
-        let command_names = matches.get_many::<String>("command-name");
+    pub fn from_cli_arguments(matches: &'a ArgMatches) -> Result<Commands<'a>> {
+        let command_names = matches
+            .get_many::<String>("command-name")
+            .map_or(vec![], |names| {
+                names.map(|v| v.as_str()).collect::<Vec<_>>()
+            });
         let command_strings = matches
             .get_many::<String>("command")
             .unwrap_or_default()
             .map(|v| v.as_str())
             .collect::<Vec<_>>();
 
-        if let Some(args) = matches.get_many::<String>("parameter-scan") {
-            let step_size = matches
-                .get_one::<String>("parameter-step-size")
-                .map(|s| s.as_str());
-            Ok(Self(Self::get_parameter_scan_commands(
+        if PARAMETER_ARGS.iter().any(|arg| matches.contains_id(arg)) {
+            let parameters = Self::get_parameter_values(matches)?;
+            let zipped: Vec<Vec<&str>> = matches
+                .get_many::<String>("parameter-zip")
+                .unwrap_or_default()
+                .map(|names| names.split(',').map(str::trim).collect())
+                .collect();
+            let filters = matches
+                .get_many::<String>("parameter-filter")
+                .unwrap_or_default()
+                .map(|filter| Filter::parse(filter))
+                .collect::<Result<Vec<_>, _>>()?;
+            Self::build_parametrized_commands(
                 command_names,
                 command_strings,
-                args,
-                step_size,
-            )?))
-        } else if let Some(args) = matches.get_many::<String>("parameter-list") {
-            let command_names = command_names.map_or(vec![], |names| {
-                names.map(|v| v.as_str()).collect::<Vec<_>>()
-            });
-            let args: Vec<_> = args.map(|v| v.as_str()).collect::<Vec<_>>();
-            let param_names_and_values: Vec<(&str, Vec<String>)> = args
-                .chunks_exact(2)
-                .map(|pair| {
-                    let name = pair[0];
-                    let list_str = pair[1];
-                    (name, tokenize(list_str))
-                })
-                .collect();
-            {
-                let duplicates =
-                    Self::find_duplicates(param_names_and_values.iter().map(|(name, _)| *name));
-                if !duplicates.is_empty() {
-                    bail!("Duplicate parameter names: {}", &duplicates.join(", "));
-                }
-            }
-
-            let dimensions: Vec<usize> = std::iter::once(command_strings.len())
-                .chain(
-                    param_names_and_values
-                        .iter()
-                        .map(|(_, values)| values.len()),
-                )
-                .collect();
-            let param_space_size = dimensions.iter().product();
-            if param_space_size == 0 {
-                return Ok(Self(Vec::new()));
-            }
-
-            // `--command-name` should appear exactly once or exactly B times,
-            // where B is the total number of benchmarks.
-            let command_name_count = command_names.len();
-            if command_name_count > 1 && command_name_count != param_space_size {
-                return Err(OptionsError::UnexpectedCommandNameCount(
-                    command_name_count,
-                    param_space_size,
-                )
-                .into());
-            }
-
-            let mut i = 0;
-            let mut commands = Vec::with_capacity(param_space_size);
-            let mut index = vec![0usize; dimensions.len()];
-            'outer: loop {
-                let name = command_names
-                    .get(i)
-                    .or_else(|| command_names.first())
-                    .copied();
-                i += 1;
-
-                let (command_index, params_indices) = index.split_first().unwrap();
-                let parameters: Vec<_> = param_names_and_values
-                    .iter()
-                    .zip(params_indices)
-                    .map(|((name, values), i)| (*name, ParameterValue::Text(values[*i].clone())))
-                    .collect();
-                commands.push(Command::new_parametrized(
-                    name,
-                    command_strings[*command_index],
-                    parameters,
-                ));
-
-                // Increment index, exiting loop on overflow.
-                for (i, n) in index.iter_mut().zip(dimensions.iter()) {
-                    *i += 1;
-                    if *i < *n {
-                        continue 'outer;
-                    } else {
-                        *i = 0;
-                    }
-                }
-                break 'outer;
-            }
-
-            Ok(Self(commands))
+                parameters,
+                &zipped,
+                &filters,
+            )
         } else {
-            let command_names = command_names.map_or(vec![], |names| {
-                names.map(|v| v.as_str()).collect::<Vec<_>>()
-            });
             if command_names.len() > command_strings.len() {
                 return Err(OptionsError::TooManyCommandNames(command_strings.len()).into());
             }
@@ -225,7 +184,7 @@ impl<'a> Commands<'a> {
             for (i, s) in command_strings.iter().enumerate() {
                 commands.push(Command::new(command_names.get(i).copied(), s));
             }
-            Ok(Self(commands))
+            Ok(Self(commands, 0))
         }
     }
 
@@ -237,106 +196,159 @@ impl<'a> Commands<'a> {
         self.0.len()
     }
 
-    /// Finds all the strings that appear multiple times in the input iterator, returning them in
-    /// sorted order. If no string appears more than once, the result is an empty vector.
-    fn find_duplicates<'b, I: IntoIterator<Item = &'b str>>(i: I) -> Vec<&'b str> {
-        let mut counts = BTreeMap::<&'b str, usize>::new();
-        for s in i {
-            *counts.entry(s).or_default() += 1;
+    /// Number of parameter combinations that have been excluded by `--parameter-filter`
+    pub fn num_filtered_combinations(&self) -> usize {
+        self.1
+    }
+
+    /// Collects the parameters from all `PARAMETER_ARGS`, in the order in which they appear on
+    /// the command line.
+    fn get_parameter_values(matches: &'a ArgMatches) -> Result<Vec<ParameterNameAndValues<'a>>> {
+        let mut parameters = vec![];
+        for arg in PARAMETER_ARGS {
+            let num_args = if arg == "parameter-scan" { 3 } else { 2 };
+            for (index, args) in Self::occurrences(matches, arg, num_args) {
+                let (name, source) = (args[0], args[1]);
+                let values: Vec<_> = match arg {
+                    "parameter-scan" => {
+                        let spacing = Self::get_spacing(matches, name, index)?;
+                        scan_values(args[1], args[2], spacing)?
+                            .into_iter()
+                            .map(ParameterValue::Numeric)
+                            .collect()
+                    }
+                    "parameter-list" => text_values(tokenize(source)),
+                    "parameter-list-file" => text_values(source::values_from_file(source)?),
+                    _ => {
+                        let shell = Self::get_parameter_command_shell(matches)?;
+                        text_values(source::values_from_command(source, &shell)?)
+                    }
+                };
+                parameters.push((index, name, values));
+            }
         }
-        counts
+        parameters.sort_by_key(|(index, _, _)| *index);
+        Ok(parameters
+            .into_iter()
+            .map(|(_, name, values)| (name, values))
+            .collect())
+    }
+
+    /// The shell for the commands of `--parameter-list-cmd`: the first one given by `--shell`,
+    /// or the default shell if there is none (or with `--shell=none`)
+    fn get_parameter_command_shell(matches: &ArgMatches) -> Result<Shell> {
+        let shell = matches
+            .get_many::<String>("shell")
+            .and_then(|mut shells| shells.next());
+        Ok(match shell.map(|s| s.as_str()) {
+            Some("default") | Some("none") | None => Shell::default(),
+            _ if matches.get_flag("debug-mode") => Shell::default(),
+            Some(shell) => Shell::parse_from_str(shell)?,
+        })
+    }
+
+    /// Finds the spacing of the parameter scan whose arguments start at the given index. A
+    /// spacing option belongs to the closest `--parameter-scan` in front of it, or to the first
+    /// scan if it is given in front of all of them.
+    fn get_spacing(matches: &'a ArgMatches, name: &str, scan_index: usize) -> Result<Spacing<'a>> {
+        let scan_indices: Vec<_> = Self::occurrences(matches, "parameter-scan", 3)
             .into_iter()
-            .filter_map(|(k, n)| if n > 1 { Some(k) } else { None })
+            .map(|(index, _)| index)
+            .collect();
+        let owner = |index: usize| {
+            scan_indices
+                .iter()
+                .copied()
+                .rfind(|&i| i < index)
+                .unwrap_or(scan_indices[0])
+        };
+
+        let mut spacings = SPACING_ARGS.iter().flat_map(|&arg| {
+            Self::occurrences(matches, arg, 1)
+                .into_iter()
+                .filter(|(index, _)| owner(*index) == scan_index)
+                .map(move |(_, args)| match arg {
+                    "parameter-step-size" => Spacing::Step(Some(args[0])),
+                    "parameter-factor" => Spacing::Factor(args[0]),
+                    _ => Spacing::Points(args[0]),
+                })
+        });
+        let spacing = spacings.next().unwrap_or(Spacing::Step(None));
+        if spacings.next().is_some() {
+            bail!(
+                "The spacing of the parameter scan '{}' has been specified multiple times. Use \
+                 only one of '--parameter-step-size', '--parameter-factor' and \
+                 '--parameter-points' for every '--parameter-scan'",
+                name
+            );
+        }
+        Ok(spacing)
+    }
+
+    /// All occurrences of an option with the given number of arguments, together with the
+    /// position of the first argument on the command line
+    fn occurrences(
+        matches: &'a ArgMatches,
+        arg: &str,
+        num_args: usize,
+    ) -> Vec<(usize, Vec<&'a str>)> {
+        let values: Vec<_> = matches
+            .get_many::<String>(arg)
+            .unwrap_or_default()
+            .map(|v| v.as_str())
+            .collect();
+        let indices = matches.indices_of(arg).into_iter().flatten();
+        indices
+            .step_by(num_args)
+            .zip(values.chunks_exact(num_args).map(|args| args.to_vec()))
             .collect()
     }
 
-    fn build_parameter_scan_commands<'b, T: Numeric>(
-        param_name: &'b str,
-        param_min: T,
-        param_max: T,
-        step: T,
-        command_names: Vec<&'b str>,
-        command_strings: Vec<&'b str>,
-    ) -> Result<Vec<Command<'b>>, ParameterScanError> {
-        let param_range = RangeStep::new(param_min, param_max, step)?;
-        let param_count = param_range.size_hint().1.unwrap();
-        let command_name_count = command_names.len();
+    /// Build the commands for all parameter combinations, see `parameter::combination`
+    fn build_parametrized_commands(
+        command_names: Vec<&'a str>,
+        command_strings: Vec<&'a str>,
+        param_names_and_values: Vec<ParameterNameAndValues<'a>>,
+        zipped: &[Vec<&str>],
+        filters: &[Filter],
+    ) -> Result<Self> {
+        if command_strings.is_empty() {
+            return Ok(Self(Vec::new(), 0));
+        }
+        let (combinations, num_filtered) = combinations(param_names_and_values, zipped, filters)?;
+        if combinations.is_empty() && num_filtered > 0 {
+            return Err(OptionsError::AllParameterCombinationsFiltered.into());
+        }
 
         // `--command-name` should appear exactly once or exactly B times,
         // where B is the total number of benchmarks.
-        if command_name_count > 1 && command_name_count != param_count {
-            return Err(ParameterScanError::UnexpectedCommandNameCount(
+        let benchmark_count = combinations.len() * command_strings.len();
+        let command_name_count = command_names.len();
+        if command_name_count > 1 && command_name_count != benchmark_count {
+            return Err(OptionsError::UnexpectedCommandNameCount(
                 command_name_count,
-                param_count,
-            ));
+                benchmark_count,
+            )
+            .into());
         }
 
-        let mut i = 0;
-        let mut commands = vec![];
-        for value in param_range {
-            for cmd in &command_strings {
+        // The command index varies fastest
+        let mut commands = Vec::with_capacity(benchmark_count);
+        for parameters in combinations {
+            for command_string in &command_strings {
                 let name = command_names
-                    .get(i)
+                    .get(commands.len())
                     .or_else(|| command_names.first())
                     .copied();
                 commands.push(Command::new_parametrized(
                     name,
-                    cmd,
-                    vec![(param_name, ParameterValue::Numeric(value.into()))],
+                    command_string,
+                    parameters.clone(),
                 ));
-                i += 1;
             }
         }
-        Ok(commands)
-    }
-
-    fn get_parameter_scan_commands<'b>(
-        command_names: Option<ValuesRef<'b, String>>,
-        command_strings: Vec<&'b str>,
-        mut vals: ValuesRef<'b, String>,
-        step: Option<&str>,
-    ) -> Result<Vec<Command<'b>>, ParameterScanError> {
-        let command_names = command_names.map_or(vec![], |names| {
-            names.map(|v| v.as_str()).collect::<Vec<_>>()
-        });
-        let command_strings = command_strings;
-        let param_name = vals.next().unwrap().as_str();
-        let param_min = vals.next().unwrap().as_str();
-        let param_max = vals.next().unwrap().as_str();
-
-        // attempt to parse as integers
-        if let (Ok(param_min), Ok(param_max), Ok(step)) = (
-            param_min.parse::<i32>(),
-            param_max.parse::<i32>(),
-            step.unwrap_or("1").parse::<i32>(),
-        ) {
-            return Self::build_parameter_scan_commands(
-                param_name,
-                param_min,
-                param_max,
-                step,
-                command_names,
-                command_strings,
-            );
-        }
-
-        // try parsing them as decimals
-        let param_min = Decimal::from_str(param_min)?;
-        let param_max = Decimal::from_str(param_max)?;
-
-        if step.is_none() {
-            return Err(ParameterScanError::StepRequired);
-        }
 
-        let step = Decimal::from_str(step.unwrap())?;
-        Self::build_parameter_scan_commands(
-            param_name,
-            param_min,
-            param_max,
-            step,
-            command_names,
-            command_strings,
-        )
+        Ok(Self(commands, num_filtered))
     }
 }
 
@@ -454,17 +466,26 @@ fn test_build_parameter_scan_commands() {
     assert_eq!(commands[1].get_command_line(), "echo 2");
 }
 
+#[cfg(test)]
+fn scan_parameter(min: &str, max: &str, step: &str) -> Vec<ParameterValue> {
+    scan_values(min, max, Spacing::Step(Some(step)))
+        .unwrap()
+        .into_iter()
+        .map(ParameterValue::Numeric)
+        .collect()
+}
+
 #[test]
 fn test_parameter_scan_commands_int() {
-    let commands = Commands::build_parameter_scan_commands(
-        "val",
-        1i32,
-        7i32,
-        3i32,
+    let commands = Commands::build_parametrized_commands(
         vec![],
         vec!["echo {val}"],
+        vec![("val", scan_parameter("1", "7", "3"))],
+        &[],
+        &[],
     )
-    .unwrap();
+    .unwrap()
+    .0;
     assert_eq!(commands.len(), 3);
     assert_eq!(commands[2].get_name(), "echo 7");
     assert_eq!(commands[2].get_command_line(), "echo 7");
@@ -472,19 +493,15 @@ fn test_parameter_scan_commands_int() {
 
 #[test]
 fn test_parameter_scan_commands_decimal() {
-    let param_min = Decimal::from_str("0").unwrap();
-    let param_max = Decimal::from_str("1").unwrap();
-    let step = Decimal::from_str("0.33").unwrap();
-
-    let commands = Commands::build_parameter_scan_commands(
-        "val",
-        param_min,
-        param_max,
-        step,
+    let commands = Commands::build_parametrized_commands(
         vec![],
         vec!["echo {val}"],
+        vec![("val", scan_parameter("0", "1", "0.33"))],
+        &[],
+        &[],
     )
-    .unwrap();
+    .unwrap()
+    .0;
     assert_eq!(commands.len(), 4);
     assert_eq!(commands[3].get_name(), "echo 0.99");
     assert_eq!(commands[3].get_command_line(), "echo 0.99");
@@ -492,15 +509,15 @@ fn test_parameter_scan_commands_decimal() {
 
 #[test]
 fn test_parameter_scan_commands_names() {
-    let commands = Commands::build_parameter_scan_commands(
-        "val",
-        1i32,
-        3i32,
-        1i32,
+    let commands = Commands::build_parametrized_commands(
         vec!["name-{val}"],
         vec!["echo {val}"],
+        vec![("val", scan_parameter("1", "3", "1"))],
+        &[],
+        &[],
     )
-    .unwrap();
+    .unwrap()
+    .0;
     assert_eq!(commands.len(), 3);
     let command_names = commands
         .iter()
@@ -511,15 +528,15 @@ fn test_parameter_scan_commands_names() {
 
 #[test]
 fn test_get_specified_command_names() {
-    let commands = Commands::build_parameter_scan_commands(
-        "val",
-        1i32,
-        3i32,
-        1i32,
+    let commands = Commands::build_parametrized_commands(
         vec!["name-a", "name-b", "name-c"],
         vec!["echo {val}"],
+        vec![("val", scan_parameter("1", "3", "1"))],
+        &[],
+        &[],
     )
-    .unwrap();
+    .unwrap()
+    .0;
     assert_eq!(commands.len(), 3);
     let command_names = commands
         .iter()
@@ -530,16 +547,267 @@ fn test_get_specified_command_names() {
 
 #[test]
 fn test_different_command_name_count_with_parameters() {
-    let result = Commands::build_parameter_scan_commands(
-        "val",
-        1i32,
-        3i32,
-        1i32,
+    let result = Commands::build_parametrized_commands(
         vec!["name-1", "name-2"],
         vec!["echo {val}"],
+        vec![("val", scan_parameter("1", "3", "1"))],
+        &[],
+        &[],
+    );
+    assert_eq!(
+        result.err().unwrap().to_string(),
+        "'--command-name' has been specified 2 times. It has to appear exactly once, or exactly 3 \
+         times (number of benchmarks)"
+    );
+}
+
+#[test]
+fn test_command_names_for_multiple_commands_with_parameters() {
+    let commands = Commands::build_parametrized_commands(
+        vec!["a-{val}", "b-{val}", "c-{val}", "d-{val}"],
+        vec!["echo a {val}", "echo b {val}"],
+        vec![("val", scan_parameter("1", "2", "1"))],
+        &[],
+        &[],
+    )
+    .unwrap()
+    .0;
+    let command_names = commands
+        .iter()
+        .map(|c| c.get_name())
+        .collect::<Vec<String>>();
+    assert_eq!(command_names, vec!["a-1", "b-1", "c-2", "d-2"]);
+}
+
+#[test]
+fn test_multiple_parameter_scans_and_lists() {
+    use crate::cli::get_cli_arguments;
+    let matches = get_cli_arguments(vec![
+        "hyperfine",
+        "--parameter-scan",
+        "threads",
+        "1",
+        "2",
+        "--parameter-list",
+        "mode",
+        "fast,slow",
+        "--parameter-scan",
+        "size",
+        "10",
+        "1000",
+        "--parameter-factor",
+        "100",
+        "run -j {threads} -n {size} --{mode}",
+    ]);
+    let commands = Commands::from_cli_arguments(&matches).unwrap().0;
+    let command_lines = commands
+        .iter()
+        .map(|c| c.get_command_line())
+        .collect::<Vec<String>>();
+    assert_eq!(
+        command_lines,
+        vec![
+            "run -j 1 -n 10 --fast",
+            "run -j 2 -n 10 --fast",
+            "run -j 1 -n 10 --slow",
+            "run -j 2 -n 10 --slow",
+            "run -j 1 -n 1000 --fast",
+            "run -j 2 -n 1000 --fast",
+            "run -j 1 -n 1000 --slow",
+            "run -j 2 -n 1000 --slow",
+        ]
+    );
+    let parameter_names = commands[0]
+        .get_parameters()
+        .iter()
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>();
+    assert_eq!(parameter_names, vec!["threads", "mode", "size"]);
+}
+
+#[test]
+fn test_spacing_belongs_to_preceding_parameter_scan() {
+    use crate::cli::get_cli_arguments;
+    let matches = get_cli_arguments(vec![
+        "hyperfine",
+        "-D",
+        "0.5",
+        "-P",
+        "x",
+        "0",
+        "1",
+        "-P",
+        "y",
+        "1",
+        "4",
+        "--parameter-points",
+        "3",
+        "echo {x} {y}",
+    ]);
+    let commands = Commands::from_cli_arguments(&matches).unwrap().0;
+    assert_eq!(commands.len(), 9);
+    assert_eq!(commands[0].get_command_line(), "echo 0 1");
+    assert_eq!(commands[1].get_command_line(), "echo 0.5 1");
+    assert_eq!(commands[8].get_command_line(), "echo 1.0 4");
+
+    let matches = get_cli_arguments(vec![
+        "hyperfine",
+        "-P",
+        "x",
+        "1",
+        "4",
+        "-D",
+        "1",
+        "--parameter-factor",
+        "2",
+        "echo {x}",
+    ]);
+    assert!(Commands::from_cli_arguments(&matches)
+        .err()
+        .unwrap()
+        .to_string()
+        .starts_with("The spacing of the parameter scan 'x' has been specified multiple times"));
+}
+
+#[test]
+fn test_zipped_parameters() {
+    use crate::cli::get_cli_arguments;
+    let matches = get_cli_arguments(vec![
+        "hyperfine",
+        "-L",
+        "version",
+        "1.0,1.1",
+        "-L",
+        "flag",
+        "-a,-b",
+        "-L",
+        "binary",
+        "/opt/a,/opt/b",
+        "--parameter-zip",
+        "version, binary",
+        "{binary} {flag} # {version}",
+    ]);
+    let commands = Commands::from_cli_arguments(&matches).unwrap().0;
+    let command_lines = commands
+        .iter()
+        .map(|c| c.get_command_line())
+        .collect::<Vec<String>>();
+    assert_eq!(
+        command_lines,
+        vec![
+            "/opt/a -a # 1.0",
+            "/opt/b -a # 1.1",
+            "/opt/a -b # 1.0",
+            "/opt/b -b # 1.1",
+        ]
+    );
+}
+
+#[test]
+fn test_invalid_zipped_parameters() {
+    use crate::cli::get_cli_arguments;
+    let error = |zip: &str| {
+        let matches = get_cli_arguments(vec![
+            "hyperfine",
+            "-L",
+            "a",
+            "1,2",
+            "-L",
+            "b",
+            "1,2,3",
+            "-L",
+            "c",
+            "1,2",
+            "--parameter-zip",
+            zip,
+            "echo {a} {b} {c}",
+        ]);
+        Commands::from_cli_arguments(&matches)
+            .err()
+            .unwrap()
+            .to_string()
+    };
+
+    assert_eq!(
+        error("a,b"),
+        "The zipped parameters 'a' and 'b' have different numbers of values (2 and 3)"
+    );
+    assert_eq!(error("a,d"), "Unknown parameter 'd' in '--parameter-zip'");
+    assert_eq!(
+        error("a,a"),
+        "Parameters can only be zipped once, but '--parameter-zip' contains a"
+    );
+}
+
+#[test]
+fn test_filtered_parameter_combinations() {
+    use crate::cli::get_cli_arguments;
+    let matches = get_cli_arguments(vec![
+        "hyperfine",
+        "-L",
+        "threads",
+        "1,4,16",
+        "-L",
+        "mode",
+        "single,multi",
+        "--parameter-filter",
+        "threads == 1 || mode != 'single'",
+        "--command-name",
+        "a",
+        "--command-name",
+        "b",
+        "--command-name",
+        "c",
+        "--command-name",
+        "d",
+        "run --{mode} -j {threads}",
+    ]);
+    let commands = Commands::from_cli_arguments(&matches).unwrap();
+    assert_eq!(commands.num_filtered_combinations(), 2);
+    let command_lines = commands
+        .iter()
+        .map(|c| c.get_command_line())
+        .collect::<Vec<String>>();
+    assert_eq!(
+        command_lines,
+        vec![
+            "run --single -j 1",
+            "run --multi -j 1",
+            "run --multi -j 4",
+            "run --multi -j 16",
+        ]
+    );
+    assert_eq!(commands.0[3].get_name(), "d");
+
+    let matches = get_cli_arguments(vec![
+        "hyperfine",
+        "-L",
+        "mode",
+        "single,multi",
+        "--parameter-filter",
+        "mode != single",
+        "run --{mode}",
+    ]);
+    assert!(Commands::from_cli_arguments(&matches)
+        .err()
+        .unwrap()
+        .to_string()
+        .starts_with("Unknown parameter 'single' in '--parameter-filter'"));
+
+    let matches = get_cli_arguments(vec![
+        "hyperfine",
+        "-L",
+        "mode",
+        "single,multi",
+        "--parameter-filter",
+        "mode == 'none'",
+        "run --{mode}",
+    ]);
+    assert_eq!(
+        Commands::from_cli_arguments(&matches)
+            .err()
+            .unwrap()
+            .to_string(),
+        "All parameter combinations were excluded by '--parameter-filter'"
     );
-    assert!(matches!(
-        result.unwrap_err(),
-        ParameterScanError::UnexpectedCommandNameCount(2, 3)
-    ));
 }
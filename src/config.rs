@@ -0,0 +1,1137 @@
+//! Benchmark suites described in a TOML configuration file (`--config <FILE>`).
+//!
+//! The top-level keys of the file are the long names of the command-line options (e.g.
+//! `warmup = 3` or `ignore-failure = true`). Commands, parameters and exports are described in
+//! dedicated tables:
+//!
+//! ```toml
+//! warmup = 3
+//! setup = "make"
+//!
+//! [[command]]
+//! name = "grep"
+//! command = "grep -r {pattern} src"
+//! prepare = "sync"
+//!
+//! [[command]]
+//! command = "rg {pattern}"
+//! prepare = "sync"
+//! working-dir = "src"
+//!
+//! [[parameter-list]]
+//! name = "pattern"
+//! values = ["TODO", "FIXME"]
+//!
+//! [export]
+//! json = "results.json"
+//! ```
+//!
+//! The options `setup`, `prepare`, `conclude`, `cleanup`, `warmup`, `shell`, `working-dir`,
+//! `input` and `accept-exit-codes` can also be given for every single command. Commands given on
+//! the command line are benchmarked after the ones from the file and use the top-level values
+//! of these options. Environment variables are given as tables (`env = { LANG = "C" }`), both
+//! at the top level and for single commands.
+//!
+//! The configuration is translated into the equivalent command-line arguments, so it is
+//! subject to exactly the same validation. Options given on the command line take precedence
+//! over the ones in the configuration file.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+
+use anyhow::{bail, Context, Result};
+use clap::parser::ValueSource;
+use clap::{ArgAction, ArgMatches};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use toml::Value;
+
+use crate::cli::build_command;
+use crate::export::ExtraColumn;
+use crate::options::check_option_value;
+use crate::parameter::combination::combinations;
+use crate::parameter::filter::Filter;
+use crate::parameter::scan::{scan_values, Spacing};
+use crate::parameter::ParameterValue;
+
+/// Options that can not be given as top-level keys, as they have a dedicated representation
+const RESERVED_KEYS: &[&str] = &[
+    "command",
+    "command-name",
+    "config",
+    "parameter-scan",
+    "parameter-step-size",
+    "parameter-factor",
+    "parameter-points",
+    "parameter-list",
+    "parameter-list-file",
+    "parameter-list-cmd",
+    "parameter-zip",
+    "parameter-filter",
+];
+
+/// Options that can be given for every single command, as keys of the `[[command]]` tables.
+/// A top-level key with the same name sets the value for all other commands.
+const PER_COMMAND_KEYS: &[&str] = &[
+    "setup",
+    "prepare",
+    "conclude",
+    "cleanup",
+    "warmup",
+    "shell",
+    "working-dir",
+    "input",
+    "accept-exit-codes",
+];
+
+/// A benchmarked command (`[[command]]`)
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+struct CommandConfig {
+    /// Name of the command in the output and the exports
+    name: Option<String>,
+
+    /// The command line to benchmark
+    command: String,
+
+    setup: Option<String>,
+    prepare: Option<String>,
+    conclude: Option<String>,
+    cleanup: Option<String>,
+    warmup: Option<u64>,
+    shell: Option<String>,
+    working_dir: Option<String>,
+    input: Option<String>,
+    accept_exit_codes: Option<StringOrInteger>,
+
+    /// Environment variables to set for this command only
+    #[serde(default)]
+    env: BTreeMap<String, Value>,
+
+    /// Environment variables to remove for this command only
+    #[serde(default)]
+    unset_env: Vec<String>,
+}
+
+impl CommandConfig {
+    /// Value of one of the `PER_COMMAND_KEYS`
+    fn get(&self, key: &str) -> Option<String> {
+        match key {
+            "setup" => self.setup.clone(),
+            "prepare" => self.prepare.clone(),
+            "conclude" => self.conclude.clone(),
+            "cleanup" => self.cleanup.clone(),
+            "warmup" => self.warmup.map(|warmup| warmup.to_string()),
+            "shell" => self.shell.clone(),
+            "working-dir" => self.working_dir.clone(),
+            "input" => self.input.clone(),
+            "accept-exit-codes" => self.accept_exit_codes.as_ref().map(ToString::to_string),
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// A value that can be given as a string or as an integer, like `accept-exit-codes = 1`
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum StringOrInteger {
+    String(String),
+    Integer(i64),
+}
+
+impl fmt::Display for StringOrInteger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StringOrInteger::String(s) => write!(f, "{}", s),
+            StringOrInteger::Integer(i) => write!(f, "{}", i),
+        }
+    }
+}
+
+/// Value of an option that is used for commands without an explicit value, if no top-level
+/// key is given either
+fn implicit_default(key: &str) -> Option<&'static str> {
+    match key {
+        "warmup" => Some("0"),
+        "shell" => Some("default"),
+        "working-dir" => Some("."),
+        "input" => Some("null"),
+        "accept-exit-codes" => Some("0"),
+        _ => None,
+    }
+}
+
+/// Keys of the `[parameter-scan]` table that determine the spacing of the values, and the
+/// corresponding command-line options
+const SCAN_SPACING_KEYS: [(&str, &str); 3] = [
+    ("step", "parameter-step-size"),
+    ("factor", "parameter-factor"),
+    ("points", "parameter-points"),
+];
+
+/// Parameter scan over a numeric range (`[parameter-scan]` or `[[parameter-scan]]`)
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ParameterScanConfig {
+    name: String,
+    min: Value,
+    max: Value,
+    step: Option<Value>,
+    factor: Option<Value>,
+    points: Option<Value>,
+}
+
+/// Parameter with a list of values (`[[parameter-list]]`)
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ParameterListConfig {
+    name: String,
+    values: Vec<Value>,
+}
+
+/// Export formats and the corresponding output files (`[export]`)
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ExportConfig {
+    asciidoc: Option<String>,
+    csv: Option<String>,
+    json: Option<String>,
+    markdown: Option<String>,
+    ndjson: Option<String>,
+    orgmode: Option<String>,
+    columns: Option<Vec<String>>,
+}
+
+/// A benchmark suite read from a configuration file
+#[derive(Debug, Default)]
+pub struct Config {
+    commands: Vec<CommandConfig>,
+    parameter_scans: Vec<ParameterScanConfig>,
+    parameter_lists: Vec<ParameterListConfig>,
+    parameter_zip: Vec<Vec<String>>,
+    parameter_filters: Vec<String>,
+    export: ExportConfig,
+
+    /// The remaining top-level keys and the corresponding command-line options
+    options: Vec<(String, String)>,
+
+    /// Values of the top-level `PER_COMMAND_KEYS`
+    shared: BTreeMap<String, String>,
+}
+
+impl Config {
+    /// Read a configuration file and translate it into command-line arguments. Options that
+    /// are overridden by the given command-line arguments are skipped.
+    pub fn cli_arguments_from_file(path: &str, matches: &ArgMatches) -> Result<Vec<String>> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read configuration file '{}'", path))?;
+
+        let cli = build_command();
+        let is_overridden = |key: &str| is_overridden(&cli, matches, key);
+        let cli_commands: Vec<&str> = matches
+            .get_many::<String>("command")
+            .unwrap_or_default()
+            .map(String::as_str)
+            .collect();
+
+        Self::parse(&content)
+            .and_then(|config| config.to_cli_arguments(&is_overridden, &cli_commands))
+            .with_context(|| format!("Invalid configuration file '{}'", path))
+    }
+
+    fn parse(content: &str) -> Result<Config> {
+        let table: toml::value::Table = toml::from_str(content)?;
+
+        let cli = build_command();
+        let mut config = Config::default();
+
+        for (key, value) in table {
+            match key.as_str() {
+                "command" => {
+                    config.commands = match value {
+                        Value::Array(commands) => commands
+                            .into_iter()
+                            .enumerate()
+                            .map(|(i, command)| deserialize(command, &format!("command[{}]", i)))
+                            .collect::<Result<_>>()?,
+                        _ => bail!("Key `command` has to be an array of tables ('[[command]]')"),
+                    };
+                    for (i, command) in config.commands.iter().enumerate() {
+                        for &key in PER_COMMAND_KEYS {
+                            if let Some(value) = command.get(key) {
+                                check_value(&cli, key, &value).with_context(|| {
+                                    format!("Invalid value for key `command[{}].{}`", i, key)
+                                })?;
+                            }
+                        }
+                    }
+                }
+                "parameter-scan" => {
+                    config.parameter_scans = match value {
+                        Value::Array(_) => deserialize(value, &key)?,
+                        _ => vec![deserialize(value, &key)?],
+                    }
+                }
+                "parameter-list" => config.parameter_lists = deserialize(value, &key)?,
+                "parameter-filter" => {
+                    config.parameter_filters = match value {
+                        Value::Array(_) => deserialize(value, &key)?,
+                        _ => vec![deserialize(value, &key)?],
+                    }
+                }
+                "parameter-zip" => {
+                    // Either a single group of names or an array of groups
+                    let groups: Vec<Value> = deserialize(value.clone(), &key)?;
+                    config.parameter_zip = match groups.first() {
+                        Some(Value::Array(_)) => deserialize(value, &key)?,
+                        _ => vec![deserialize(value, &key)?],
+                    }
+                }
+                "export" => {
+                    config.export = deserialize(value, &key)?;
+                    if let Some(columns) = &config.export.columns {
+                        check_export_columns(columns.iter().map(String::as_str))
+                            .context("Invalid value for key `export.columns`")?;
+                    }
+                }
+                "env" => {
+                    let variables: BTreeMap<String, Value> = deserialize(value, &key)?;
+                    for (name, value) in variables {
+                        let value = to_argument(value, &format!("env.{}", name))?;
+                        let option = format!("--env={}={}", name, value);
+                        config.options.push((key.clone(), option));
+                    }
+                }
+                key if PER_COMMAND_KEYS.contains(&key) => {
+                    let value = to_argument(value, key)?;
+                    check_value(&cli, key, &value)
+                        .with_context(|| format!("Invalid value for key `{}`", key))?;
+                    config.shared.insert(key.to_string(), value);
+                }
+                _ => {
+                    let arg = cli.get_arguments().find(|arg| {
+                        arg.get_long() == Some(key.as_str())
+                            && !RESERVED_KEYS.contains(&key.as_str())
+                    });
+                    let arg = match arg {
+                        Some(arg) => arg,
+                        None => bail!("Unknown key `{}`", key),
+                    };
+
+                    if let ArgAction::SetTrue = arg.get_action() {
+                        match value {
+                            Value::Boolean(true) => {
+                                let option = format!("--{}", key);
+                                config.options.push((key, option));
+                            }
+                            Value::Boolean(false) => {}
+                            _ => bail!("The value of key `{}` has to be a boolean", key),
+                        }
+                    } else {
+                        let value = match value {
+                            Value::Array(values) if arg.get_value_delimiter().is_some() => values
+                                .into_iter()
+                                .map(|v| to_argument(v, &key))
+                                .collect::<Result<Vec<_>>>()?
+                                .join(","),
+                            value => to_argument(value, &key)?,
+                        };
+                        check_value(&cli, &key, &value)
+                            .with_context(|| format!("Invalid value for key `{}`", key))?;
+                        let option = format!("--{}={}", key, value);
+                        config.options.push((key, option));
+                    }
+                }
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Number of benchmarks per command, i.e. the number of parameter combinations
+    fn number_of_parameter_combinations(&self) -> Result<usize> {
+        let mut parameters = vec![];
+        for scan in &self.parameter_scans {
+            let values = scan
+                .values()
+                .context("Invalid value for key `parameter-scan`")?;
+            parameters.push((scan.name.as_str(), values));
+        }
+        for (i, list) in self.parameter_lists.iter().enumerate() {
+            let key = format!("parameter-list[{}].values", i);
+            let values = list
+                .values
+                .iter()
+                .map(|value| Ok(ParameterValue::Text(to_argument(value.clone(), &key)?)))
+                .collect::<Result<_>>()?;
+            parameters.push((list.name.as_str(), values));
+        }
+
+        let zipped: Vec<Vec<&str>> = self
+            .parameter_zip
+            .iter()
+            .map(|group| group.iter().map(String::as_str).collect())
+            .collect();
+        let filters = self
+            .parameter_filters
+            .iter()
+            .map(|filter| Filter::parse(filter))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(combinations(parameters, &zipped, &filters)?.0.len())
+    }
+
+    /// Translate the configuration into command-line arguments. The commands given on the
+    /// command line are benchmarked after the ones from the configuration file, with the
+    /// top-level values of the per-command options.
+    fn to_cli_arguments(
+        &self,
+        is_overridden: &dyn Fn(&str) -> bool,
+        cli_commands: &[&str],
+    ) -> Result<Vec<String>> {
+        let mut args: Vec<String> = self
+            .options
+            .iter()
+            .filter(|(key, _)| !is_overridden(key))
+            .map(|(_, option)| option.clone())
+            .collect();
+
+        let parameters_overridden = [
+            "parameter-scan",
+            "parameter-list",
+            "parameter-list-file",
+            "parameter-list-cmd",
+        ]
+        .iter()
+        .any(|key| is_overridden(key));
+
+        for scan in &self.parameter_scans {
+            if parameters_overridden {
+                break;
+            }
+            args.push("--parameter-scan".into());
+            args.push(scan.name.clone());
+            args.push(to_argument(scan.min.clone(), "parameter-scan.min")?);
+            args.push(to_argument(scan.max.clone(), "parameter-scan.max")?);
+            for (key, option) in SCAN_SPACING_KEYS {
+                if let Some(value) = scan.spacing_argument(key)? {
+                    args.push(format!("--{}={}", option, value));
+                }
+            }
+        }
+
+        for (i, list) in self.parameter_lists.iter().enumerate() {
+            if parameters_overridden {
+                break;
+            }
+            let key = format!("parameter-list[{}].values", i);
+            let values = list
+                .values
+                .iter()
+                .map(|value| Ok(escape_list_value(&to_argument(value.clone(), &key)?)))
+                .collect::<Result<Vec<_>>>()?;
+            args.push("--parameter-list".into());
+            args.push(list.name.clone());
+            args.push(values.join(","));
+        }
+
+        if !parameters_overridden && !is_overridden("parameter-zip") {
+            for group in &self.parameter_zip {
+                args.push(format!("--parameter-zip={}", group.join(",")));
+            }
+        }
+        if !parameters_overridden && !is_overridden("parameter-filter") {
+            for filter in &self.parameter_filters {
+                args.push(format!("--parameter-filter={}", filter));
+            }
+        }
+
+        let exports = [
+            ("export-asciidoc", &self.export.asciidoc),
+            ("export-csv", &self.export.csv),
+            ("export-json", &self.export.json),
+            ("export-markdown", &self.export.markdown),
+            ("export-ndjson", &self.export.ndjson),
+            ("export-orgmode", &self.export.orgmode),
+        ];
+        for (key, path) in exports {
+            if let (Some(path), false) = (path, is_overridden(key)) {
+                args.push(format!("--{}={}", key, path));
+            }
+        }
+        if let (Some(columns), false) = (&self.export.columns, is_overridden("export-columns")) {
+            args.push(format!("--export-columns={}", columns.join(",")));
+        }
+
+        // Per-command options have to be given for every single benchmark, i.e. repeated for
+        // each parameter combination. The command index varies fastest.
+        let repetitions = if parameters_overridden {
+            1
+        } else {
+            self.number_of_parameter_combinations()?
+        };
+
+        if self.commands.iter().any(|command| command.name.is_some())
+            && !is_overridden("command-name")
+        {
+            for _ in 0..repetitions {
+                for command in &self.commands {
+                    let name = command.name.as_ref().unwrap_or(&command.command);
+                    args.push(format!("--command-name={}", name));
+                }
+                for command in cli_commands {
+                    args.push(format!("--command-name={}", command));
+                }
+            }
+        }
+
+        for &key in PER_COMMAND_KEYS {
+            if is_overridden(key) {
+                continue;
+            }
+
+            let shared = self.shared.get(key).map(String::as_str);
+            if self
+                .commands
+                .iter()
+                .any(|command| command.get(key).is_some())
+            {
+                let mut values = self
+                    .commands
+                    .iter()
+                    .enumerate()
+                    .map(|(i, command)| {
+                        match command.get(key) {
+                            Some(value) => Some(value),
+                            None => shared.or_else(|| implicit_default(key)).map(String::from),
+                        }
+                        .with_context(|| {
+                            format!(
+                                "Missing key `command[{}].{}`. The '{}' option has to be given \
+                                 for all commands (or as a top-level key).",
+                                i, key, key
+                            )
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                for _ in cli_commands {
+                    let value = shared.or_else(|| implicit_default(key)).with_context(|| {
+                        format!(
+                            "The '{}' option is given for single commands in the configuration \
+                             file. Add a top-level `{}` key for the commands on the command line.",
+                            key, key
+                        )
+                    })?;
+                    values.push(value.to_string());
+                }
+                for _ in 0..repetitions {
+                    for value in &values {
+                        args.push(format!("--{}={}", key, value));
+                    }
+                }
+            } else if let Some(value) = shared {
+                args.push(format!("--{}={}", key, value));
+            }
+        }
+
+        // Environment variables of single commands are changed by prefixing them with the
+        // (one-based) benchmark number
+        let commands_per_repetition = self.commands.len() + cli_commands.len();
+        for repetition in 0..repetitions {
+            for (i, command) in self.commands.iter().enumerate() {
+                let number = repetition * commands_per_repetition + i + 1;
+                if !is_overridden("unset-env") {
+                    for name in &command.unset_env {
+                        args.push(format!("--unset-env={}:{}", number, name));
+                    }
+                }
+                if !is_overridden("env") {
+                    for (name, value) in &command.env {
+                        let key = format!("command[{}].env.{}", i, name);
+                        let value = to_argument(value.clone(), &key)?;
+                        args.push(format!("--env={}:{}={}", number, name, value));
+                    }
+                }
+            }
+        }
+
+        args.extend(self.commands.iter().map(|command| command.command.clone()));
+
+        Ok(args)
+    }
+}
+
+impl ParameterScanConfig {
+    fn spacing_argument(&self, key: &str) -> Result<Option<String>> {
+        let value = match key {
+            "step" => &self.step,
+            "factor" => &self.factor,
+            _ => &self.points,
+        };
+        value
+            .clone()
+            .map(|value| to_argument(value, &format!("parameter-scan.{}", key)))
+            .transpose()
+    }
+
+    /// Values in the scanned range, see `parameter::scan::scan_values`
+    fn values(&self) -> Result<Vec<ParameterValue>> {
+        let min = to_argument(self.min.clone(), "parameter-scan.min")?;
+        let max = to_argument(self.max.clone(), "parameter-scan.max")?;
+        let step = self.spacing_argument("step")?;
+        let factor = self.spacing_argument("factor")?;
+        let points = self.spacing_argument("points")?;
+
+        let spacing = match (step.is_some(), &factor, &points) {
+            (false, Some(factor), None) => Spacing::Factor(factor),
+            (false, None, Some(points)) => Spacing::Points(points),
+            (_, None, None) => Spacing::Step(step.as_deref()),
+            _ => bail!("Only one of the keys `step`, `factor` and `points` can be given"),
+        };
+        Ok(scan_values(&min, &max, spacing)?
+            .into_iter()
+            .map(ParameterValue::Numeric)
+            .collect())
+    }
+}
+
+/// Whether the option with the given key is overridden by the command-line arguments, i.e. if
+/// the option itself or a conflicting one is given on the command line.
+fn is_overridden(cli: &clap::Command, matches: &ArgMatches, key: &str) -> bool {
+    let option = match cli.get_arguments().find(|arg| arg.get_id() == key) {
+        Some(option) => option,
+        None => return false,
+    };
+
+    cli.get_arguments()
+        .filter(|arg| !arg.is_positional())
+        .filter(|arg| matches.value_source(arg.get_id().as_str()) == Some(ValueSource::CommandLine))
+        .any(|arg| {
+            arg.get_id() == key
+                || cli.get_arg_conflicts_with(option).contains(&arg)
+                || cli.get_arg_conflicts_with(arg).contains(&option)
+        })
+}
+
+/// Check the value of an option on its own, such that an invalid value is reported together
+/// with its key. Problems that involve several options are reported when the complete command
+/// line is parsed.
+fn check_value(cli: &clap::Command, key: &str, value: &str) -> Result<()> {
+    let arg = match cli.get_arguments().find(|arg| arg.get_long() == Some(key)) {
+        Some(arg) => arg,
+        None => return Ok(()),
+    };
+
+    let possible_values: Vec<_> = arg
+        .get_possible_values()
+        .iter()
+        .map(|value| value.get_name().to_string())
+        .collect();
+    if !possible_values.is_empty() && !possible_values.iter().any(|v| v == value) {
+        bail!(
+            "'{}' is not one of the possible values ({})",
+            value,
+            possible_values.join(", ")
+        );
+    }
+
+    if key == "export-columns" {
+        return check_export_columns(value.split(','));
+    }
+
+    // The error borrows the key, so it is converted to a message
+    if let Err(error) = check_option_value(key, value) {
+        bail!("{}", error);
+    }
+    Ok(())
+}
+
+/// Check the names of the extra columns of the exports
+fn check_export_columns<'a>(mut names: impl Iterator<Item = &'a str>) -> Result<()> {
+    names.try_for_each(|name| ExtraColumn::parse(name).map(drop))
+}
+
+/// Deserialize the value of the given key
+fn deserialize<T: DeserializeOwned>(value: Value, key: &str) -> Result<T> {
+    value
+        .try_into()
+        .with_context(|| format!("Invalid value for key `{}`", key))
+}
+
+/// Convert a single value to a command-line argument
+fn to_argument(value: Value, key: &str) -> Result<String> {
+    match value {
+        Value::String(s) => Ok(s),
+        Value::Integer(i) => Ok(i.to_string()),
+        Value::Float(f) => Ok(f.to_string()),
+        _ => bail!(
+            "The value of key `{}` has to be a string or a number, but is {}",
+            key,
+            value.type_str()
+        ),
+    }
+}
+
+/// Escape a value for `--parameter-list`, see `parameter::tokenize`
+fn escape_list_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace(',', "\\,")
+}
+
+#[test]
+fn test_translates_options_to_cli_arguments() {
+    let config = Config::parse(
+        r#"
+        warmup = 3
+        ignore-failure = true
+        show-output = false
+        export-columns = ["minor-page-faults", "voluntary-context-switches"]
+        prepare = "sync"
+
+        [[command]]
+        command = "sleep 0.1"
+
+        [[command]]
+        command = "sleep 0.2"
+
+        [export]
+        json = "results.json"
+        ndjson = "events.ndjson"
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        config.to_cli_arguments(&|_| false, &[]).unwrap(),
+        vec![
+            "--export-columns=minor-page-faults,voluntary-context-switches",
+            "--ignore-failure",
+            "--export-json=results.json",
+            "--export-ndjson=events.ndjson",
+            "--prepare=sync",
+            "--warmup=3",
+            "sleep 0.1",
+            "sleep 0.2",
+        ]
+    );
+}
+
+#[test]
+fn test_repeats_per_command_options_for_every_parameter_value() {
+    let config = Config::parse(
+        r#"
+        [[command]]
+        name = "a-{n}"
+        command = "a {n}"
+        prepare = "prepare a"
+
+        [[command]]
+        command = "b {n}"
+        prepare = "prepare b"
+
+        [parameter-scan]
+        name = "n"
+        min = 1
+        max = 2
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        config.to_cli_arguments(&|_| false, &[]).unwrap(),
+        vec![
+            "--parameter-scan",
+            "n",
+            "1",
+            "2",
+            "--command-name=a-{n}",
+            "--command-name=b {n}",
+            "--command-name=a-{n}",
+            "--command-name=b {n}",
+            "--prepare=prepare a",
+            "--prepare=prepare b",
+            "--prepare=prepare a",
+            "--prepare=prepare b",
+            "a {n}",
+            "b {n}",
+        ]
+    );
+}
+
+#[test]
+fn test_translates_geometric_parameter_scan() {
+    let config = Config::parse(
+        r#"
+        prepare = "sync"
+
+        [[command]]
+        command = "head -c {size} /dev/zero"
+        prepare = "sync"
+
+        [parameter-scan]
+        name = "size"
+        min = 1
+        max = 1000
+        factor = 10
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        config.to_cli_arguments(&|_| false, &[]).unwrap(),
+        vec![
+            "--parameter-scan",
+            "size",
+            "1",
+            "1000",
+            "--parameter-factor=10",
+            "--prepare=sync",
+            "--prepare=sync",
+            "--prepare=sync",
+            "--prepare=sync",
+            "head -c {size} /dev/zero",
+        ]
+    );
+
+    let config = Config::parse(
+        r#"
+        [[command]]
+        command = "sleep {t}"
+        prepare = "sync"
+
+        [parameter-scan]
+        name = "t"
+        min = 1
+        max = 10
+        step = 1
+        points = 3
+        "#,
+    )
+    .unwrap();
+    assert!(config.to_cli_arguments(&|_| false, &[]).is_err());
+}
+
+#[test]
+fn test_combines_parameter_scans_and_lists() {
+    let config = Config::parse(
+        r#"
+        [[command]]
+        command = "run -j {threads} -n {size} --{mode}"
+        prepare = "sync"
+
+        [[parameter-scan]]
+        name = "threads"
+        min = 1
+        max = 2
+
+        [[parameter-scan]]
+        name = "size"
+        min = 10
+        max = 1000
+        factor = 10
+
+        [[parameter-list]]
+        name = "mode"
+        values = ["fast", "slow"]
+        "#,
+    )
+    .unwrap();
+
+    let args = config.to_cli_arguments(&|_| false, &[]).unwrap();
+    assert_eq!(
+        args[..13],
+        [
+            "--parameter-scan",
+            "threads",
+            "1",
+            "2",
+            "--parameter-scan",
+            "size",
+            "10",
+            "1000",
+            "--parameter-factor=10",
+            "--parameter-list",
+            "mode",
+            "fast,slow",
+            "--prepare=sync",
+        ]
+    );
+    assert_eq!(
+        args.iter().filter(|arg| *arg == "--prepare=sync").count(),
+        12
+    );
+}
+
+#[test]
+fn test_zipped_parameters_count_once() {
+    let config = Config::parse(
+        r#"
+        parameter-zip = ["version", "binary"]
+
+        [[command]]
+        command = "{binary} --version {flag}"
+        prepare = "sync"
+
+        [[parameter-list]]
+        name = "version"
+        values = ["1.0", "1.1"]
+
+        [[parameter-list]]
+        name = "binary"
+        values = ["/opt/a", "/opt/b"]
+
+        [[parameter-list]]
+        name = "flag"
+        values = ["-a", "-b", "-c"]
+        "#,
+    )
+    .unwrap();
+
+    let args = config.to_cli_arguments(&|_| false, &[]).unwrap();
+    assert!(args.contains(&"--parameter-zip=version,binary".to_string()));
+    assert_eq!(
+        args.iter().filter(|arg| *arg == "--prepare=sync").count(),
+        6
+    );
+}
+
+#[test]
+fn test_filtered_parameter_combinations_are_not_counted() {
+    let config = Config::parse(
+        r#"
+        parameter-filter = ["threads <= cores", "threads != 2"]
+
+        [[command]]
+        command = "run -j {threads}"
+        prepare = "sync"
+
+        [[parameter-scan]]
+        name = "threads"
+        min = 1
+        max = 4
+
+        [[parameter-list]]
+        name = "cores"
+        values = [2, 4]
+        "#,
+    )
+    .unwrap();
+
+    let args = config.to_cli_arguments(&|_| false, &[]).unwrap();
+    assert!(args.contains(&"--parameter-filter=threads <= cores".to_string()));
+    assert!(args.contains(&"--parameter-filter=threads != 2".to_string()));
+    // (1, 2), (1, 4), (3, 4), (4, 4)
+    assert_eq!(
+        args.iter().filter(|arg| *arg == "--prepare=sync").count(),
+        4
+    );
+}
+
+#[test]
+fn test_escapes_parameter_list_values() {
+    let config = Config::parse(
+        r#"
+        [[parameter-list]]
+        name = "x"
+        values = ["a,b", 'c\d', 1]
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        config.to_cli_arguments(&|_| false, &[]).unwrap(),
+        vec!["--parameter-list", "x", r"a\,b,c\\d,1"]
+    );
+}
+
+#[test]
+fn test_invalid_keys() {
+    let error = |content| format!("{:#}", Config::parse(content).unwrap_err());
+
+    assert_eq!(error("warmpu = 3"), "Unknown key `warmpu`");
+    assert_eq!(error("command-name = 'a'"), "Unknown key `command-name`");
+    assert_eq!(
+        error("ignore-failure = 'yes'"),
+        "The value of key `ignore-failure` has to be a boolean"
+    );
+    assert_eq!(
+        error("warmup = true"),
+        "The value of key `warmup` has to be a string or a number, but is boolean"
+    );
+    assert_eq!(
+        error("style = 'bogus'"),
+        "Invalid value for key `style`: 'bogus' is not one of the possible values (auto, basic, \
+         full, nocolor, color, none)"
+    );
+    assert!(error("warmup = 'x'").starts_with("Invalid value for key `warmup`: "));
+    assert!(error("runs = -1").starts_with("Invalid value for key `runs`: "));
+    assert!(error("[[command]]\ncommand = 'a'\naccept-exit-codes = 'x'")
+        .starts_with("Invalid value for key `command[0].accept-exit-codes`: "));
+    assert!(error("runs = 0.5").starts_with("Invalid value for key `runs`: "));
+    assert!(error("timeout = 0").starts_with("Invalid value for key `timeout`: "));
+    assert!(error("export-columns = ['minor-page-faults', 'page-faults']").starts_with(
+        "Invalid value for key `export-columns`: Unknown column 'page-faults' in '--export-columns'"
+    ));
+    assert!(error("[export]\ncolumns = ['context-switches']")
+        .starts_with("Invalid value for key `export.columns`: Unknown column 'context-switches'"));
+    assert!(error("[[command]]\nname = 'a'")
+        .starts_with("Invalid value for key `command[0]`: missing field `command`"));
+    assert!(error("[[command]]\ncommand = 'a'\nsetpu = 'b'")
+        .starts_with("Invalid value for key `command[0]`: unknown field `setpu`"));
+
+    let config =
+        Config::parse("[[command]]\ncommand = 'a'\nprepare = 'b'\n[[command]]\ncommand = 'c'")
+            .unwrap();
+    assert!(format!(
+        "{:#}",
+        config.to_cli_arguments(&|_| false, &[]).unwrap_err()
+    )
+    .starts_with("Missing key `command[1].prepare`"));
+}
+
+#[test]
+fn test_per_command_options() {
+    let config = Config::parse(
+        r#"
+        setup = "make"
+
+        [[command]]
+        command = "a"
+        warmup = 3
+        working-dir = "dir-a"
+
+        [[command]]
+        command = "b"
+        setup = "make b"
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        config.to_cli_arguments(&|_| false, &[]).unwrap(),
+        vec![
+            "--setup=make",
+            "--setup=make b",
+            "--warmup=3",
+            "--warmup=0",
+            "--working-dir=dir-a",
+            "--working-dir=.",
+            "a",
+            "b",
+        ]
+    );
+
+    // Options given on the command line replace the ones from the configuration file
+    assert_eq!(
+        config
+            .to_cli_arguments(&|key| key == "setup" || key == "working-dir", &[])
+            .unwrap(),
+        vec!["--warmup=3", "--warmup=0", "a", "b"]
+    );
+}
+
+#[test]
+fn test_per_command_options_with_commands_from_the_command_line() {
+    let config = Config::parse(
+        r#"
+        [[command]]
+        command = "a"
+        prepare = "prepare a"
+
+        [[command]]
+        command = "b"
+        prepare = "prepare b"
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        format!(
+            "{:#}",
+            config.to_cli_arguments(&|_| false, &["c"]).unwrap_err()
+        ),
+        "The 'prepare' option is given for single commands in the configuration file. Add a \
+         top-level `prepare` key for the commands on the command line."
+    );
+
+    // The command-line commands use the top-level values
+    let config = Config::parse(
+        r#"
+        prepare = "prepare"
+
+        [[command]]
+        command = "a"
+        prepare = "prepare a"
+        env = { MODE = "fast" }
+
+        [[command]]
+        command = "b"
+
+        [[parameter-list]]
+        name = "x"
+        values = [1, 2]
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        config.to_cli_arguments(&|_| false, &["c"]).unwrap(),
+        vec![
+            "--parameter-list",
+            "x",
+            "1,2",
+            "--prepare=prepare a",
+            "--prepare=prepare",
+            "--prepare=prepare",
+            "--prepare=prepare a",
+            "--prepare=prepare",
+            "--prepare=prepare",
+            "--env=1:MODE=fast",
+            "--env=4:MODE=fast",
+            "a",
+            "b",
+        ]
+    );
+}
+
+#[test]
+fn test_environment_variables() {
+    let config = Config::parse(
+        r#"
+        env = { LANG = "C", THREADS = 4 }
+        clean-env = true
+        keep-env = ["PATH", "HOME"]
+
+        [[command]]
+        command = "a"
+        env = { MODE = "fast" }
+
+        [[command]]
+        command = "b"
+        unset-env = ["THREADS"]
+
+        [[parameter-list]]
+        name = "x"
+        values = [1, 2]
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        config.to_cli_arguments(&|_| false, &[]).unwrap(),
+        vec![
+            "--clean-env",
+            "--env=LANG=C",
+            "--env=THREADS=4",
+            "--keep-env=PATH,HOME",
+            "--parameter-list",
+            "x",
+            "1,2",
+            "--env=1:MODE=fast",
+            "--unset-env=2:THREADS",
+            "--env=3:MODE=fast",
+            "--unset-env=4:THREADS",
+            "a",
+            "b",
+        ]
+    );
+}
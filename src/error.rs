@@ -17,8 +17,12 @@ pub enum ParameterScanError {
     ZeroStep,
     #[error("A step size is required when the range bounds are floating point numbers. The step size can be specified with the '-D/--parameter-step-size <DELTA>' parameter")]
     StepRequired,
-    #[error("'--command-name' has been specified {0} times. It has to appear exactly once, or exactly {1} times (number of benchmarks)")]
-    UnexpectedCommandNameCount(usize, usize),
+    #[error("The bounds of a geometric or logarithmic parameter scan have to be positive")]
+    NonPositiveBound,
+    #[error("The factor of a geometric parameter scan has to be greater than one")]
+    InvalidFactor,
+    #[error("A logarithmic parameter scan needs at least two points")]
+    TooFewPoints,
 }
 
 impl From<num::ParseIntError> for ParameterScanError {
@@ -53,4 +57,24 @@ pub enum OptionsError<'a> {
     ShellParseError(shell_words::ParseError),
     #[error("Unknown output policy '{0}'. Use './{0}' to output to a file named '{0}'.")]
     UnknownOutputPolicy(String),
+    #[error("The significance level has to be strictly between 0 and 1, but is {0}")]
+    InvalidSignificanceLevel(f64),
+    #[error("The confidence level has to be strictly between 0 and 1, but is {0}")]
+    InvalidConfidenceLevel(f64),
+    #[error("Invalid precision '{0}'. Expected a percentage of the mean like '1%'")]
+    InvalidPrecision(String),
+    #[error("The argument to '--{0}' has to be a positive number of seconds, but is {1}")]
+    InvalidTimeout(&'a str, f64),
+    #[error("Invalid batch size '{0}'. Expected a positive integer or 'auto'")]
+    InvalidBatchSize(String),
+    #[error("Invalid exit codes '{0}'. Expected a comma-separated list of exit codes and ranges like '0,1' or '0-2'")]
+    InvalidExitCodes(String),
+    #[error("Invalid argument '{0}' to '--env'. Expected 'NAME=VALUE', optionally prefixed with a benchmark number like '2:NAME=VALUE'")]
+    InvalidEnvironmentVariable(String),
+    #[error("Invalid argument '{0}' to '--unset-env'. Expected a variable name, optionally prefixed with a benchmark number like '2:NAME'")]
+    InvalidEnvironmentVariableName(String),
+    #[error("Invalid parameter filter '{0}': {1}")]
+    InvalidParameterFilter(String, String),
+    #[error("All parameter combinations were excluded by '--parameter-filter'")]
+    AllParameterCombinationsFiltered,
 }
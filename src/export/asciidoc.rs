@@ -1,10 +1,17 @@
 use super::markup::Alignment;
 use crate::export::markup::MarkupExporter;
+use crate::export::ExtraColumn;
 
 #[derive(Default)]
-pub struct AsciidocExporter {}
+pub struct AsciidocExporter {
+    pub extra_columns: Vec<ExtraColumn>,
+}
 
 impl MarkupExporter for AsciidocExporter {
+    fn extra_columns(&self) -> &[ExtraColumn] {
+        &self.extra_columns
+    }
+
     fn table_header(&self, cell_aligmnents: &[Alignment]) -> String {
         format!(
             "[cols=\"{}\"]\n|===",
@@ -99,10 +106,23 @@ fn test_asciidoc_format_s() {
             median: 1.0,
             user: 3.0,
             system: 4.0,
+            memory_mean: None,
+            memory_min: None,
+            memory_max: None,
+            resource_usage: None,
             min: 5.0,
             max: 6.0,
+            mean_ci: None,
+            median_ci: None,
+            relative_speed_ci: None,
+            p_value: None,
             times: Some(vec![7.0, 8.0, 9.0]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
+            timeouts: 0,
+            accepted_exit_codes: None,
+            failed_runs: 0,
+            batch_size: None,
+            output_hash: None,
             parameters: {
                 let mut params = BTreeMap::new();
                 params.insert("foo".into(), "1".into());
@@ -117,10 +137,23 @@ fn test_asciidoc_format_s() {
             median: 11.0,
             user: 13.0,
             system: 14.0,
+            memory_mean: None,
+            memory_min: None,
+            memory_max: None,
+            resource_usage: None,
             min: 15.0,
             max: 16.0,
+            mean_ci: None,
+            median_ci: None,
+            relative_speed_ci: None,
+            p_value: None,
             times: Some(vec![17.0, 18.0, 19.0]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
+            timeouts: 0,
+            accepted_exit_codes: None,
+            failed_runs: 0,
+            batch_size: None,
+            output_hash: None,
             parameters: {
                 let mut params = BTreeMap::new();
                 params.insert("foo".into(), "1".into());
@@ -168,10 +201,23 @@ fn test_asciidoc_format_ms() {
             median: 0.011,
             user: 0.013,
             system: 0.014,
+            memory_mean: None,
+            memory_min: None,
+            memory_max: None,
+            resource_usage: None,
             min: 0.015,
             max: 0.016,
+            mean_ci: None,
+            median_ci: None,
+            relative_speed_ci: None,
+            p_value: None,
             times: Some(vec![0.017, 0.018, 0.019]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
+            timeouts: 0,
+            accepted_exit_codes: None,
+            failed_runs: 0,
+            batch_size: None,
+            output_hash: None,
             parameters: {
                 let mut params = BTreeMap::new();
                 params.insert("foo".into(), "1".into());
@@ -186,10 +232,23 @@ fn test_asciidoc_format_ms() {
             median: 1.0,
             user: 3.0,
             system: 4.0,
+            memory_mean: None,
+            memory_min: None,
+            memory_max: None,
+            resource_usage: None,
             min: 5.0,
             max: 6.0,
+            mean_ci: None,
+            median_ci: None,
+            relative_speed_ci: None,
+            p_value: None,
             times: Some(vec![7.0, 8.0, 9.0]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
+            timeouts: 0,
+            accepted_exit_codes: None,
+            failed_runs: 0,
+            batch_size: None,
+            output_hash: None,
             parameters: {
                 let mut params = BTreeMap::new();
                 params.insert("foo".into(), "1".into());
@@ -2,14 +2,16 @@ use std::borrow::Cow;
 
 use csv::WriterBuilder;
 
-use super::Exporter;
+use super::{Exporter, ExtraColumn};
 use crate::benchmark::benchmark_result::BenchmarkResult;
 use crate::util::units::Unit;
 
 use anyhow::Result;
 
 #[derive(Default)]
-pub struct CsvExporter {}
+pub struct CsvExporter {
+    pub extra_columns: Vec<ExtraColumn>,
+}
 
 impl Exporter for CsvExporter {
     fn serialize(&self, results: &[BenchmarkResult], _unit: Option<Unit>) -> Result<Vec<u8>> {
@@ -18,11 +20,30 @@ impl Exporter for CsvExporter {
         {
             let mut headers: Vec<Cow<[u8]>> = [
                 // The list of times and exit codes cannot be exported to the CSV file - omit them.
-                "command", "mean", "stddev", "median", "user", "system", "min", "max",
+                "command",
+                "mean",
+                "stddev",
+                "median",
+                "user",
+                "system",
+                "min",
+                "max",
+                "mean_ci_lower",
+                "mean_ci_upper",
+                "median_ci_lower",
+                "median_ci_upper",
+                "relative_speed_ci_lower",
+                "relative_speed_ci_upper",
+                "memory_mean",
+                "memory_min",
+                "memory_max",
             ]
             .iter()
             .map(|x| Cow::Borrowed(x.as_bytes()))
             .collect();
+            for column in &self.extra_columns {
+                headers.push(Cow::Borrowed(column.key().as_bytes()));
+            }
             if let Some(res) = results.first() {
                 for param_name in res.parameters.keys() {
                     headers.push(Cow::Owned(format!("parameter_{}", param_name).into_bytes()));
@@ -44,6 +65,32 @@ impl Exporter for CsvExporter {
             ] {
                 fields.push(Cow::Owned(f.to_string().into_bytes()))
             }
+            // Confidence intervals are left empty if they are not available
+            for ci in &[res.mean_ci, res.median_ci, res.relative_speed_ci] {
+                for bound in &[ci.map(|ci| ci.lower), ci.map(|ci| ci.upper)] {
+                    fields.push(Cow::Owned(
+                        bound
+                            .map_or_else(String::new, |b| b.to_string())
+                            .into_bytes(),
+                    ))
+                }
+            }
+            // Memory usage is left empty if it is not available
+            for memory in &[res.memory_mean, res.memory_min, res.memory_max] {
+                fields.push(Cow::Owned(
+                    memory
+                        .map_or_else(String::new, |m| m.to_string())
+                        .into_bytes(),
+                ))
+            }
+            for column in &self.extra_columns {
+                fields.push(Cow::Owned(
+                    column
+                        .value(res)
+                        .map_or_else(String::new, |v| v.to_string())
+                        .into_bytes(),
+                ))
+            }
             for v in res.parameters.values() {
                 fields.push(Cow::Borrowed(v.as_bytes()))
             }
@@ -56,6 +103,7 @@ impl Exporter for CsvExporter {
 
 #[test]
 fn test_csv() {
+    use crate::bootstrap::ConfidenceInterval;
     use std::collections::BTreeMap;
     let exporter = CsvExporter::default();
 
@@ -67,10 +115,27 @@ fn test_csv() {
             median: 1.0,
             user: 3.0,
             system: 4.0,
+            memory_mean: Some(2048),
+            memory_min: Some(1024),
+            memory_max: Some(4096),
+            resource_usage: None,
             min: 5.0,
             max: 6.0,
+            mean_ci: Some(ConfidenceInterval {
+                level: 0.95,
+                lower: 0.5,
+                upper: 1.5,
+            }),
+            median_ci: None,
+            relative_speed_ci: None,
+            p_value: None,
             times: Some(vec![7.0, 8.0, 9.0]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
+            timeouts: 0,
+            accepted_exit_codes: None,
+            failed_runs: 0,
+            batch_size: None,
+            output_hash: None,
             parameters: {
                 let mut params = BTreeMap::new();
                 params.insert("foo".into(), "one".into());
@@ -85,10 +150,27 @@ fn test_csv() {
             median: 11.0,
             user: 13.0,
             system: 14.0,
+            memory_mean: None,
+            memory_min: None,
+            memory_max: None,
+            resource_usage: None,
             min: 15.0,
             max: 16.5,
+            mean_ci: None,
+            median_ci: None,
+            relative_speed_ci: Some(ConfidenceInterval {
+                level: 0.95,
+                lower: 10.0,
+                upper: 12.0,
+            }),
+            p_value: None,
             times: Some(vec![17.0, 18.0, 19.0]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
+            timeouts: 0,
+            accepted_exit_codes: None,
+            failed_runs: 0,
+            batch_size: None,
+            output_hash: None,
             parameters: {
                 let mut params = BTreeMap::new();
                 params.insert("foo".into(), "one".into());
@@ -98,9 +180,12 @@ fn test_csv() {
         },
     ];
     let exps: String = String::from(
-        "command,mean,stddev,median,user,system,min,max,parameter_bar,parameter_foo\n\
-        FOO=one BAR=two command | 1,1,2,1,3,4,5,6,two,one\n\
-        FOO=one BAR=seven command | 2,11,12,11,13,14,15,16.5,seven,one\n\
+        "command,mean,stddev,median,user,system,min,max,\
+        mean_ci_lower,mean_ci_upper,median_ci_lower,median_ci_upper,\
+        relative_speed_ci_lower,relative_speed_ci_upper,\
+        memory_mean,memory_min,memory_max,parameter_bar,parameter_foo\n\
+        FOO=one BAR=two command | 1,1,2,1,3,4,5,6,0.5,1.5,,,,,2048,1024,4096,two,one\n\
+        FOO=one BAR=seven command | 2,11,12,11,13,14,15,16.5,,,,,10,12,,,,seven,one\n\
         ",
     );
     let gens =
@@ -108,3 +193,42 @@ fn test_csv() {
 
     assert_eq!(exps, gens);
 }
+
+#[test]
+fn test_csv_extra_columns() {
+    use crate::benchmark::benchmark_result::ResourceUsage;
+
+    let exporter = CsvExporter {
+        extra_columns: vec![
+            ExtraColumn::MajorPageFaults,
+            ExtraColumn::BlockOutputOperations,
+        ],
+    };
+
+    let results = vec![
+        BenchmarkResult {
+            command: String::from("a"),
+            resource_usage: Some(ResourceUsage {
+                major_page_faults: 2.0,
+                block_output_operations: 0.5,
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+        BenchmarkResult {
+            command: String::from("b"),
+            ..Default::default()
+        },
+    ];
+    let gens = String::from_utf8(exporter.serialize(&results, None).unwrap()).unwrap();
+
+    assert_eq!(
+        "command,mean,stddev,median,user,system,min,max,\
+        mean_ci_lower,mean_ci_upper,median_ci_lower,median_ci_upper,\
+        relative_speed_ci_lower,relative_speed_ci_upper,\
+        memory_mean,memory_min,memory_max,major_page_faults,block_output_operations\n\
+        a,0,0,0,0,0,0,0,,,,,,,,,,2,0.5\n\
+        b,0,0,0,0,0,0,0,,,,,,,,,,,\n",
+        gens
+    );
+}
@@ -1,11 +1,18 @@
 use crate::export::markup::MarkupExporter;
+use crate::export::ExtraColumn;
 
 use super::markup::Alignment;
 
 #[derive(Default)]
-pub struct MarkdownExporter {}
+pub struct MarkdownExporter {
+    pub extra_columns: Vec<ExtraColumn>,
+}
 
 impl MarkupExporter for MarkdownExporter {
+    fn extra_columns(&self) -> &[ExtraColumn] {
+        &self.extra_columns
+    }
+
     fn table_row(&self, cells: &[&str]) -> String {
         format!("| {} |\n", cells.join(" | "))
     }
@@ -76,10 +83,23 @@ fn test_markdown_format_ms() {
             median: 0.1057,
             user: 0.0009,
             system: 0.0011,
+            memory_mean: None,
+            memory_min: None,
+            memory_max: None,
+            resource_usage: None,
             min: 0.1023,
             max: 0.1080,
+            mean_ci: None,
+            median_ci: None,
+            relative_speed_ci: None,
+            p_value: None,
             times: Some(vec![0.1, 0.1, 0.1]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
+            timeouts: 0,
+            accepted_exit_codes: None,
+            failed_runs: 0,
+            batch_size: None,
+            output_hash: None,
             parameters: BTreeMap::new(),
         },
         BenchmarkResult {
@@ -89,10 +109,23 @@ fn test_markdown_format_ms() {
             median: 2.0050,
             user: 0.0009,
             system: 0.0012,
+            memory_mean: None,
+            memory_min: None,
+            memory_max: None,
+            resource_usage: None,
             min: 2.0020,
             max: 2.0080,
+            mean_ci: None,
+            median_ci: None,
+            relative_speed_ci: None,
+            p_value: None,
             times: Some(vec![2.0, 2.0, 2.0]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
+            timeouts: 0,
+            accepted_exit_codes: None,
+            failed_runs: 0,
+            batch_size: None,
+            output_hash: None,
             parameters: BTreeMap::new(),
         },
     ];
@@ -126,10 +159,23 @@ fn test_markdown_format_s() {
             median: 2.0050,
             user: 0.0009,
             system: 0.0012,
+            memory_mean: None,
+            memory_min: None,
+            memory_max: None,
+            resource_usage: None,
             min: 2.0020,
             max: 2.0080,
+            mean_ci: None,
+            median_ci: None,
+            relative_speed_ci: None,
+            p_value: None,
             times: Some(vec![2.0, 2.0, 2.0]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
+            timeouts: 0,
+            accepted_exit_codes: None,
+            failed_runs: 0,
+            batch_size: None,
+            output_hash: None,
             parameters: BTreeMap::new(),
         },
         BenchmarkResult {
@@ -139,10 +185,23 @@ fn test_markdown_format_s() {
             median: 0.1057,
             user: 0.0009,
             system: 0.0011,
+            memory_mean: None,
+            memory_min: None,
+            memory_max: None,
+            resource_usage: None,
             min: 0.1023,
             max: 0.1080,
+            mean_ci: None,
+            median_ci: None,
+            relative_speed_ci: None,
+            p_value: None,
             times: Some(vec![0.1, 0.1, 0.1]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
+            timeouts: 0,
+            accepted_exit_codes: None,
+            failed_runs: 0,
+            batch_size: None,
+            output_hash: None,
             parameters: BTreeMap::new(),
         },
     ];
@@ -176,10 +235,23 @@ fn test_markdown_format_time_unit_s() {
             median: 0.1057,
             user: 0.0009,
             system: 0.0011,
+            memory_mean: None,
+            memory_min: None,
+            memory_max: None,
+            resource_usage: None,
             min: 0.1023,
             max: 0.1080,
+            mean_ci: None,
+            median_ci: None,
+            relative_speed_ci: None,
+            p_value: None,
             times: Some(vec![0.1, 0.1, 0.1]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
+            timeouts: 0,
+            accepted_exit_codes: None,
+            failed_runs: 0,
+            batch_size: None,
+            output_hash: None,
             parameters: BTreeMap::new(),
         },
         BenchmarkResult {
@@ -189,10 +261,23 @@ fn test_markdown_format_time_unit_s() {
             median: 2.0050,
             user: 0.0009,
             system: 0.0012,
+            memory_mean: None,
+            memory_min: None,
+            memory_max: None,
+            resource_usage: None,
             min: 2.0020,
             max: 2.0080,
+            mean_ci: None,
+            median_ci: None,
+            relative_speed_ci: None,
+            p_value: None,
             times: Some(vec![2.0, 2.0, 2.0]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
+            timeouts: 0,
+            accepted_exit_codes: None,
+            failed_runs: 0,
+            batch_size: None,
+            output_hash: None,
             parameters: BTreeMap::new(),
         },
     ];
@@ -232,10 +317,23 @@ fn test_markdown_format_time_unit_ms() {
             median: 2.0050,
             user: 0.0009,
             system: 0.0012,
+            memory_mean: None,
+            memory_min: None,
+            memory_max: None,
+            resource_usage: None,
             min: 2.0020,
             max: 2.0080,
+            mean_ci: None,
+            median_ci: None,
+            relative_speed_ci: None,
+            p_value: None,
             times: Some(vec![2.0, 2.0, 2.0]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
+            timeouts: 0,
+            accepted_exit_codes: None,
+            failed_runs: 0,
+            batch_size: None,
+            output_hash: None,
             parameters: BTreeMap::new(),
         },
         BenchmarkResult {
@@ -245,10 +343,23 @@ fn test_markdown_format_time_unit_ms() {
             median: 0.1057,
             user: 0.0009,
             system: 0.0011,
+            memory_mean: None,
+            memory_min: None,
+            memory_max: None,
+            resource_usage: None,
             min: 0.1023,
             max: 0.1080,
+            mean_ci: None,
+            median_ci: None,
+            relative_speed_ci: None,
+            p_value: None,
             times: Some(vec![0.1, 0.1, 0.1]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
+            timeouts: 0,
+            accepted_exit_codes: None,
+            failed_runs: 0,
+            batch_size: None,
+            output_hash: None,
             parameters: BTreeMap::new(),
         },
     ];
@@ -3,7 +3,7 @@ use crate::benchmark::{benchmark_result::BenchmarkResult, relative_speed};
 use crate::output::format::format_duration_value;
 use crate::util::units::Unit;
 
-use super::Exporter;
+use super::{Exporter, ExtraColumn};
 use anyhow::{anyhow, Result};
 
 pub enum Alignment {
@@ -11,30 +11,52 @@ pub enum Alignment {
     Right,
 }
 pub trait MarkupExporter {
+    /// Additional columns to include in the table
+    fn extra_columns(&self) -> &[ExtraColumn];
+
     fn table_results(&self, entries: &[BenchmarkResultWithRelativeSpeed], unit: Unit) -> String {
         // prepare table header strings
         let notation = format!("[{}]", unit.short_name());
 
+        // only show the memory column if the peak memory usage has been measured
+        let show_memory = entries.iter().any(|e| e.result.memory_mean.is_some());
+
         // prepare table cells alignment
-        let cells_alignment = [
+        let mut cells_alignment = vec![
             Alignment::Left,
             Alignment::Right,
             Alignment::Right,
             Alignment::Right,
             Alignment::Right,
         ];
+        if show_memory {
+            cells_alignment.push(Alignment::Right);
+        }
+        for _ in self.extra_columns() {
+            cells_alignment.push(Alignment::Right);
+        }
 
         // emit table header format
         let mut table = self.table_header(&cells_alignment);
 
         // emit table header data
-        table.push_str(&self.table_row(&[
+        let mean_header = format!("Mean {}", notation);
+        let min_header = format!("Min {}", notation);
+        let max_header = format!("Max {}", notation);
+        let mut header = vec![
             "Command",
-            &format!("Mean {}", notation),
-            &format!("Min {}", notation),
-            &format!("Max {}", notation),
+            &mean_header,
+            &min_header,
+            &max_header,
             "Relative",
-        ]));
+        ];
+        if show_memory {
+            header.push("Memory [MiB]");
+        }
+        for column in self.extra_columns() {
+            header.push(column.title());
+        }
+        table.push_str(&self.table_row(&header));
 
         // emit horizontal line
         table.push_str(&self.table_divider(&cells_alignment));
@@ -59,15 +81,33 @@ pub trait MarkupExporter {
             } else {
                 "".into()
             };
+            let rel_ci_str = match measurement.relative_speed_ci {
+                Some(ci) if !entry.is_fastest => format!(" ({:.2} … {:.2})", ci.lower, ci.upper),
+                _ => "".into(),
+            };
+            let memory_str = measurement.memory_mean.map_or("".into(), |memory| {
+                format!("{:.1}", memory as f64 / (1024.0 * 1024.0))
+            });
 
             // prepare table row entries
-            table.push_str(&self.table_row(&[
-                &self.command(&cmd_str),
-                &format!("{}{}", mean_str, stddev_str),
-                &min_str,
-                &max_str,
-                &format!("{}{}", rel_str, rel_stddev_str),
-            ]))
+            let cmd_cell = self.command(&cmd_str);
+            let mean_cell = format!("{}{}", mean_str, stddev_str);
+            let rel_cell = format!("{}{}{}", rel_str, rel_stddev_str, rel_ci_str);
+            let mut row = vec![cmd_cell.as_str(), &mean_cell, &min_str, &max_str, &rel_cell];
+            if show_memory {
+                row.push(&memory_str);
+            }
+            let extra_strs: Vec<String> = self
+                .extra_columns()
+                .iter()
+                .map(|column| {
+                    column
+                        .value(measurement)
+                        .map_or("".into(), |v| format!("{:.1}", v))
+                })
+                .collect();
+            row.extend(extra_strs.iter().map(String::as_str));
+            table.push_str(&self.table_row(&row))
         }
 
         // emit table footer format
@@ -116,6 +156,117 @@ impl<T: MarkupExporter> Exporter for T {
     }
 }
 
+/// Ensure that the confidence interval of the relative speed is shown if it is available
+#[test]
+fn test_relative_speed_interval() {
+    use crate::bootstrap::ConfidenceInterval;
+    use crate::export::markdown::MarkdownExporter;
+
+    let fast = BenchmarkResult {
+        command: String::from("sleep 0.1"),
+        mean: 0.1,
+        ..Default::default()
+    };
+    let slow = BenchmarkResult {
+        command: String::from("sleep 0.2"),
+        mean: 0.2,
+        relative_speed_ci: Some(ConfidenceInterval {
+            level: 0.95,
+            lower: 1.9,
+            upper: 2.1,
+        }),
+        ..Default::default()
+    };
+
+    let table = String::from_utf8(
+        MarkdownExporter::default()
+            .serialize(&[fast, slow], Some(Unit::MilliSecond))
+            .unwrap(),
+    )
+    .unwrap();
+    assert!(table.contains("| 2.00 (1.90 … 2.10) |"));
+}
+
+/// Ensure that the memory column is only added if the memory usage is available
+#[test]
+fn test_memory_column() {
+    use crate::export::markdown::MarkdownExporter;
+
+    let result = BenchmarkResult {
+        command: String::from("sleep 0.1"),
+        mean: 0.1,
+        median: 0.1,
+        min: 0.1,
+        max: 0.1,
+        ..Default::default()
+    };
+
+    let results = vec![result.clone()];
+    let table = String::from_utf8(
+        MarkdownExporter::default()
+            .serialize(&results, Some(Unit::MilliSecond))
+            .unwrap(),
+    )
+    .unwrap();
+    assert!(!table.contains("Memory"));
+
+    let results = vec![BenchmarkResult {
+        memory_mean: Some(3 * 1024 * 1024 / 2),
+        ..result
+    }];
+    let table = String::from_utf8(
+        MarkdownExporter::default()
+            .serialize(&results, Some(Unit::MilliSecond))
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        table,
+        "| Command | Mean [ms] | Min [ms] | Max [ms] | Relative | Memory [MiB] |\n\
+         |:---|---:|---:|---:|---:|---:|\n\
+         | `sleep 0.1` | 100.0 | 100.0 | 100.0 | 1.00 | 1.5 |\n"
+    );
+}
+
+/// Ensure that the selected extra columns are added to the table
+#[test]
+fn test_extra_columns() {
+    use crate::benchmark::benchmark_result::ResourceUsage;
+    use crate::export::markdown::MarkdownExporter;
+
+    let results = vec![BenchmarkResult {
+        command: String::from("sleep 0.1"),
+        mean: 0.1,
+        median: 0.1,
+        min: 0.1,
+        max: 0.1,
+        resource_usage: Some(ResourceUsage {
+            minor_page_faults: 120.5,
+            involuntary_context_switches: 3.0,
+            ..Default::default()
+        }),
+        ..Default::default()
+    }];
+    let exporter = MarkdownExporter {
+        extra_columns: vec![
+            ExtraColumn::MinorPageFaults,
+            ExtraColumn::InvoluntaryContextSwitches,
+        ],
+    };
+    let table = String::from_utf8(
+        exporter
+            .serialize(&results, Some(Unit::MilliSecond))
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        table,
+        "| Command | Mean [ms] | Min [ms] | Max [ms] | Relative | Minor faults | Invol. ctx switches |\n\
+         |:---|---:|---:|---:|---:|---:|---:|\n\
+         | `sleep 0.1` | 100.0 | 100.0 | 100.0 | 1.00 | 120.5 | 3.0 |\n"
+    );
+}
+
 /// Check unit resolving for timing results and given unit 's'
 #[test]
 fn test_determine_unit_from_results_unit_given_s() {
@@ -128,10 +279,23 @@ fn test_determine_unit_from_results_unit_given_s() {
             median: 2.0050,
             user: 0.0009,
             system: 0.0012,
+            memory_mean: None,
+            memory_min: None,
+            memory_max: None,
+            resource_usage: None,
             min: 2.0020,
             max: 2.0080,
+            mean_ci: None,
+            median_ci: None,
+            relative_speed_ci: None,
+            p_value: None,
             times: Some(vec![2.0, 2.0, 2.0]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
+            timeouts: 0,
+            accepted_exit_codes: None,
+            failed_runs: 0,
+            batch_size: None,
+            output_hash: None,
             parameters: BTreeMap::new(),
         },
         BenchmarkResult {
@@ -141,15 +305,29 @@ fn test_determine_unit_from_results_unit_given_s() {
             median: 0.1057,
             user: 0.0009,
             system: 0.0011,
+            memory_mean: None,
+            memory_min: None,
+            memory_max: None,
+            resource_usage: None,
             min: 0.1023,
             max: 0.1080,
+            mean_ci: None,
+            median_ci: None,
+            relative_speed_ci: None,
+            p_value: None,
             times: Some(vec![0.1, 0.1, 0.1]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
+            timeouts: 0,
+            accepted_exit_codes: None,
+            failed_runs: 0,
+            batch_size: None,
+            output_hash: None,
             parameters: BTreeMap::new(),
         },
     ];
     let unit = Some(Unit::Second);
 
+    #[allow(clippy::unnecessary_literal_unwrap)]
     let markup_actual = unit.unwrap_or_else(|| determine_unit_from_results(&results));
     let markup_expected = Unit::Second;
 
@@ -168,10 +346,23 @@ fn test_determine_unit_from_results_unit_given_ms() {
             median: 2.0050,
             user: 0.0009,
             system: 0.0012,
+            memory_mean: None,
+            memory_min: None,
+            memory_max: None,
+            resource_usage: None,
             min: 2.0020,
             max: 2.0080,
+            mean_ci: None,
+            median_ci: None,
+            relative_speed_ci: None,
+            p_value: None,
             times: Some(vec![2.0, 2.0, 2.0]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
+            timeouts: 0,
+            accepted_exit_codes: None,
+            failed_runs: 0,
+            batch_size: None,
+            output_hash: None,
             parameters: BTreeMap::new(),
         },
         BenchmarkResult {
@@ -181,15 +372,29 @@ fn test_determine_unit_from_results_unit_given_ms() {
             median: 0.1057,
             user: 0.0009,
             system: 0.0011,
+            memory_mean: None,
+            memory_min: None,
+            memory_max: None,
+            resource_usage: None,
             min: 0.1023,
             max: 0.1080,
+            mean_ci: None,
+            median_ci: None,
+            relative_speed_ci: None,
+            p_value: None,
             times: Some(vec![0.1, 0.1, 0.1]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
+            timeouts: 0,
+            accepted_exit_codes: None,
+            failed_runs: 0,
+            batch_size: None,
+            output_hash: None,
             parameters: BTreeMap::new(),
         },
     ];
     let unit = Some(Unit::MilliSecond);
 
+    #[allow(clippy::unnecessary_literal_unwrap)]
     let markup_actual = unit.unwrap_or_else(|| determine_unit_from_results(&results));
     let markup_expected = Unit::MilliSecond;
 
@@ -208,10 +413,23 @@ fn test_determine_unit_from_results_unit_first_s() {
             median: 2.0050,
             user: 0.0009,
             system: 0.0012,
+            memory_mean: None,
+            memory_min: None,
+            memory_max: None,
+            resource_usage: None,
             min: 2.0020,
             max: 2.0080,
+            mean_ci: None,
+            median_ci: None,
+            relative_speed_ci: None,
+            p_value: None,
             times: Some(vec![2.0, 2.0, 2.0]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
+            timeouts: 0,
+            accepted_exit_codes: None,
+            failed_runs: 0,
+            batch_size: None,
+            output_hash: None,
             parameters: BTreeMap::new(),
         },
         BenchmarkResult {
@@ -221,15 +439,29 @@ fn test_determine_unit_from_results_unit_first_s() {
             median: 0.1057,
             user: 0.0009,
             system: 0.0011,
+            memory_mean: None,
+            memory_min: None,
+            memory_max: None,
+            resource_usage: None,
             min: 0.1023,
             max: 0.1080,
+            mean_ci: None,
+            median_ci: None,
+            relative_speed_ci: None,
+            p_value: None,
             times: Some(vec![0.1, 0.1, 0.1]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
+            timeouts: 0,
+            accepted_exit_codes: None,
+            failed_runs: 0,
+            batch_size: None,
+            output_hash: None,
             parameters: BTreeMap::new(),
         },
     ];
     let unit = None;
 
+    #[allow(clippy::unnecessary_literal_unwrap)]
     let markup_actual = unit.unwrap_or_else(|| determine_unit_from_results(&results));
     let markup_expected = Unit::Second;
 
@@ -248,10 +480,23 @@ fn test_determine_unit_from_results_unit_first_ms() {
             median: 0.1057,
             user: 0.0009,
             system: 0.0011,
+            memory_mean: None,
+            memory_min: None,
+            memory_max: None,
+            resource_usage: None,
             min: 0.1023,
             max: 0.1080,
+            mean_ci: None,
+            median_ci: None,
+            relative_speed_ci: None,
+            p_value: None,
             times: Some(vec![0.1, 0.1, 0.1]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
+            timeouts: 0,
+            accepted_exit_codes: None,
+            failed_runs: 0,
+            batch_size: None,
+            output_hash: None,
             parameters: BTreeMap::new(),
         },
         BenchmarkResult {
@@ -261,15 +506,29 @@ fn test_determine_unit_from_results_unit_first_ms() {
             median: 2.0050,
             user: 0.0009,
             system: 0.0012,
+            memory_mean: None,
+            memory_min: None,
+            memory_max: None,
+            resource_usage: None,
             min: 2.0020,
             max: 2.0080,
+            mean_ci: None,
+            median_ci: None,
+            relative_speed_ci: None,
+            p_value: None,
             times: Some(vec![2.0, 2.0, 2.0]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
+            timeouts: 0,
+            accepted_exit_codes: None,
+            failed_runs: 0,
+            batch_size: None,
+            output_hash: None,
             parameters: BTreeMap::new(),
         },
     ];
     let unit = None;
 
+    #[allow(clippy::unnecessary_literal_unwrap)]
     let markup_actual = unit.unwrap_or_else(|| determine_unit_from_results(&results));
     let markup_expected = Unit::MilliSecond;
 
@@ -282,6 +541,7 @@ fn test_determine_unit_from_results_unit_default_s() {
     let results: Vec<BenchmarkResult> = vec![];
     let unit = None;
 
+    #[allow(clippy::unnecessary_literal_unwrap)]
     let markup_actual = unit.unwrap_or_else(|| determine_unit_from_results(&results));
     let markup_expected = Unit::Second;
 
@@ -6,6 +6,7 @@ mod csv;
 mod json;
 mod markdown;
 mod markup;
+mod ndjson;
 mod orgmode;
 
 use self::asciidoc::AsciidocExporter;
@@ -14,8 +15,10 @@ use self::json::JsonExporter;
 use self::markdown::MarkdownExporter;
 use self::orgmode::OrgmodeExporter;
 
+pub use self::ndjson::NdjsonObserver;
+
 use crate::benchmark::benchmark_result::BenchmarkResult;
-use crate::util::units::Unit;
+use crate::util::units::{Scalar, Unit};
 
 use anyhow::{Context, Result};
 use clap::ArgMatches;
@@ -39,6 +42,100 @@ pub enum ExportType {
     Orgmode,
 }
 
+/// Additional columns that can be added to the CSV and markup exports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExtraColumn {
+    MinorPageFaults,
+    MajorPageFaults,
+    VoluntaryContextSwitches,
+    InvoluntaryContextSwitches,
+    BlockInputOperations,
+    BlockOutputOperations,
+}
+
+/// All extra columns and their names on the command line (`--export-columns`)
+const EXTRA_COLUMNS: [(ExtraColumn, &str); 6] = [
+    (ExtraColumn::MinorPageFaults, "minor-page-faults"),
+    (ExtraColumn::MajorPageFaults, "major-page-faults"),
+    (
+        ExtraColumn::VoluntaryContextSwitches,
+        "voluntary-context-switches",
+    ),
+    (
+        ExtraColumn::InvoluntaryContextSwitches,
+        "involuntary-context-switches",
+    ),
+    (ExtraColumn::BlockInputOperations, "block-input-operations"),
+    (
+        ExtraColumn::BlockOutputOperations,
+        "block-output-operations",
+    ),
+];
+
+impl ExtraColumn {
+    /// Parse the column name as given on the command line
+    pub fn from_name(name: &str) -> Option<ExtraColumn> {
+        EXTRA_COLUMNS
+            .iter()
+            .find(|(_, n)| *n == name)
+            .map(|(column, _)| *column)
+    }
+
+    /// Parse the column name as given on the command line, with an error listing the
+    /// possible names
+    pub fn parse(name: &str) -> Result<ExtraColumn> {
+        ExtraColumn::from_name(name).with_context(|| {
+            format!(
+                "Unknown column '{}' in '--export-columns'. Possible values: {}",
+                name,
+                ExtraColumn::names().collect::<Vec<_>>().join(", ")
+            )
+        })
+    }
+
+    /// Names of all columns, as given on the command line
+    pub fn names() -> impl Iterator<Item = &'static str> {
+        EXTRA_COLUMNS.iter().map(|(_, name)| *name)
+    }
+
+    /// Column header in machine-readable exports
+    pub fn key(self) -> &'static str {
+        match self {
+            ExtraColumn::MinorPageFaults => "minor_page_faults",
+            ExtraColumn::MajorPageFaults => "major_page_faults",
+            ExtraColumn::VoluntaryContextSwitches => "voluntary_context_switches",
+            ExtraColumn::InvoluntaryContextSwitches => "involuntary_context_switches",
+            ExtraColumn::BlockInputOperations => "block_input_operations",
+            ExtraColumn::BlockOutputOperations => "block_output_operations",
+        }
+    }
+
+    /// Column header in human-readable tables
+    pub fn title(self) -> &'static str {
+        match self {
+            ExtraColumn::MinorPageFaults => "Minor faults",
+            ExtraColumn::MajorPageFaults => "Major faults",
+            ExtraColumn::VoluntaryContextSwitches => "Vol. ctx switches",
+            ExtraColumn::InvoluntaryContextSwitches => "Invol. ctx switches",
+            ExtraColumn::BlockInputOperations => "Block inputs",
+            ExtraColumn::BlockOutputOperations => "Block outputs",
+        }
+    }
+
+    /// The (average) value of this column for the given result, if available
+    pub fn value(self, result: &BenchmarkResult) -> Option<Scalar> {
+        let usage = result.resource_usage.as_ref()?;
+        Some(match self {
+            ExtraColumn::MinorPageFaults => usage.minor_page_faults,
+            ExtraColumn::MajorPageFaults => usage.major_page_faults,
+            ExtraColumn::VoluntaryContextSwitches => usage.voluntary_context_switches,
+            ExtraColumn::InvoluntaryContextSwitches => usage.involuntary_context_switches,
+            ExtraColumn::BlockInputOperations => usage.block_input_operations,
+            ExtraColumn::BlockOutputOperations => usage.block_output_operations,
+        })
+    }
+}
+
 /// Interface for different exporters.
 trait Exporter {
     /// Export the given entries in the serialized form.
@@ -54,13 +151,23 @@ struct ExporterWithFilename {
 #[derive(Default)]
 pub struct ExportManager {
     exporters: Vec<ExporterWithFilename>,
+    extra_columns: Vec<ExtraColumn>,
 }
 
 impl ExportManager {
     /// Build the ExportManager that will export the results specified
     /// in the given ArgMatches
     pub fn from_cli_arguments(matches: &ArgMatches) -> Result<Self> {
-        let mut export_manager = Self::default();
+        let extra_columns = matches
+            .get_many::<String>("export-columns")
+            .into_iter()
+            .flatten()
+            .map(|name| ExtraColumn::parse(name))
+            .collect::<Result<_>>()?;
+        let mut export_manager = Self {
+            extra_columns,
+            ..Default::default()
+        };
         {
             let mut add_exporter = |flag, exporttype| -> Result<()> {
                 if let Some(filename) = matches.get_one::<String>(flag) {
@@ -82,12 +189,13 @@ impl ExportManager {
         let _ = File::create(filename)
             .with_context(|| format!("Could not create export file '{}'", filename))?;
 
+        let extra_columns = self.extra_columns.clone();
         let exporter: Box<dyn Exporter> = match export_type {
-            ExportType::Asciidoc => Box::new(AsciidocExporter::default()),
-            ExportType::Csv => Box::new(CsvExporter::default()),
+            ExportType::Asciidoc => Box::new(AsciidocExporter { extra_columns }),
+            ExportType::Csv => Box::new(CsvExporter { extra_columns }),
             ExportType::Json => Box::new(JsonExporter::default()),
-            ExportType::Markdown => Box::new(MarkdownExporter::default()),
-            ExportType::Orgmode => Box::new(OrgmodeExporter::default()),
+            ExportType::Markdown => Box::new(MarkdownExporter { extra_columns }),
+            ExportType::Orgmode => Box::new(OrgmodeExporter { extra_columns }),
         };
         self.exporters.push(ExporterWithFilename {
             exporter,
@@ -0,0 +1,201 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, Write};
+
+use serde::Serialize;
+
+use crate::benchmark::observer::{Event, Observer, Phase};
+use crate::util::units::{Scalar, Second};
+
+use anyhow::{Context, Result};
+
+/// A single line of the event stream
+#[derive(Serialize, Debug)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum Record<'a> {
+    BenchmarkStarted {
+        #[serde(flatten)]
+        benchmark: &'a BenchmarkInfo,
+    },
+    PhaseStarted {
+        phase: Phase,
+        #[serde(flatten)]
+        benchmark: Option<&'a BenchmarkInfo>,
+        runs: u64,
+    },
+    Run {
+        #[serde(flatten)]
+        benchmark: &'a BenchmarkInfo,
+        iteration: u64,
+        time_real: Second,
+        time_user: Second,
+        time_system: Second,
+        exit_code: Option<i32>,
+        timed_out: bool,
+    },
+    PhaseFinished {
+        phase: Phase,
+        #[serde(flatten)]
+        benchmark: Option<&'a BenchmarkInfo>,
+    },
+    Warning {
+        #[serde(flatten)]
+        benchmark: Option<&'a BenchmarkInfo>,
+        message: String,
+    },
+    Notice {
+        message: &'a str,
+    },
+    BenchmarkFinished {
+        #[serde(flatten)]
+        benchmark: &'a BenchmarkInfo,
+        mean: Second,
+        stddev: Option<Second>,
+        median: Second,
+        min: Second,
+        max: Second,
+    },
+    Comparison {
+        results: Vec<RelativeSpeed<'a>>,
+    },
+}
+
+/// Speed of a benchmark relative to the fastest one
+#[derive(Serialize, Debug)]
+struct RelativeSpeed<'a> {
+    command: &'a str,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    parameters: &'a BTreeMap<String, String>,
+    relative_speed: Scalar,
+    relative_speed_stddev: Option<Scalar>,
+}
+
+#[derive(Serialize, Debug)]
+struct BenchmarkInfo {
+    command: String,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    parameters: BTreeMap<String, String>,
+}
+
+/// Writes every timed run and phase transition as a line of JSON while the benchmarks are
+/// running, see `--export-ndjson`. The time unit is always seconds.
+pub struct NdjsonObserver {
+    writer: RefCell<Box<dyn Write>>,
+
+    /// Commands and parameters of the benchmarks that have been started
+    benchmarks: RefCell<Vec<BenchmarkInfo>>,
+}
+
+impl NdjsonObserver {
+    /// Create an observer that writes to the given file, or to stdout for `-`
+    pub fn new(filename: &str) -> Result<Self> {
+        let writer: Box<dyn Write> = if filename == "-" {
+            Box::new(io::stdout())
+        } else {
+            Box::new(
+                File::create(filename)
+                    .with_context(|| format!("Could not create export file '{}'", filename))?,
+            )
+        };
+
+        Ok(NdjsonObserver {
+            writer: RefCell::new(writer),
+            benchmarks: RefCell::new(vec![]),
+        })
+    }
+
+    fn write(&self, record: &Record) -> Result<()> {
+        let mut line = serde_json::to_vec(record)?;
+        line.push(b'\n');
+
+        // Flush every line, such that other processes can follow the benchmarks
+        let mut writer = self.writer.borrow_mut();
+        writer
+            .write_all(&line)
+            .and_then(|_| writer.flush())
+            .context("Failed to write the NDJSON event stream")
+    }
+}
+
+impl Observer for NdjsonObserver {
+    fn notify(&self, event: &Event) -> Result<()> {
+        if let Event::BenchmarkStarted { number, command } = event {
+            let info = BenchmarkInfo {
+                command: command.get_name(),
+                parameters: command
+                    .get_parameters()
+                    .iter()
+                    .map(|(name, value)| (name.to_string(), value.to_string()))
+                    .collect(),
+            };
+            debug_assert_eq!(self.benchmarks.borrow().len(), *number);
+            self.benchmarks.borrow_mut().push(info);
+        }
+
+        let benchmarks = self.benchmarks.borrow();
+        let benchmark = |number: &usize| &benchmarks[*number];
+        let record = match event {
+            Event::BenchmarkStarted { number, .. } => Record::BenchmarkStarted {
+                benchmark: benchmark(number),
+            },
+            Event::PhaseStarted {
+                phase,
+                number,
+                runs,
+            } => Record::PhaseStarted {
+                phase: *phase,
+                benchmark: number.as_ref().map(benchmark),
+                runs: *runs,
+            },
+            Event::SampleRecorded {
+                number,
+                iteration,
+                result,
+                exit_code,
+            } => Record::Run {
+                benchmark: benchmark(number),
+                iteration: *iteration,
+                time_real: result.time_real,
+                time_user: result.time_user,
+                time_system: result.time_system,
+                exit_code: *exit_code,
+                timed_out: result.timed_out,
+            },
+            Event::PhaseFinished { phase, number } => Record::PhaseFinished {
+                phase: *phase,
+                benchmark: number.as_ref().map(benchmark),
+            },
+            Event::WarningRaised { number, warning } => Record::Warning {
+                benchmark: number.as_ref().map(benchmark),
+                message: warning.to_string(),
+            },
+            Event::Notice { message } => Record::Notice { message },
+            Event::BenchmarkFinished { number, result } => Record::BenchmarkFinished {
+                benchmark: benchmark(number),
+                mean: result.mean,
+                stddev: result.stddev,
+                median: result.median,
+                min: result.min,
+                max: result.max,
+            },
+            Event::ComparisonComputed {
+                comparison: Some(comparison),
+            } => Record::Comparison {
+                results: comparison
+                    .iter()
+                    .map(|item| RelativeSpeed {
+                        command: &item.result.command,
+                        parameters: &item.result.parameters,
+                        relative_speed: item.relative_speed,
+                        relative_speed_stddev: item.relative_speed_stddev,
+                    })
+                    .collect(),
+            },
+            Event::ComparisonComputed { comparison: None } => return Ok(()),
+            Event::WarmupRunFinished { .. } | Event::ProgressUpdated { .. } => return Ok(()),
+        };
+
+        self.write(&record)
+    }
+}
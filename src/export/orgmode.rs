@@ -1,10 +1,17 @@
 use super::markup::Alignment;
 use crate::export::markup::MarkupExporter;
+use crate::export::ExtraColumn;
 
 #[derive(Default)]
-pub struct OrgmodeExporter {}
+pub struct OrgmodeExporter {
+    pub extra_columns: Vec<ExtraColumn>,
+}
 
 impl MarkupExporter for OrgmodeExporter {
+    fn extra_columns(&self) -> &[ExtraColumn] {
+        &self.extra_columns
+    }
+
     fn table_row(&self, cells: &[&str]) -> String {
         format!(
             "| {}  |  {} |\n",
@@ -82,10 +89,23 @@ fn test_orgmode_format_ms() {
             median: 0.1057,
             user: 0.0009,
             system: 0.0011,
+            memory_mean: None,
+            memory_min: None,
+            memory_max: None,
+            resource_usage: None,
             min: 0.1023,
             max: 0.1080,
+            mean_ci: None,
+            median_ci: None,
+            relative_speed_ci: None,
+            p_value: None,
             times: Some(vec![0.1, 0.1, 0.1]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
+            timeouts: 0,
+            accepted_exit_codes: None,
+            failed_runs: 0,
+            batch_size: None,
+            output_hash: None,
             parameters: BTreeMap::new(),
         },
         BenchmarkResult {
@@ -95,10 +115,23 @@ fn test_orgmode_format_ms() {
             median: 2.0050,
             user: 0.0009,
             system: 0.0012,
+            memory_mean: None,
+            memory_min: None,
+            memory_max: None,
+            resource_usage: None,
             min: 2.0020,
             max: 2.0080,
+            mean_ci: None,
+            median_ci: None,
+            relative_speed_ci: None,
+            p_value: None,
             times: Some(vec![2.0, 2.0, 2.0]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
+            timeouts: 0,
+            accepted_exit_codes: None,
+            failed_runs: 0,
+            batch_size: None,
+            output_hash: None,
             parameters: BTreeMap::new(),
         },
     ];
@@ -134,10 +167,23 @@ fn test_orgmode_format_s() {
             median: 2.0050,
             user: 0.0009,
             system: 0.0012,
+            memory_mean: None,
+            memory_min: None,
+            memory_max: None,
+            resource_usage: None,
             min: 2.0020,
             max: 2.0080,
+            mean_ci: None,
+            median_ci: None,
+            relative_speed_ci: None,
+            p_value: None,
             times: Some(vec![2.0, 2.0, 2.0]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
+            timeouts: 0,
+            accepted_exit_codes: None,
+            failed_runs: 0,
+            batch_size: None,
+            output_hash: None,
             parameters: BTreeMap::new(),
         },
         BenchmarkResult {
@@ -147,10 +193,23 @@ fn test_orgmode_format_s() {
             median: 0.1057,
             user: 0.0009,
             system: 0.0011,
+            memory_mean: None,
+            memory_min: None,
+            memory_max: None,
+            resource_usage: None,
             min: 0.1023,
             max: 0.1080,
+            mean_ci: None,
+            median_ci: None,
+            relative_speed_ci: None,
+            p_value: None,
             times: Some(vec![0.1, 0.1, 0.1]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
+            timeouts: 0,
+            accepted_exit_codes: None,
+            failed_runs: 0,
+            batch_size: None,
+            output_hash: None,
             parameters: BTreeMap::new(),
         },
     ];
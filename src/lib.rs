@@ -0,0 +1,80 @@
+//! Command-line benchmarking, usable as a library.
+//!
+//! The [`BenchmarkBuilder`] runs benchmarks without any terminal output (unless requested) and
+//! returns the results:
+//!
+//! ```no_run
+//! use hyperfine::BenchmarkBuilder;
+//!
+//! let results = BenchmarkBuilder::new()
+//!     .command("sleep 0.1")
+//!     .command("sleep 0.2")
+//!     .warmup(3)
+//!     .run()?;
+//!
+//! for result in &results {
+//!     println!("{}: {:.3} s", result.command, result.mean);
+//! }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+#![cfg_attr(
+    all(windows, feature = "windows_process_extensions_main_thread_handle"),
+    feature(windows_process_extensions_main_thread_handle)
+)]
+
+use anyhow::Result;
+
+use benchmark::observer::Observer;
+use benchmark::scheduler::Scheduler;
+use command::Commands;
+use export::ExportManager;
+use options::Options;
+
+pub(crate) mod benchmark;
+pub(crate) mod bootstrap;
+pub(crate) mod error;
+pub(crate) mod outlier_detection;
+pub(crate) mod parameter;
+pub(crate) mod significance;
+pub(crate) mod timer;
+pub(crate) mod util;
+
+pub mod export;
+
+// Internals of the command-line tool
+#[doc(hidden)]
+pub mod cli;
+#[doc(hidden)]
+pub mod command;
+#[doc(hidden)]
+pub mod config;
+#[doc(hidden)]
+pub mod options;
+#[doc(hidden)]
+pub mod output;
+
+pub use benchmark::benchmark_result::{BenchmarkResult, ResourceUsage};
+pub use benchmark::builder::BenchmarkBuilder;
+pub use benchmark::observer;
+pub use bootstrap::ConfidenceInterval;
+pub use error::OptionsError;
+pub use options::{ExitCodes, OutputStyleOption, Shell};
+
+/// Run the benchmarks for all commands, export the results and compare the commands. The
+/// progress and the comparison are reported to the observer, e.g. a `TerminalObserver`. This is
+/// what the command-line tool does after parsing the arguments.
+#[doc(hidden)]
+pub fn run_benchmarks(
+    commands: &Commands,
+    options: &Options,
+    export_manager: &ExportManager,
+    observer: &dyn Observer,
+) -> Result<Vec<BenchmarkResult>> {
+    options.validate_against_command_list(commands)?;
+
+    let mut scheduler = Scheduler::new(commands, options, export_manager, observer);
+    scheduler.run_benchmarks()?;
+    scheduler.report_relative_speed_comparison()?;
+
+    Ok(scheduler.into_results())
+}
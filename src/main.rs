@@ -1,46 +1,51 @@
-#![cfg_attr(
-    all(windows, feature = "windows_process_extensions_main_thread_handle"),
-    feature(windows_process_extensions_main_thread_handle)
-)]
-
 use std::env;
+use std::ffi::OsString;
 
-use benchmark::scheduler::Scheduler;
-use cli::get_cli_arguments;
-use command::Commands;
-use export::ExportManager;
-use options::Options;
+use hyperfine::cli::get_cli_arguments;
+use hyperfine::command::Commands;
+use hyperfine::config::Config;
+use hyperfine::export::{ExportManager, NdjsonObserver};
+use hyperfine::observer::Observer;
+use hyperfine::options::Options;
+use hyperfine::output::terminal::TerminalObserver;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use colored::*;
 
-pub mod benchmark;
-pub mod cli;
-pub mod command;
-pub mod error;
-pub mod export;
-pub mod options;
-pub mod outlier_detection;
-pub mod output;
-pub mod parameter;
-pub mod timer;
-pub mod util;
-
 fn run() -> Result<()> {
     // Enabled ANSI colors on Windows 10
     #[cfg(windows)]
     colored::control::set_virtual_terminal(true).unwrap();
 
-    let cli_arguments = get_cli_arguments(env::args_os());
+    let mut cli_arguments = get_cli_arguments(env::args_os());
+    if let Some(path) = cli_arguments.get_one::<String>("config") {
+        // Options from the configuration file are skipped if they are given on the command
+        // line as well. Commands from both sources are combined.
+        let config_arguments = Config::cli_arguments_from_file(path, &cli_arguments)?;
+        let args: Vec<OsString> = env::args_os()
+            .take(1)
+            .chain(config_arguments.into_iter().map(OsString::from))
+            .chain(env::args_os().skip(1))
+            .collect();
+        cli_arguments = get_cli_arguments(args);
+
+        if cli_arguments.get_many::<String>("command").is_none() {
+            bail!(
+                "No commands to benchmark. Add '[[command]]' tables to the configuration file \
+                 or specify the commands on the command line."
+            );
+        }
+    }
     let options = Options::from_cli_arguments(&cli_arguments)?;
     let commands = Commands::from_cli_arguments(&cli_arguments)?;
     let export_manager = ExportManager::from_cli_arguments(&cli_arguments)?;
 
-    options.validate_against_command_list(&commands)?;
+    let mut observers: Vec<Box<dyn Observer>> = vec![Box::new(TerminalObserver::new(&options))];
+    if let Some(filename) = cli_arguments.get_one::<String>("export-ndjson") {
+        observers.push(Box::new(NdjsonObserver::new(filename)?));
+    }
 
-    let mut scheduler = Scheduler::new(&commands, &options, &export_manager);
-    scheduler.run_benchmarks()?;
-    scheduler.print_relative_speed_comparison();
+    hyperfine::run_benchmarks(&commands, &options, &export_manager, &observers)?;
 
     Ok(())
 }
@@ -1,4 +1,5 @@
 use std::fs::File;
+use std::ops::RangeInclusive;
 use std::path::PathBuf;
 use std::process::{Command, Stdio};
 use std::{cmp, env, fmt, io};
@@ -7,9 +8,11 @@ use anyhow::ensure;
 use atty::Stream;
 use clap::ArgMatches;
 
+use crate::bootstrap::DEFAULT_CONFIDENCE_LEVEL;
 use crate::command::Commands;
 use crate::error::OptionsError;
-use crate::util::units::{Second, Unit};
+use crate::significance::DEFAULT_SIGNIFICANCE_LEVEL;
+use crate::util::units::{Scalar, Second, Unit};
 
 use anyhow::Result;
 
@@ -20,7 +23,7 @@ pub const DEFAULT_SHELL: &str = "sh";
 pub const DEFAULT_SHELL: &str = "cmd.exe";
 
 /// Shell to use for executing benchmarked commands
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Shell {
     /// Default shell command
     Default(&'static str),
@@ -66,6 +69,70 @@ impl Shell {
     }
 }
 
+/// Set of exit codes that are considered successful, e.g. '0,1' or '0-2,127'
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExitCodes(Vec<RangeInclusive<i32>>);
+
+impl Default for ExitCodes {
+    fn default() -> Self {
+        ExitCodes(vec![0..=0])
+    }
+}
+
+impl ExitCodes {
+    /// Parse a comma-separated list of exit codes and ranges of exit codes
+    pub fn parse<'a>(s: &str) -> Result<Self, OptionsError<'a>> {
+        let error = || OptionsError::InvalidExitCodes(s.to_string());
+        let parse_code = |code: &str| code.trim().parse::<i32>().map_err(|_| error());
+
+        let ranges = s
+            .split(',')
+            .map(|part| match part.split_once('-') {
+                Some((start, end)) => {
+                    let (start, end) = (parse_code(start)?, parse_code(end)?);
+                    if start > end {
+                        return Err(error());
+                    }
+                    Ok(start..=end)
+                }
+                None => parse_code(part).map(|code| code..=code),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(ExitCodes(ranges))
+    }
+
+    /// Whether the given exit code is accepted. Processes that have been terminated by a
+    /// signal are not accepted, unless their exit code is given (see `extract_exit_code`).
+    pub fn contains(&self, exit_code: Option<i32>) -> bool {
+        exit_code.map_or(false, |code| {
+            self.0.iter().any(|range| range.contains(&code))
+        })
+    }
+
+    /// Whether only the exit code 0 is accepted
+    pub fn is_default(&self) -> bool {
+        *self == ExitCodes::default()
+    }
+}
+
+impl fmt::Display for ExitCodes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ranges: Vec<String> = self
+            .0
+            .iter()
+            .map(|range| {
+                if range.start() == range.end() {
+                    range.start().to_string()
+                } else {
+                    format!("{}-{}", range.start(), range.end())
+                }
+            })
+            .collect();
+        write!(f, "{}", ranges.join(","))
+    }
+}
+
 /// Action to take when an executed command fails.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CmdFailureAction {
@@ -76,6 +143,80 @@ pub enum CmdFailureAction {
     Ignore,
 }
 
+/// Action to take when a benchmark run exceeds the timeout
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeoutAction {
+    /// Exit with an error message
+    Abort,
+
+    /// Exclude the run from the statistics
+    Exclude,
+}
+
+/// Action to take when the output of a benchmarked command differs from the expected one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputMismatchAction {
+    /// Exit with an error message
+    Abort,
+
+    /// Show a warning and continue
+    Warn,
+}
+
+/// Check that all runs of the benchmarked commands produce the same output, see
+/// `--check-output`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutputCheck {
+    /// Whether to compare the standard error in addition to the standard output
+    pub include_stderr: bool,
+
+    /// What to do if the outputs differ
+    pub mismatch_action: OutputMismatchAction,
+}
+
+/// Time limits for single runs of the different kinds of commands
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Timeouts {
+    /// Time limit for warmup and timing runs of the benchmarked commands
+    pub benchmark: Option<Second>,
+
+    /// Time limit for the setup command
+    pub setup: Option<Second>,
+
+    /// Time limit for the preparation commands
+    pub prepare: Option<Second>,
+
+    /// Time limit for the conclusion commands
+    pub conclude: Option<Second>,
+
+    /// Time limit for the cleanup command
+    pub cleanup: Option<Second>,
+}
+
+/// Order in which the timing runs of the different commands are performed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecutionOrder {
+    /// Perform all runs of a command before moving on to the next one
+    Sequential,
+
+    /// Perform one run of every command per round
+    RoundRobin,
+
+    /// Perform one run of every command per round, in a random order
+    Shuffled { seed: u64 },
+}
+
+/// Number of back-to-back invocations of the benchmarked command per timing run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatchSize {
+    /// Choose the number of invocations such that a timing run takes long enough to be
+    /// measured accurately
+    Auto,
+
+    /// Use a fixed number of invocations
+    Fixed(u64),
+}
+
 /// Output style type option
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum OutputStyleOption {
@@ -110,6 +251,60 @@ impl Default for RunBounds {
     }
 }
 
+impl RunBounds {
+    /// Bounds for the explicitly requested minimum and maximum number of runs (`--min-runs`
+    /// and `--max-runs`). The default minimum is lowered if only a smaller maximum is given.
+    pub fn from_limits<'a>(
+        min: Option<u64>,
+        max: Option<u64>,
+    ) -> Result<RunBounds, OptionsError<'a>> {
+        let default = RunBounds::default();
+        match (min, max) {
+            (Some(min), None) => Ok(RunBounds { min, max: None }),
+            (None, Some(max)) => Ok(RunBounds {
+                min: cmp::min(default.min, max),
+                max: Some(max),
+            }),
+            (Some(min), Some(max)) if min > max => Err(OptionsError::EmptyRunsRange),
+            (Some(min), Some(max)) => Ok(RunBounds {
+                min,
+                max: Some(max),
+            }),
+            (None, None) => Ok(default),
+        }
+    }
+}
+
+/// Default wall-clock budget for adaptive benchmarking (in seconds)
+pub const DEFAULT_PRECISION_TIME_BUDGET: Second = 60.0;
+
+/// Stopping criterion for adaptive benchmarking: keep performing benchmark runs until the
+/// confidence interval of the mean is narrow enough
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PrecisionTarget {
+    /// Target half-width of the confidence interval, relative to the mean
+    pub relative_half_width: Scalar,
+
+    /// Maximum wall-clock time to spend on the timing runs of a single command
+    pub time_budget: Second,
+}
+
+impl PrecisionTarget {
+    /// Parse a precision like "1%" or "1" (percent of the mean)
+    pub fn parse_relative_half_width<'a>(s: &str) -> Result<Scalar, OptionsError<'a>> {
+        let percent = s
+            .trim()
+            .trim_end_matches('%')
+            .trim_end()
+            .parse::<Scalar>()
+            .map_err(|_| OptionsError::InvalidPrecision(s.to_string()))?;
+        if !(percent > 0.0 && percent < 100.0) {
+            return Err(OptionsError::InvalidPrecision(s.to_string()));
+        }
+        Ok(percent / 100.0)
+    }
+}
+
 /// How to handle the output of benchmarked commands
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CommandOutputPolicy {
@@ -124,6 +319,10 @@ pub enum CommandOutputPolicy {
 
     /// Show command output on the terminal
     Inherit,
+
+    /// Feed output through a pipe and compute a hash of it. The standard error is only
+    /// captured if `stderr` is set, and discarded otherwise.
+    Capture { stderr: bool },
 }
 
 impl Default for CommandOutputPolicy {
@@ -141,17 +340,27 @@ impl CommandOutputPolicy {
             CommandOutputPolicy::Pipe => (Stdio::piped(), Stdio::null()),
 
             CommandOutputPolicy::File(path) => {
-                let file = File::create(&path)?;
+                let file = File::create(path)?;
                 (file.into(), Stdio::null())
             }
 
             CommandOutputPolicy::Inherit => (Stdio::inherit(), Stdio::inherit()),
+
+            CommandOutputPolicy::Capture { stderr } => (
+                Stdio::piped(),
+                if *stderr {
+                    Stdio::piped()
+                } else {
+                    Stdio::null()
+                },
+            ),
         };
 
         Ok(streams)
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
 pub enum ExecutorKind {
     Raw,
     Shell(Shell),
@@ -164,13 +373,279 @@ impl Default for ExecutorKind {
     }
 }
 
+/// Value of an option that is either given once for all commands, or once for every single
+/// benchmark command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PerCommand<T>(Vec<T>);
+
+impl<T> PerCommand<T> {
+    /// Use the same value for all commands
+    pub fn all(value: T) -> Self {
+        PerCommand(vec![value])
+    }
+
+    /// Use the given values, one for each command. A single value is used for all commands.
+    pub fn from_values(values: Vec<T>) -> Self {
+        assert!(!values.is_empty());
+        PerCommand(values)
+    }
+
+    /// Value for the command with the given number
+    pub fn get(&self, number: usize) -> &T {
+        if self.0.len() == 1 {
+            &self.0[0]
+        } else {
+            &self.0[number]
+        }
+    }
+
+    /// Number of values that have been specified
+    pub fn num_values(&self) -> usize {
+        self.0.len()
+    }
+}
+
+/// Environment in which the commands of a single benchmark are run
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Environment {
+    /// If set, start from an empty environment that only contains the given variables of the
+    /// environment of hyperfine
+    pub clean: Option<Vec<String>>,
+
+    /// Variables to remove
+    pub unset: Vec<String>,
+
+    /// Variables to set
+    pub set: Vec<(String, String)>,
+}
+
+impl Environment {
+    /// Apply the changes to the environment of the given command
+    pub fn apply(&self, command: &mut Command) {
+        if let Some(keep) = &self.clean {
+            command.env_clear();
+            for name in keep {
+                if let Some(value) = env::var_os(name) {
+                    command.env(name, value);
+                }
+            }
+        }
+
+        for name in &self.unset {
+            command.env_remove(name);
+        }
+
+        for (name, value) in &self.set {
+            command.env(name, value);
+        }
+    }
+}
+
+/// Changes of the environment, either for all benchmarks or just for the one with the given
+/// (zero-based) number
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnvironmentOptions {
+    /// If set, start from an empty environment that only contains the given variables
+    pub clean: Option<Vec<String>>,
+
+    /// Variables to remove
+    pub unset: Vec<(Option<usize>, String)>,
+
+    /// Variables to set
+    pub set: Vec<(Option<usize>, String, String)>,
+}
+
+impl EnvironmentOptions {
+    /// Environment of the benchmark with the given number. Changes for this specific benchmark
+    /// are applied after the ones for all benchmarks.
+    pub fn for_benchmark(&self, number: usize) -> Environment {
+        let mut environment = Environment {
+            clean: self.clean.clone(),
+            ..Environment::default()
+        };
+
+        for specific in [false, true] {
+            for (benchmark, name) in &self.unset {
+                if benchmark.is_some() == specific && benchmark.map_or(true, |b| b == number) {
+                    environment.set.retain(|(n, _)| n != name);
+                    environment.unset.push(name.clone());
+                }
+            }
+            for (benchmark, name, value) in &self.set {
+                if benchmark.is_some() == specific && benchmark.map_or(true, |b| b == number) {
+                    environment.unset.retain(|n| n != name);
+                    environment.set.push((name.clone(), value.clone()));
+                }
+            }
+        }
+
+        environment
+    }
+
+    /// Largest benchmark number for which the environment is changed
+    fn max_benchmark_number(&self) -> Option<usize> {
+        let unset = self.unset.iter().filter_map(|(benchmark, _)| *benchmark);
+        let set = self.set.iter().filter_map(|(benchmark, _, _)| *benchmark);
+        unset.chain(set).max()
+    }
+}
+
+/// Split an optional benchmark number prefix like '2:' from the argument of '--env' or
+/// '--unset-env'. The number is converted to a zero-based index.
+fn split_benchmark_number(argument: &str) -> Option<(Option<usize>, &str)> {
+    match argument.split_once(':') {
+        Some((number, rest))
+            if !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()) =>
+        {
+            match number.parse::<usize>() {
+                Ok(number) if number > 0 => Some((Some(number - 1), rest)),
+                _ => None,
+            }
+        }
+        _ => Some((None, argument)),
+    }
+}
+
+/// Parse an argument to '--env' like 'NAME=VALUE' or '2:NAME=VALUE'
+fn parse_environment_variable(
+    argument: &str,
+) -> Result<(Option<usize>, String, String), OptionsError<'static>> {
+    let error = || OptionsError::InvalidEnvironmentVariable(argument.to_string());
+    let (benchmark, assignment) = split_benchmark_number(argument).ok_or_else(error)?;
+    match assignment.split_once('=') {
+        Some((name, value)) if !name.is_empty() => {
+            Ok((benchmark, name.to_string(), value.to_string()))
+        }
+        _ => Err(error()),
+    }
+}
+
+/// Parse an argument to '--unset-env' like 'NAME' or '2:NAME'
+fn parse_environment_variable_name(
+    argument: &str,
+) -> Result<(Option<usize>, String), OptionsError<'static>> {
+    let error = || OptionsError::InvalidEnvironmentVariableName(argument.to_string());
+    match split_benchmark_number(argument).ok_or_else(error)? {
+        (benchmark, name) if !name.is_empty() && !name.contains('=') => {
+            Ok((benchmark, name.to_string()))
+        }
+        _ => Err(error()),
+    }
+}
+
+/// Parse a non-negative integer argument like '--runs'
+fn parse_u64<'a>(option: &'a str, value: &str) -> Result<u64, OptionsError<'a>> {
+    value
+        .parse::<u64>()
+        .map_err(|e| OptionsError::IntParsingError(option, e))
+}
+
+/// Parse a floating point argument like '--min-benchmarking-time'
+fn parse_float<'a>(option: &'a str, value: &str) -> Result<Scalar, OptionsError<'a>> {
+    value
+        .parse::<Scalar>()
+        .map_err(|e| OptionsError::FloatParsingError(option, e))
+}
+
+/// Parse a positive and finite number of seconds like '--timeout'
+fn parse_seconds<'a>(option: &'a str, value: &str) -> Result<Second, OptionsError<'a>> {
+    let seconds = parse_float(option, value)?;
+    if seconds > 0.0 && seconds.is_finite() {
+        Ok(seconds)
+    } else {
+        Err(OptionsError::InvalidTimeout(option, seconds))
+    }
+}
+
+/// Parse a level like '--significance-level', which has to be strictly between 0 and 1
+fn parse_level<'a>(
+    option: &'a str,
+    value: &str,
+    invalid: fn(Scalar) -> OptionsError<'a>,
+) -> Result<Scalar, OptionsError<'a>> {
+    let level = parse_float(option, value)?;
+    if level > 0.0 && level < 1.0 {
+        Ok(level)
+    } else {
+        Err(invalid(level))
+    }
+}
+
+/// Parse an argument to '--batch' like '10' or 'auto'
+fn parse_batch_size(value: &str) -> Result<BatchSize, OptionsError<'static>> {
+    match value {
+        "auto" => Ok(BatchSize::Auto),
+        size => match size.parse::<u64>() {
+            Ok(size) if size > 0 => Ok(BatchSize::Fixed(size)),
+            _ => Err(OptionsError::InvalidBatchSize(size.to_string())),
+        },
+    }
+}
+
+/// Parse an argument to '--output' like 'pipe' or './file.log'
+fn parse_output_policy(value: &str) -> Result<CommandOutputPolicy, OptionsError<'static>> {
+    match value {
+        "null" => Ok(CommandOutputPolicy::Null),
+        "pipe" => Ok(CommandOutputPolicy::Pipe),
+        "inherit" => Ok(CommandOutputPolicy::Inherit),
+        arg => {
+            let path = PathBuf::from(arg);
+            if path.components().count() <= 1 {
+                return Err(OptionsError::UnknownOutputPolicy(arg.to_string()));
+            }
+            Ok(CommandOutputPolicy::File(path))
+        }
+    }
+}
+
+/// Parse an argument to '--shell' like 'default', 'none' or 'bash -e'
+fn parse_executor_kind(value: &str) -> Result<ExecutorKind, OptionsError<'static>> {
+    match value {
+        "default" => Ok(ExecutorKind::Shell(Shell::default())),
+        "none" => Ok(ExecutorKind::Raw),
+        shell => Ok(ExecutorKind::Shell(Shell::parse_from_str(shell)?)),
+    }
+}
+
+/// Check the value of a single option on its own, with the same parser that is used for the
+/// command line. Options whose values can only be checked together with other options (or not
+/// at all) are accepted.
+pub fn check_option_value<'a>(option: &'a str, value: &str) -> Result<(), OptionsError<'a>> {
+    match option {
+        "warmup" | "runs" | "min-runs" | "max-runs" | "seed" => parse_u64(option, value).map(drop),
+        "timeout"
+        | "setup-timeout"
+        | "prepare-timeout"
+        | "conclude-timeout"
+        | "cleanup-timeout"
+        | "max-benchmarking-time" => parse_seconds(option, value).map(drop),
+        "min-benchmarking-time" => parse_float(option, value).map(drop),
+        "significance-level" => {
+            parse_level(option, value, OptionsError::InvalidSignificanceLevel).map(drop)
+        }
+        "confidence-level" => {
+            parse_level(option, value, OptionsError::InvalidConfidenceLevel).map(drop)
+        }
+        "precision" => PrecisionTarget::parse_relative_half_width(value).map(drop),
+        "batch" => parse_batch_size(value).map(drop),
+        "output" => parse_output_policy(value).map(drop),
+        "shell" => parse_executor_kind(value).map(drop),
+        "accept-exit-codes" => ExitCodes::parse(value).map(drop),
+        "env" => parse_environment_variable(value).map(drop),
+        "unset-env" => value
+            .split(',')
+            .try_for_each(|name| parse_environment_variable_name(name).map(drop)),
+        _ => Ok(()),
+    }
+}
+
 /// The main settings for a hyperfine benchmark session
 pub struct Options {
     /// Upper and lower bound for the number of benchmark runs
     pub run_bounds: RunBounds,
 
     /// Number of warmup runs
-    pub warmup_count: u64,
+    pub warmup_count: PerCommand<u64>,
 
     /// Minimum benchmarking time
     pub min_benchmarking_time: Second,
@@ -178,42 +653,100 @@ pub struct Options {
     /// Whether or not to ignore non-zero exit codes
     pub command_failure_action: CmdFailureAction,
 
+    /// Exit codes of the benchmarked commands that are considered successful
+    pub accepted_exit_codes: PerCommand<ExitCodes>,
+
     /// Command(s) to run before each timing run
-    pub preparation_command: Option<Vec<String>>,
+    pub preparation_command: Option<PerCommand<String>>,
+
+    /// Command(s) to run after each timing run
+    pub conclusion_command: Option<PerCommand<String>>,
+
+    /// Order of the timing runs of the different commands
+    pub execution_order: ExecutionOrder,
+
+    /// If set, every timing run consists of a batch of invocations of the command
+    pub batch_size: Option<BatchSize>,
+
+    /// Time limits for single runs of the benchmarked and the intermediate commands
+    pub timeouts: Timeouts,
+
+    /// What to do with runs that exceed the timeout
+    pub timeout_action: TimeoutAction,
 
     /// Command to run before each *batch* of timing runs, i.e. before each individual benchmark
-    pub setup_command: Option<String>,
+    pub setup_command: Option<PerCommand<String>>,
 
     /// Command to run after each *batch* of timing runs, i.e. after each individual benchmark
-    pub cleanup_command: Option<String>,
+    pub cleanup_command: Option<PerCommand<String>>,
 
     /// What color mode to use for the terminal output
     pub output_style: OutputStyleOption,
 
     /// Determines how we run commands
-    pub executor_kind: ExecutorKind,
+    pub executor_kind: PerCommand<ExecutorKind>,
+
+    /// Directory in which the commands are run, if not the current one
+    pub working_directory: Option<PerCommand<PathBuf>>,
+
+    /// Changes of the environment in which the commands are run
+    pub environment: EnvironmentOptions,
+
+    /// File that is connected to the standard input of the benchmarked commands ('null' for
+    /// the null device). Parameters are substituted in the path.
+    pub input: Option<PerCommand<String>>,
+
+    /// Whether to measure the whole process tree of every run by using a cgroup (Linux only)
+    pub use_cgroup: bool,
 
     /// What to do with the output of the benchmarked command
     pub command_output_policy: CommandOutputPolicy,
 
+    /// If set, check that the commands produce the same output in all runs
+    pub output_check: Option<OutputCheck>,
+
     /// Which time unit to use when displaying resuls
     pub time_unit: Option<Unit>,
+
+    /// Significance level α for the comparison of benchmark results
+    pub significance_level: Scalar,
+
+    /// Confidence level for the bootstrap confidence intervals
+    pub confidence_level: Scalar,
+
+    /// If set, determine the number of runs adaptively instead of using the minimum
+    /// benchmarking time
+    pub precision_target: Option<PrecisionTarget>,
 }
 
 impl Default for Options {
     fn default() -> Options {
         Options {
             run_bounds: RunBounds::default(),
-            warmup_count: 0,
+            warmup_count: PerCommand::all(0),
             min_benchmarking_time: 3.0,
             command_failure_action: CmdFailureAction::RaiseError,
+            accepted_exit_codes: PerCommand::all(ExitCodes::default()),
             preparation_command: None,
+            conclusion_command: None,
+            execution_order: ExecutionOrder::Sequential,
+            batch_size: None,
+            timeouts: Timeouts::default(),
+            timeout_action: TimeoutAction::Abort,
             setup_command: None,
             cleanup_command: None,
             output_style: OutputStyleOption::Full,
-            executor_kind: ExecutorKind::default(),
+            executor_kind: PerCommand::all(ExecutorKind::default()),
+            working_directory: None,
+            environment: EnvironmentOptions::default(),
+            input: None,
+            use_cgroup: false,
             command_output_policy: CommandOutputPolicy::Null,
+            output_check: None,
             time_unit: None,
+            significance_level: DEFAULT_SIGNIFICANCE_LEVEL,
+            confidence_level: DEFAULT_CONFIDENCE_LEVEL,
+            precision_target: None,
         }
     }
 }
@@ -224,14 +757,16 @@ impl Options {
         let param_to_u64 = |param| {
             matches
                 .get_one::<String>(param)
-                .map(|n| {
-                    n.parse::<u64>()
-                        .map_err(|e| OptionsError::IntParsingError(param, e))
-                })
+                .map(|n| parse_u64(param, n))
                 .transpose()
         };
 
-        options.warmup_count = param_to_u64("warmup")?.unwrap_or(options.warmup_count);
+        if let Some(values) = matches.get_many::<String>("warmup") {
+            let values = values
+                .map(|n| parse_u64("warmup", n))
+                .collect::<Result<_, _>>()?;
+            options.warmup_count = PerCommand::from_values(values);
+        }
 
         let mut min_runs = param_to_u64("min-runs")?;
         let mut max_runs = param_to_u64("max-runs")?;
@@ -241,53 +776,67 @@ impl Options {
             max_runs = Some(runs);
         }
 
-        match (min_runs, max_runs) {
-            (Some(min), None) => {
-                options.run_bounds.min = min;
-            }
-            (None, Some(max)) => {
-                // Since the minimum was not explicit we lower it if max is below the default min.
-                options.run_bounds.min = cmp::min(options.run_bounds.min, max);
-                options.run_bounds.max = Some(max);
-            }
-            (Some(min), Some(max)) if min > max => {
-                return Err(OptionsError::EmptyRunsRange);
-            }
-            (Some(min), Some(max)) => {
-                options.run_bounds.min = min;
-                options.run_bounds.max = Some(max);
-            }
-            (None, None) => {}
+        options.run_bounds = RunBounds::from_limits(min_runs, max_runs)?;
+
+        options.execution_order = match matches
+            .get_one::<String>("execution-order")
+            .map(|s| s.as_str())
+        {
+            Some("round-robin") => ExecutionOrder::RoundRobin,
+            Some("shuffled") => ExecutionOrder::Shuffled {
+                seed: param_to_u64("seed")?.unwrap_or_else(rand::random),
+            },
+            _ => ExecutionOrder::Sequential,
         };
 
-        options.setup_command = matches.get_one::<String>("setup").map(String::from);
+        options.batch_size = matches
+            .get_one::<String>("batch")
+            .map(|size| parse_batch_size(size))
+            .transpose()?;
 
-        options.preparation_command = matches
-            .get_many::<String>("prepare")
-            .map(|values| values.map(String::from).collect::<Vec<String>>());
+        let per_command = |name| {
+            matches
+                .get_many::<String>(name)
+                .map(|values| PerCommand::from_values(values.map(String::from).collect()))
+        };
 
-        options.cleanup_command = matches.get_one::<String>("cleanup").map(String::from);
+        options.setup_command = per_command("setup");
+        options.preparation_command = per_command("prepare");
+        options.conclusion_command = per_command("conclude");
+        options.cleanup_command = per_command("cleanup");
+        options.input = per_command("input");
 
         options.command_output_policy = if matches.get_flag("show-output") {
             CommandOutputPolicy::Inherit
-        } else if let Some(output) = matches.get_one::<String>("output").map(|s| s.as_str()) {
-            match output {
-                "null" => CommandOutputPolicy::Null,
-                "pipe" => CommandOutputPolicy::Pipe,
-                "inherit" => CommandOutputPolicy::Inherit,
-                arg => {
-                    let path = PathBuf::from(arg);
-                    if path.components().count() <= 1 {
-                        return Err(OptionsError::UnknownOutputPolicy(arg.to_string()));
-                    }
-                    CommandOutputPolicy::File(path)
-                }
-            }
+        } else if let Some(output) = matches.get_one::<String>("output") {
+            parse_output_policy(output)?
         } else {
             CommandOutputPolicy::Null
         };
 
+        if matches.get_flag("check-output") {
+            let output_check = OutputCheck {
+                include_stderr: matches.get_flag("check-stderr"),
+                mismatch_action: match matches
+                    .get_one::<String>("on-output-mismatch")
+                    .map(|s| s.as_str())
+                {
+                    Some("warn") => OutputMismatchAction::Warn,
+                    _ => OutputMismatchAction::Abort,
+                },
+            };
+            options.command_output_policy = CommandOutputPolicy::Capture {
+                stderr: output_check.include_stderr,
+            };
+            options.output_check = Some(output_check);
+        }
+
+        // The event stream of `--export-ndjson -` must not be mixed with the terminal output
+        let ndjson_to_stdout = matches
+            .get_one::<String>("export-ndjson")
+            .map_or(false, |filename| filename == "-");
         options.output_style = match matches.get_one::<String>("style").map(|s| s.as_str()) {
+            _ if ndjson_to_stdout => OutputStyleOption::Disabled,
             Some("full") => OutputStyleOption::Full,
             Some("basic") => OutputStyleOption::Basic,
             Some("nocolor") => OutputStyleOption::NoColor,
@@ -322,26 +871,77 @@ impl Options {
             OutputStyleOption::Disabled => {}
         };
 
-        options.executor_kind = if matches.get_flag("no-shell") {
-            ExecutorKind::Raw
-        } else {
-            match (
-                matches.get_flag("debug-mode"),
-                matches.get_one::<String>("shell"),
-            ) {
-                (false, Some(shell)) if shell == "default" => ExecutorKind::Shell(Shell::default()),
-                (false, Some(shell)) if shell == "none" => ExecutorKind::Raw,
-                (false, Some(shell)) => ExecutorKind::Shell(Shell::parse_from_str(shell)?),
-                (false, None) => ExecutorKind::Shell(Shell::default()),
-                (true, Some(shell)) => ExecutorKind::Mock(Some(shell.into())),
-                (true, None) => ExecutorKind::Mock(None),
-            }
+        let debug_mode = matches.get_flag("debug-mode");
+        options.executor_kind = match matches.get_many::<String>("shell") {
+            _ if matches.get_flag("no-shell") => PerCommand::all(ExecutorKind::Raw),
+            Some(shells) => PerCommand::from_values(
+                shells
+                    .map(|shell| match shell.as_str() {
+                        shell if debug_mode => Ok(ExecutorKind::Mock(Some(shell.into()))),
+                        shell => parse_executor_kind(shell),
+                    })
+                    .collect::<Result<_, OptionsError>>()?,
+            ),
+            None if debug_mode => PerCommand::all(ExecutorKind::Mock(None)),
+            None => PerCommand::all(ExecutorKind::Shell(Shell::default())),
+        };
+
+        options.working_directory = matches
+            .get_many::<String>("working-dir")
+            .map(|values| PerCommand::from_values(values.map(PathBuf::from).collect()));
+
+        options.environment = EnvironmentOptions {
+            clean: matches.get_flag("clean-env").then(|| {
+                matches
+                    .get_many::<String>("keep-env")
+                    .map_or_else(Vec::new, |names| names.cloned().collect())
+            }),
+            unset: matches
+                .get_many::<String>("unset-env")
+                .into_iter()
+                .flatten()
+                .map(|name| parse_environment_variable_name(name))
+                .collect::<Result<_, _>>()?,
+            set: matches
+                .get_many::<String>("env")
+                .into_iter()
+                .flatten()
+                .map(|variable| parse_environment_variable(variable))
+                .collect::<Result<_, _>>()?,
+        };
+
+        options.use_cgroup = matches.get_flag("cgroup");
+
+        let parse_timeout = |name| -> Result<Option<Second>, OptionsError> {
+            matches
+                .get_one::<String>(name)
+                .map(|timeout| parse_seconds(name, timeout))
+                .transpose()
+        };
+        options.timeouts = Timeouts {
+            benchmark: parse_timeout("timeout")?,
+            setup: parse_timeout("setup-timeout")?,
+            prepare: parse_timeout("prepare-timeout")?,
+            conclude: parse_timeout("conclude-timeout")?,
+            cleanup: parse_timeout("cleanup-timeout")?,
+        };
+
+        options.timeout_action = match matches.get_one::<String>("on-timeout").map(|s| s.as_str()) {
+            Some("exclude") => TimeoutAction::Exclude,
+            _ => TimeoutAction::Abort,
         };
 
         if matches.get_flag("ignore-failure") {
             options.command_failure_action = CmdFailureAction::Ignore;
         }
 
+        if let Some(values) = matches.get_many::<String>("accept-exit-codes") {
+            let values = values
+                .map(|codes| ExitCodes::parse(codes))
+                .collect::<Result<_, _>>()?;
+            options.accepted_exit_codes = PerCommand::from_values(values);
+        }
+
         options.time_unit = match matches.get_one::<String>("time-unit").map(|s| s.as_str()) {
             Some("millisecond") => Some(Unit::MilliSecond),
             Some("second") => Some(Unit::Second),
@@ -349,21 +949,88 @@ impl Options {
         };
 
         if let Some(time) = matches.get_one::<String>("min-benchmarking-time") {
-            options.min_benchmarking_time = time
-                .parse::<f64>()
-                .map_err(|e| OptionsError::FloatParsingError("min-benchmarking-time", e))?;
+            options.min_benchmarking_time = parse_float("min-benchmarking-time", time)?;
+        }
+
+        if let Some(alpha) = matches.get_one::<String>("significance-level") {
+            options.significance_level = parse_level(
+                "significance-level",
+                alpha,
+                OptionsError::InvalidSignificanceLevel,
+            )?;
+        }
+
+        if let Some(level) = matches.get_one::<String>("confidence-level") {
+            options.confidence_level = parse_level(
+                "confidence-level",
+                level,
+                OptionsError::InvalidConfidenceLevel,
+            )?;
+        }
+
+        if let Some(precision) = matches.get_one::<String>("precision") {
+            let time_budget =
+                parse_timeout("max-benchmarking-time")?.unwrap_or(DEFAULT_PRECISION_TIME_BUDGET);
+            options.precision_target = Some(PrecisionTarget {
+                relative_half_width: PrecisionTarget::parse_relative_half_width(precision)?,
+                time_budget,
+            });
         }
 
         Ok(options)
     }
 
     pub fn validate_against_command_list(&self, commands: &Commands) -> Result<()> {
-        if let Some(preparation_command) = &self.preparation_command {
+        let counts = [
+            ("warmup", Some(self.warmup_count.num_values())),
+            (
+                "setup",
+                self.setup_command.as_ref().map(PerCommand::num_values),
+            ),
+            (
+                "prepare",
+                self.preparation_command
+                    .as_ref()
+                    .map(PerCommand::num_values),
+            ),
+            (
+                "conclude",
+                self.conclusion_command.as_ref().map(PerCommand::num_values),
+            ),
+            (
+                "cleanup",
+                self.cleanup_command.as_ref().map(PerCommand::num_values),
+            ),
+            ("shell", Some(self.executor_kind.num_values())),
+            (
+                "working-dir",
+                self.working_directory.as_ref().map(PerCommand::num_values),
+            ),
+            ("input", self.input.as_ref().map(PerCommand::num_values)),
+            (
+                "accept-exit-codes",
+                Some(self.accepted_exit_codes.num_values()),
+            ),
+        ];
+
+        for (option, count) in counts {
+            if let Some(count) = count {
+                ensure!(
+                    count <= 1 || commands.num_commands() == count,
+                    "The '--{}' option has to be provided just once or N times, where N is the \
+                     number of benchmark commands.",
+                    option
+                );
+            }
+        }
+
+        if let Some(number) = self.environment.max_benchmark_number() {
             ensure!(
-                preparation_command.len() <= 1
-                    || commands.num_commands() == preparation_command.len(),
-                "The '--prepare' option has to be provided just once or N times, where N is the \
-             number of benchmark commands."
+                number < commands.num_commands(),
+                "The environment of benchmark {} can not be changed, as there are only {} \
+                 benchmark commands.",
+                number + 1,
+                commands.num_commands()
             );
         }
 
@@ -414,3 +1081,101 @@ fn test_can_parse_shell_command_line_from_str() {
         OptionsError::EmptyShell
     ));
 }
+
+#[test]
+fn test_parse_precision() {
+    use approx::assert_relative_eq;
+
+    assert_relative_eq!(
+        0.01,
+        PrecisionTarget::parse_relative_half_width("1%").unwrap()
+    );
+    assert_relative_eq!(
+        0.005,
+        PrecisionTarget::parse_relative_half_width("0.5").unwrap()
+    );
+    assert_relative_eq!(
+        0.025,
+        PrecisionTarget::parse_relative_half_width(" 2.5 %").unwrap()
+    );
+
+    // Error cases
+    for input in &["", "%", "abc", "0%", "-1%", "100%"] {
+        assert!(matches!(
+            PrecisionTarget::parse_relative_half_width(input).unwrap_err(),
+            OptionsError::InvalidPrecision(_)
+        ));
+    }
+}
+
+#[test]
+fn test_parse_environment_variables() {
+    assert_eq!(
+        parse_environment_variable("FOO=bar=baz").unwrap(),
+        (None, "FOO".into(), "bar=baz".into())
+    );
+    assert_eq!(
+        parse_environment_variable("2:FOO=").unwrap(),
+        (Some(1), "FOO".into(), "".into())
+    );
+    assert_eq!(
+        parse_environment_variable("a:b=c").unwrap(),
+        (None, "a:b".into(), "c".into())
+    );
+    assert!(parse_environment_variable("FOO").is_err());
+    assert!(parse_environment_variable("=bar").is_err());
+    assert!(parse_environment_variable("0:FOO=bar").is_err());
+
+    assert_eq!(
+        parse_environment_variable_name("3:FOO").unwrap(),
+        (Some(2), "FOO".into())
+    );
+    assert!(parse_environment_variable_name("FOO=bar").is_err());
+    assert!(parse_environment_variable_name("1:").is_err());
+}
+
+#[test]
+fn test_environment_for_benchmark() {
+    let options = EnvironmentOptions {
+        clean: None,
+        unset: vec![(None, "A".into()), (Some(1), "B".into())],
+        set: vec![
+            (Some(0), "A".into(), "specific".into()),
+            (None, "B".into(), "all".into()),
+        ],
+    };
+
+    assert_eq!(
+        options.for_benchmark(0),
+        Environment {
+            clean: None,
+            unset: vec![],
+            set: vec![("B".into(), "all".into()), ("A".into(), "specific".into())],
+        }
+    );
+    assert_eq!(
+        options.for_benchmark(1),
+        Environment {
+            clean: None,
+            unset: vec!["A".into(), "B".into()],
+            set: vec![],
+        }
+    );
+}
+
+#[test]
+fn test_parse_exit_codes() {
+    let codes = ExitCodes::parse("0, 2-4,127").unwrap();
+    assert_eq!(codes.to_string(), "0,2-4,127");
+    assert!(codes.contains(Some(0)));
+    assert!(!codes.contains(Some(1)));
+    assert!(codes.contains(Some(3)));
+    assert!(codes.contains(Some(127)));
+    assert!(!codes.contains(None));
+
+    assert!(ExitCodes::parse("0").unwrap().is_default());
+    assert!(ExitCodes::parse("").is_err());
+    assert!(ExitCodes::parse("1,").is_err());
+    assert!(ExitCodes::parse("4-2").is_err());
+    assert!(ExitCodes::parse("one").is_err());
+}
@@ -1,4 +1,4 @@
-use crate::util::units::{Second, Unit};
+use crate::util::units::{Byte, Second, Unit};
 
 /// Format the given duration as a string. The output-unit can be enforced by setting `unit` to
 /// `Some(target_unit)`. If `unit` is `None`, it will be determined automatically.
@@ -23,6 +23,49 @@ pub fn format_duration_value(duration: Second, unit: Option<Unit>) -> (String, U
     }
 }
 
+/// Format a memory size using binary prefixes, e.g. "2.5 MiB"
+pub fn format_bytes(bytes: Byte) -> String {
+    const PREFIXES: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+
+    let mut value = bytes as f64 / 1024.0;
+    let mut prefix = PREFIXES[0];
+    for next_prefix in &PREFIXES[1..] {
+        if value < 1024.0 {
+            break;
+        }
+        value /= 1024.0;
+        prefix = next_prefix;
+    }
+
+    format!("{:.1} {}", value, prefix)
+}
+
+#[test]
+fn test_format_bytes() {
+    assert_eq!("0 B", format_bytes(0));
+    assert_eq!("1023 B", format_bytes(1023));
+    assert_eq!("1.0 KiB", format_bytes(1024));
+    assert_eq!("2.5 MiB", format_bytes(5 * 512 * 1024));
+    assert_eq!("3.0 GiB", format_bytes(3 * 1024 * 1024 * 1024));
+}
+
+/// Format a fraction like 0.95 as a percentage ("95%")
+pub fn format_percentage(fraction: f64) -> String {
+    format!("{}%", (fraction * 10000.0).round() / 100.0)
+}
+
+#[test]
+fn test_format_percentage() {
+    assert_eq!("95%", format_percentage(0.95));
+    assert_eq!("99.9%", format_percentage(0.999));
+    assert_eq!("57%", format_percentage(0.57));
+    assert_eq!("0.01%", format_percentage(0.0001));
+}
+
 #[test]
 fn test_format_duration_unit_basic() {
     let (out_str, out_unit) = format_duration_unit(1.3, None);
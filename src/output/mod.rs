@@ -1,3 +1,4 @@
 pub mod format;
 pub mod progress_bar;
+pub mod terminal;
 pub mod warnings;
@@ -0,0 +1,311 @@
+use std::cell::{Cell, RefCell};
+
+use anyhow::Result;
+use colored::*;
+use indicatif::ProgressBar;
+
+use crate::benchmark::benchmark_result::BenchmarkResult;
+use crate::benchmark::observer::{Event, Observer, Phase, ProgressStatus};
+use crate::benchmark::relative_speed::BenchmarkResultWithRelativeSpeed;
+use crate::options::{ExecutionOrder, Options, OutputStyleOption};
+use crate::output::format::{
+    format_bytes, format_duration, format_duration_unit, format_percentage,
+};
+use crate::output::progress_bar::get_progress_bar;
+
+/// Progress bars, statistics and warnings of the benchmarks on the terminal. Only the warnings
+/// are shown (on stderr) with `--style=none`.
+pub struct TerminalObserver<'a> {
+    options: &'a Options,
+
+    /// Progress bar of the current phase
+    progress_bar: RefCell<Option<ProgressBar>>,
+
+    /// Warnings of the current benchmark, which are shown after its statistics
+    warnings: RefCell<Vec<String>>,
+
+    /// Number of the benchmark that finished last
+    last_finished: Cell<Option<usize>>,
+}
+
+impl<'a> TerminalObserver<'a> {
+    pub fn new(options: &'a Options) -> Self {
+        TerminalObserver {
+            options,
+            progress_bar: RefCell::new(None),
+            warnings: RefCell::new(vec![]),
+            last_finished: Cell::new(None),
+        }
+    }
+
+    fn enabled(&self) -> bool {
+        self.options.output_style != OutputStyleOption::Disabled
+    }
+
+    /// With an interleaved execution order, the header is shown together with the statistics
+    /// after all timing runs.
+    fn interleaved(&self) -> bool {
+        self.options.execution_order != ExecutionOrder::Sequential
+    }
+
+    fn format_progress_status(&self, status: &ProgressStatus) -> String {
+        match status {
+            ProgressStatus::Precision(precision) => format!(
+                "Current precision: {}",
+                precision
+                    .map_or("-".into(), |p| format!("±{}", format_percentage(p)))
+                    .green()
+            ),
+            ProgressStatus::MeanEstimate(None) => "Current estimate: -".to_string(),
+            ProgressStatus::MeanEstimate(Some(mean)) => format!(
+                "Current estimate: {}",
+                format_duration(*mean, self.options.time_unit).green()
+            ),
+            ProgressStatus::CommandsRemaining(remaining) => {
+                format!("{} commands remaining", remaining)
+            }
+        }
+    }
+
+    fn print_header(&self, number: usize, name: &str) {
+        println!(
+            "{}{}: {}",
+            "Benchmark ".bold(),
+            (number + 1).to_string().bold(),
+            name,
+        );
+    }
+
+    fn print_statistics(&self, result: &BenchmarkResult) {
+        let (mean_str, time_unit) = format_duration_unit(result.mean, self.options.time_unit);
+        let min_str = format_duration(result.min, Some(time_unit));
+        let max_str = format_duration(result.max, Some(time_unit));
+        let num_runs = result.times.as_ref().map_or(0, Vec::len);
+        let num_str = match result.batch_size {
+            Some(batch_size) if batch_size > 1 => {
+                format!("{} runs of {} invocations", num_runs, batch_size)
+            }
+            _ => format!("{} runs", num_runs),
+        };
+
+        let user_str = format_duration(result.user, Some(time_unit));
+        let system_str = format_duration(result.system, Some(time_unit));
+        let memory_str = result.memory_mean.map_or("".into(), |memory| {
+            format!(", Memory: {}", format_bytes(memory).blue())
+        });
+
+        if let Some(stddev) = result.stddev {
+            let stddev_str = format_duration(stddev, Some(time_unit));
+
+            println!(
+                "  Time ({} ± {}):     {:>8} ± {:>8}    [User: {}, System: {}{}]",
+                "mean".green().bold(),
+                "σ".green(),
+                mean_str.green().bold(),
+                stddev_str.green(),
+                user_str.blue(),
+                system_str.blue(),
+                memory_str
+            );
+
+            println!(
+                "  Range ({} … {}):   {:>8} … {:>8}    {}",
+                "min".cyan(),
+                "max".purple(),
+                min_str.cyan(),
+                max_str.purple(),
+                num_str.dimmed()
+            );
+
+            if let (Some(mean_ci), Some(median_ci)) = (&result.mean_ci, &result.median_ci) {
+                let percentage = format_percentage(mean_ci.level);
+                // Align with the lines above. The padding can not be computed by `format!`
+                // as the label contains (invisible) color codes.
+                let padding = 21usize.saturating_sub(12 + percentage.len());
+                println!(
+                    "  CI ({}, {}):{}{:>8} … {:>8}    [Median: {} … {}]",
+                    "mean".green(),
+                    percentage,
+                    " ".repeat(padding),
+                    format_duration(mean_ci.lower, Some(time_unit)).green(),
+                    format_duration(mean_ci.upper, Some(time_unit)).green(),
+                    format_duration(median_ci.lower, Some(time_unit)).blue(),
+                    format_duration(median_ci.upper, Some(time_unit)).blue(),
+                );
+            }
+        } else {
+            println!(
+                "  Time ({} ≡):        {:>8}  {:>8}     [User: {}, System: {}{}]",
+                "abs".green().bold(),
+                mean_str.green().bold(),
+                "        ", // alignment
+                user_str.blue(),
+                system_str.blue(),
+                memory_str
+            );
+        }
+    }
+
+    fn print_comparison(&self, comparison: Option<&[BenchmarkResultWithRelativeSpeed]>) {
+        let (fastest, others) = match comparison.and_then(|c| c.split_first()) {
+            Some(comparison) => comparison,
+            None => {
+                eprintln!(
+                    "{}: The benchmark comparison could not be computed as some benchmark times are zero. \
+                     This could be caused by background interference during the initial calibration phase \
+                     of hyperfine, in combination with very fast commands (faster than a few milliseconds). \
+                     Try to re-run the benchmark on a quiet system. If it does not help, you command is \
+                     most likely too fast to be accurately benchmarked by hyperfine.",
+                    "Note".bold().red()
+                );
+                return;
+            }
+        };
+
+        println!("{}", "Summary".bold());
+        println!("  '{}' ran", fastest.result.command.cyan());
+
+        for item in others {
+            println!(
+                "{}{} times faster than '{}'{}",
+                format!("{:8.2}", item.relative_speed).bold().green(),
+                if let Some(stddev) = item.relative_speed_stddev {
+                    format!(" ± {}", format!("{:.2}", stddev).green())
+                } else {
+                    "".into()
+                },
+                &item.result.command.magenta(),
+                self.format_comparison_details(item)
+            );
+        }
+    }
+
+    /// Confidence interval of the relative speed and result of the significance test, if
+    /// available
+    fn format_comparison_details(&self, item: &BenchmarkResultWithRelativeSpeed) -> String {
+        let mut details = vec![];
+
+        if let Some(ci) = item.result.relative_speed_ci {
+            details.push(format!(
+                "{} CI: {:.2} … {:.2}",
+                format_percentage(ci.level),
+                ci.lower,
+                ci.upper
+            ));
+        }
+
+        if let Some(ttest) = item.ttest {
+            let alpha = self.options.significance_level;
+            details.push(format!(
+                "{} at α = {}, p = {:.2e}",
+                if ttest.is_significant(alpha) {
+                    "statistically significant"
+                } else {
+                    "not statistically significant"
+                },
+                alpha,
+                ttest.p_value
+            ));
+        }
+
+        if details.is_empty() {
+            "".into()
+        } else {
+            format!(" ({})", details.join("; ")).dimmed().to_string()
+        }
+    }
+}
+
+impl Observer for TerminalObserver<'_> {
+    fn notify(&self, event: &Event) -> Result<()> {
+        match event {
+            Event::BenchmarkStarted { number, command } => {
+                if self.enabled() && !self.interleaved() {
+                    self.print_header(*number, &command.get_name());
+                }
+            }
+            Event::PhaseStarted { phase, runs, .. } => {
+                if self.enabled() {
+                    let message = match phase {
+                        Phase::Warmup => "Performing warmup runs",
+                        Phase::Timing => "Initial time measurement",
+                    };
+                    *self.progress_bar.borrow_mut() =
+                        Some(get_progress_bar(*runs, message, self.options.output_style));
+                }
+            }
+            Event::WarmupRunFinished { .. } | Event::SampleRecorded { .. } => {
+                if let Some(bar) = self.progress_bar.borrow().as_ref() {
+                    bar.inc(1)
+                }
+            }
+            Event::ProgressUpdated {
+                estimated_total,
+                status,
+            } => {
+                if let Some(bar) = self.progress_bar.borrow().as_ref() {
+                    if let Some(total) = estimated_total {
+                        bar.set_length(*total);
+                    }
+                    bar.set_message(self.format_progress_status(status))
+                }
+            }
+            Event::PhaseFinished { .. } => {
+                if let Some(bar) = self.progress_bar.borrow_mut().take() {
+                    bar.finish_and_clear()
+                }
+            }
+            Event::WarningRaised {
+                number: None,
+                warning,
+            } => {
+                eprintln!("{}: {}\n", "Warning".yellow(), warning);
+            }
+            Event::WarningRaised {
+                number: Some(number),
+                warning,
+            } => {
+                let warning = format!("  {}: {}", "Warning".yellow(), warning);
+                if self.last_finished.get() == Some(*number) {
+                    eprintln!("{}\n ", warning);
+                } else {
+                    self.warnings.borrow_mut().push(warning);
+                }
+            }
+            Event::BenchmarkFinished { number, result } => {
+                if self.enabled() {
+                    if self.interleaved() {
+                        self.print_header(*number, &result.command);
+                    }
+                    self.print_statistics(result);
+                }
+
+                let warnings = self.warnings.take();
+                if !warnings.is_empty() {
+                    eprintln!(" ");
+
+                    for warning in &warnings {
+                        eprintln!("{}", warning);
+                    }
+                }
+
+                if self.enabled() {
+                    println!(" ");
+                }
+                self.last_finished.set(Some(*number));
+            }
+            Event::Notice { message } => {
+                if self.enabled() {
+                    println!("{}\n", message);
+                }
+            }
+            Event::ComparisonComputed { comparison } => {
+                if self.enabled() {
+                    self.print_comparison(*comparison);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
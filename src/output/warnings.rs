@@ -1,8 +1,9 @@
 use std::fmt;
 
 use crate::benchmark::MIN_EXECUTION_TIME;
-use crate::output::format::format_duration;
-use crate::util::units::Second;
+use crate::options::ExitCodes;
+use crate::output::format::{format_duration, format_percentage};
+use crate::util::units::{Scalar, Second};
 
 pub struct OutlierWarningOptions {
     pub warmup_in_use: bool,
@@ -11,24 +12,54 @@ pub struct OutlierWarningOptions {
 
 /// A list of all possible warnings
 pub enum Warnings {
-    FastExecutionTime,
-    NonZeroExitCode,
+    /// The argument indicates whether batch mode is in use
+    FastExecutionTime(bool),
+    /// Number of runs with an exit code that is not accepted, and the accepted exit codes
+    UnacceptedExitCode(u64, ExitCodes),
     SlowInitialRun(Second, OutlierWarningOptions),
     OutliersDetected(OutlierWarningOptions),
+    PrecisionNotReached(Scalar, Scalar),
+    TimeoutsExcluded(u64, Second),
+    InconsistentOutput(u64),
+    /// The output differs from the one of the given command
+    DifferentOutput(String),
+    /// No cgroup could be created for `--cgroup`, for the given reason
+    CgroupUnavailable(String),
+    /// The cgroup controllers that could not be enabled for `--cgroup`
+    CgroupControllersUnavailable(Vec<&'static str>),
 }
 
 impl fmt::Display for Warnings {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match *self {
-            Warnings::FastExecutionTime => write!(
+            Warnings::FastExecutionTime(batch_in_use) => write!(
                 f,
-                "Command took less than {:.0} ms to complete. Note that the results might be \
+                "{} took less than {:.0} ms to complete. Note that the results might be \
                 inaccurate because hyperfine can not calibrate the shell startup time much \
-                more precise than this limit. You can try to use the `-N`/`--shell=none` \
+                more precise than this limit. You can try to {}, or use the `-N`/`--shell=none` \
                 option to disable the shell completely.",
-                MIN_EXECUTION_TIME * 1e3
+                if batch_in_use { "A batch of invocations" } else { "Command" },
+                MIN_EXECUTION_TIME * 1e3,
+                if batch_in_use {
+                    "increase the batch size with the `--batch` option"
+                } else {
+                    "use the `--batch=auto` option to run the command multiple times per \
+                    measurement"
+                }
             ),
-            Warnings::NonZeroExitCode => write!(f, "Ignoring non-zero exit code."),
+            Warnings::UnacceptedExitCode(count, ref accepted) => {
+                let runs = if count == 1 { "run" } else { "runs" };
+                if accepted.is_default() {
+                    write!(f, "Ignoring the non-zero exit code of {} {}.", count, runs)
+                } else {
+                    write!(
+                        f,
+                        "Ignoring the exit code of {} {}, which is not one of the accepted exit \
+                         codes ({}).",
+                        count, runs, accepted
+                    )
+                }
+            }
             Warnings::SlowInitialRun(time_first_run, ref options) => write!(
                 f,
                 "The first benchmarking run for this command was significantly slower than the \
@@ -64,6 +95,53 @@ impl fmt::Display for Warnings {
                     " It might help to use the '--warmup' or '--prepare' options."
                 }
             ),
+            Warnings::PrecisionNotReached(precision, target) => write!(
+                f,
+                "The requested precision of ±{target} could not be reached within the limits \
+                 (achieved: ±{precision}). Consider increasing '--max-runs' or \
+                 '--max-benchmarking-time', or re-running this benchmark on a quiet system.",
+                target = format_percentage(target),
+                precision = format_percentage(precision),
+            ),
+            Warnings::TimeoutsExcluded(count, timeout) => write!(
+                f,
+                "{count} {runs} exceeded the timeout of {timeout} and {have} been excluded from \
+                 the statistics.",
+                count = count,
+                runs = if count == 1 { "run" } else { "runs" },
+                timeout = format_duration(timeout, None),
+                have = if count == 1 { "has" } else { "have" },
+            ),
+            Warnings::InconsistentOutput(count) => write!(
+                f,
+                "{count} {runs} produced a different output than the first run. The command \
+                 might not be deterministic.",
+                count = count,
+                runs = if count == 1 { "run" } else { "runs" },
+            ),
+            Warnings::DifferentOutput(ref other) => write!(
+                f,
+                "The output of this command differs from the output of '{}'.",
+                other
+            ),
+            Warnings::CgroupUnavailable(ref reason) => write!(
+                f,
+                "Could not create a cgroup for the measurement ({}). Only the benchmarked \
+                 processes themselves will be measured, not the whole process trees.",
+                reason
+            ),
+            Warnings::CgroupControllersUnavailable(ref controllers) => write!(
+                f,
+                "The cgroup controller{} {} could not be enabled. The peak memory usage and \
+                 block I/O are only measured for the benchmarked processes themselves, not the \
+                 whole process trees.",
+                if controllers.len() == 1 { "" } else { "s" },
+                controllers
+                    .iter()
+                    .map(|controller| format!("'{}'", controller))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
         }
     }
 }
@@ -0,0 +1,156 @@
+//! Combinations of parameter values, i.e. the cross product of the values of all parameters.
+//! Zipped parameters (`--parameter-zip`) vary in lockstep instead of being combined with each
+//! other, and combinations can be excluded with filters (`--parameter-filter`).
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Result};
+
+use super::filter::Filter;
+use super::{ParameterNameAndValue, ParameterNameAndValues, MAX_PARAMETERS};
+
+/// Compute all parameter combinations that pass the filters, together with the number of
+/// combinations that have been excluded by them. The values of the first parameter vary
+/// fastest.
+pub fn combinations<'a>(
+    param_names_and_values: Vec<ParameterNameAndValues<'a>>,
+    zipped: &[Vec<&str>],
+    filters: &[Filter],
+) -> Result<(Vec<Vec<ParameterNameAndValue<'a>>>, usize)> {
+    {
+        let duplicates = find_duplicates(param_names_and_values.iter().map(|(name, _)| *name));
+        if !duplicates.is_empty() {
+            bail!("Duplicate parameter names: {}", &duplicates.join(", "));
+        }
+    }
+    for name in filters.iter().flat_map(|filter| filter.parameter_names()) {
+        if !param_names_and_values.iter().any(|(n, _)| *n == name) {
+            bail!(
+                "Unknown parameter '{}' in '--parameter-filter'. String values have to be \
+                 quoted, e.g. \"mode == 'single'\"",
+                name
+            );
+        }
+    }
+    let param_dimensions = group_zipped_parameters(param_names_and_values, zipped)?;
+
+    let dimensions: Vec<usize> = param_dimensions
+        .iter()
+        .map(|parameters| parameters[0].1.len())
+        .collect();
+    let num_combinations: usize = dimensions.iter().product();
+    if num_combinations == 0 {
+        return Ok((Vec::new(), 0));
+    }
+    if num_combinations > MAX_PARAMETERS {
+        bail!(
+            "Too many parameter combinations ({}). At most {} are supported",
+            num_combinations,
+            MAX_PARAMETERS
+        );
+    }
+
+    let mut combinations = Vec::with_capacity(num_combinations);
+    let mut index = vec![0usize; dimensions.len()];
+    'outer: loop {
+        let parameters: Vec<_> = param_dimensions
+            .iter()
+            .zip(&index)
+            .flat_map(|(parameters, i)| {
+                parameters
+                    .iter()
+                    .map(move |(name, values)| (*name, values[*i].clone()))
+            })
+            .collect();
+        if filters.iter().all(|filter| filter.matches(&parameters)) {
+            combinations.push(parameters);
+        }
+
+        // Increment index, exiting loop on overflow.
+        for (i, n) in index.iter_mut().zip(dimensions.iter()) {
+            *i += 1;
+            if *i < *n {
+                continue 'outer;
+            } else {
+                *i = 0;
+            }
+        }
+        break 'outer;
+    }
+
+    let num_filtered = num_combinations - combinations.len();
+    Ok((combinations, num_filtered))
+}
+
+/// Finds all the strings that appear multiple times in the input iterator, returning them in
+/// sorted order. If no string appears more than once, the result is an empty vector.
+fn find_duplicates<'b, I: IntoIterator<Item = &'b str>>(i: I) -> Vec<&'b str> {
+    let mut counts = BTreeMap::<&'b str, usize>::new();
+    for s in i {
+        *counts.entry(s).or_default() += 1;
+    }
+    counts
+        .into_iter()
+        .filter_map(|(k, n)| if n > 1 { Some(k) } else { None })
+        .collect()
+}
+
+/// Groups the parameters into the dimensions of the cross product. Zipped parameters vary
+/// together and form a single dimension at the position of the first one of them, all other
+/// parameters form a dimension of their own.
+fn group_zipped_parameters<'a>(
+    param_names_and_values: Vec<ParameterNameAndValues<'a>>,
+    zipped: &[Vec<&str>],
+) -> Result<Vec<Vec<ParameterNameAndValues<'a>>>> {
+    let names: Vec<_> = param_names_and_values
+        .iter()
+        .map(|(name, _)| *name)
+        .collect();
+    {
+        let duplicates = find_duplicates(zipped.iter().flatten().copied());
+        if !duplicates.is_empty() {
+            bail!(
+                "Parameters can only be zipped once, but '--parameter-zip' contains {}",
+                &duplicates.join(", ")
+            );
+        }
+    }
+    // Index of the zip group of every parameter
+    let mut group_of: Vec<Option<usize>> = vec![None; names.len()];
+    for (group, zipped_names) in zipped.iter().enumerate() {
+        for name in zipped_names {
+            match names.iter().position(|n| n == name) {
+                Some(i) => group_of[i] = Some(group),
+                None => bail!("Unknown parameter '{}' in '--parameter-zip'", name),
+            }
+        }
+    }
+
+    let mut dimensions: Vec<Vec<ParameterNameAndValues<'a>>> = vec![];
+    let mut dimension_of_group: Vec<Option<usize>> = vec![None; zipped.len()];
+    for (parameter, group) in param_names_and_values.into_iter().zip(group_of) {
+        match group.and_then(|g| dimension_of_group[g]) {
+            Some(dimension) => {
+                let dimension = &mut dimensions[dimension];
+                let (first_name, first_values) = &dimension[0];
+                if parameter.1.len() != first_values.len() {
+                    bail!(
+                        "The zipped parameters '{}' and '{}' have different numbers of \
+                         values ({} and {})",
+                        first_name,
+                        parameter.0,
+                        first_values.len(),
+                        parameter.1.len()
+                    );
+                }
+                dimension.push(parameter);
+            }
+            None => {
+                if let Some(g) = group {
+                    dimension_of_group[g] = Some(dimensions.len());
+                }
+                dimensions.push(vec![parameter]);
+            }
+        }
+    }
+    Ok(dimensions)
+}
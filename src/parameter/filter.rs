@@ -0,0 +1,362 @@
+//! Filter expressions that exclude parameter combinations (`--parameter-filter`).
+//!
+//! An expression compares parameters with each other or with literals, e.g.
+//! `threads <= cores && !(mode == 'single' && threads > 1)`. The comparison operators are
+//! `==`, `!=`, `<`, `<=`, `>` and `>=`, which can be combined with `&&`, `||`, `!` and
+//! parentheses. Values that are numbers on both sides are compared numerically, all other
+//! values are compared as strings. String literals are enclosed in single or double quotes.
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+use rust_decimal::Decimal;
+
+use super::ParameterNameAndValue;
+use crate::error::OptionsError;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Operator(&'static str),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Operand {
+    Parameter(String),
+    Literal(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expression {
+    Compare(Operand, Comparison, Operand),
+    Not(Box<Expression>),
+    And(Box<Expression>, Box<Expression>),
+    Or(Box<Expression>, Box<Expression>),
+}
+
+/// A parsed `--parameter-filter` expression
+#[derive(Debug, Clone, PartialEq)]
+pub struct Filter {
+    source: String,
+    expression: Expression,
+}
+
+impl Filter {
+    pub fn parse(source: &str) -> Result<Filter, OptionsError<'static>> {
+        let error =
+            |reason: &str| OptionsError::InvalidParameterFilter(source.into(), reason.into());
+
+        let tokens = tokenize(source).map_err(|reason| error(&reason))?;
+        let mut parser = Parser {
+            tokens,
+            position: 0,
+        };
+        let expression = parser.parse_or().map_err(|reason| error(&reason))?;
+        if let Some(token) = parser.peek() {
+            return Err(error(&format!("unexpected {}", describe(token))));
+        }
+
+        Ok(Filter {
+            source: source.into(),
+            expression,
+        })
+    }
+
+    /// The names of all parameters that appear in the expression
+    pub fn parameter_names(&self) -> Vec<&str> {
+        let mut names = vec![];
+        self.expression.collect_parameter_names(&mut names);
+        names
+    }
+
+    /// Whether the given parameter combination passes the filter. All parameters in the
+    /// expression have to be part of the combination, see `parameter_names`.
+    pub fn matches(&self, parameters: &[ParameterNameAndValue]) -> bool {
+        self.expression.evaluate(parameters)
+    }
+}
+
+impl std::fmt::Display for Filter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+impl Expression {
+    fn evaluate(&self, parameters: &[ParameterNameAndValue]) -> bool {
+        match self {
+            Expression::Compare(left, comparison, right) => {
+                let ordering = compare(&left.value(parameters), &right.value(parameters));
+                match comparison {
+                    Comparison::Equal => ordering == Ordering::Equal,
+                    Comparison::NotEqual => ordering != Ordering::Equal,
+                    Comparison::Less => ordering == Ordering::Less,
+                    Comparison::LessOrEqual => ordering != Ordering::Greater,
+                    Comparison::Greater => ordering == Ordering::Greater,
+                    Comparison::GreaterOrEqual => ordering != Ordering::Less,
+                }
+            }
+            Expression::Not(e) => !e.evaluate(parameters),
+            Expression::And(a, b) => a.evaluate(parameters) && b.evaluate(parameters),
+            Expression::Or(a, b) => a.evaluate(parameters) || b.evaluate(parameters),
+        }
+    }
+
+    fn collect_parameter_names<'a>(&'a self, names: &mut Vec<&'a str>) {
+        match self {
+            Expression::Compare(left, _, right) => {
+                for operand in [left, right] {
+                    if let Operand::Parameter(name) = operand {
+                        names.push(name);
+                    }
+                }
+            }
+            Expression::Not(e) => e.collect_parameter_names(names),
+            Expression::And(a, b) | Expression::Or(a, b) => {
+                a.collect_parameter_names(names);
+                b.collect_parameter_names(names);
+            }
+        }
+    }
+}
+
+impl Operand {
+    fn value(&self, parameters: &[ParameterNameAndValue]) -> String {
+        match self {
+            Operand::Parameter(name) => parameters
+                .iter()
+                .find(|(n, _)| n == name)
+                .map(|(_, value)| value.to_string())
+                .unwrap_or_default(),
+            Operand::Literal(value) => value.clone(),
+        }
+    }
+}
+
+/// Compare numerically if both values are numbers, and as strings otherwise
+fn compare(left: &str, right: &str) -> Ordering {
+    match (Decimal::from_str(left), Decimal::from_str(right)) {
+        (Ok(left), Ok(right)) => left.cmp(&right),
+        _ => left.cmp(right),
+    }
+}
+
+const OPERATORS: [&str; 11] = ["==", "!=", "<=", ">=", "&&", "||", "<", ">", "!", "(", ")"];
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = vec![];
+    let mut rest = source.trim_start();
+    while let Some(c) = rest.chars().next() {
+        if let Some(operator) = OPERATORS.iter().find(|op| rest.starts_with(*op)) {
+            tokens.push(Token::Operator(operator));
+            rest = &rest[operator.len()..];
+        } else if c == '\'' || c == '"' {
+            let end = rest[1..]
+                .find(c)
+                .ok_or_else(|| format!("missing closing quote ({})", c))?;
+            tokens.push(Token::Quoted(rest[1..=end].to_string()));
+            rest = &rest[end + 2..];
+        } else {
+            let end = rest
+                .find(|c: char| c.is_whitespace() || "=!<>&|()'\"".contains(c))
+                .unwrap_or(rest.len());
+            if end == 0 {
+                return Err(format!("unexpected character '{}'", c));
+            }
+            tokens.push(Token::Word(rest[..end].to_string()));
+            rest = &rest[end..];
+        }
+        rest = rest.trim_start();
+    }
+    Ok(tokens)
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Word(word) => format!("'{}'", word),
+        Token::Quoted(s) => format!("\"{}\"", s),
+        Token::Operator(op) => format!("'{}'", op),
+    }
+}
+
+/// Recursive descent parser, from the lowest to the highest precedence: `||`, `&&`, `!`
+/// and parentheses, comparisons
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Result<Token, String> {
+        let token = self
+            .tokens
+            .get(self.position)
+            .cloned()
+            .ok_or_else(|| "unexpected end of the expression".to_string())?;
+        self.position += 1;
+        Ok(token)
+    }
+
+    fn accept(&mut self, operator: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Operator(op)) if *op == operator) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Expression, String> {
+        let mut expression = self.parse_and()?;
+        while self.accept("||") {
+            expression = Expression::Or(Box::new(expression), Box::new(self.parse_and()?));
+        }
+        Ok(expression)
+    }
+
+    fn parse_and(&mut self) -> Result<Expression, String> {
+        let mut expression = self.parse_unary()?;
+        while self.accept("&&") {
+            expression = Expression::And(Box::new(expression), Box::new(self.parse_unary()?));
+        }
+        Ok(expression)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expression, String> {
+        if self.accept("!") {
+            Ok(Expression::Not(Box::new(self.parse_unary()?)))
+        } else if self.accept("(") {
+            let expression = self.parse_or()?;
+            if !self.accept(")") {
+                return Err("missing closing parenthesis".into());
+            }
+            Ok(expression)
+        } else {
+            let left = self.parse_operand()?;
+            let comparison = match self.next()? {
+                Token::Operator("==") => Comparison::Equal,
+                Token::Operator("!=") => Comparison::NotEqual,
+                Token::Operator("<") => Comparison::Less,
+                Token::Operator("<=") => Comparison::LessOrEqual,
+                Token::Operator(">") => Comparison::Greater,
+                Token::Operator(">=") => Comparison::GreaterOrEqual,
+                token => {
+                    return Err(format!(
+                        "expected a comparison operator, but found {}",
+                        describe(&token)
+                    ))
+                }
+            };
+            let right = self.parse_operand()?;
+            Ok(Expression::Compare(left, comparison, right))
+        }
+    }
+
+    fn parse_operand(&mut self) -> Result<Operand, String> {
+        match self.next()? {
+            Token::Word(word) if Decimal::from_str(&word).is_ok() => Ok(Operand::Literal(word)),
+            Token::Word(word) => Ok(Operand::Parameter(word)),
+            Token::Quoted(s) => Ok(Operand::Literal(s)),
+            token => Err(format!(
+                "expected a parameter name or a value, but found {}",
+                describe(&token)
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parameter::ParameterValue;
+
+    fn matches(filter: &str, parameters: &[(&str, &str)]) -> bool {
+        let parameters: Vec<_> = parameters
+            .iter()
+            .map(|(name, value)| (*name, ParameterValue::Text(value.to_string())))
+            .collect();
+        Filter::parse(filter).unwrap().matches(&parameters)
+    }
+
+    #[test]
+    fn test_comparisons() {
+        assert!(matches(
+            "threads <= cores",
+            &[("threads", "4"), ("cores", "8")]
+        ));
+        assert!(!matches(
+            "threads <= cores",
+            &[("threads", "16"), ("cores", "8")]
+        ));
+        assert!(matches("x == 1.0", &[("x", "1")]));
+        assert!(matches("x > 9", &[("x", "10")]));
+        assert!(matches("x > '9'", &[("x", "a")]));
+        assert!(matches("mode != \"single\"", &[("mode", "multi")]));
+        assert!(matches("mode == 'single'", &[("mode", "single")]));
+        assert!(matches("-1 < x", &[("x", "0")]));
+    }
+
+    #[test]
+    fn test_boolean_operators() {
+        let filter = "!(threads > 1 && mode == 'single') || threads == 16";
+        assert!(matches(filter, &[("threads", "1"), ("mode", "single")]));
+        assert!(!matches(filter, &[("threads", "2"), ("mode", "single")]));
+        assert!(matches(filter, &[("threads", "2"), ("mode", "multi")]));
+        assert!(matches(filter, &[("threads", "16"), ("mode", "single")]));
+
+        // `&&` binds more strongly than `||`
+        assert!(matches(
+            "a == 1 || a == 2 && b == 3",
+            &[("a", "1"), ("b", "0")]
+        ));
+    }
+
+    #[test]
+    fn test_parameter_names() {
+        let filter = Filter::parse("a < b || (c == 'd' && 1 != e)").unwrap();
+        assert_eq!(filter.parameter_names(), vec!["a", "b", "c", "e"]);
+    }
+
+    #[test]
+    fn test_invalid_filters() {
+        let error = |filter| Filter::parse(filter).unwrap_err().to_string();
+        assert_eq!(
+            error("threads"),
+            "Invalid parameter filter 'threads': unexpected end of the expression"
+        );
+        assert_eq!(
+            error("a = 1"),
+            "Invalid parameter filter 'a = 1': unexpected character '='"
+        );
+        assert_eq!(
+            error("a == 'b"),
+            "Invalid parameter filter 'a == 'b': missing closing quote (')"
+        );
+        assert_eq!(
+            error("(a == 1"),
+            "Invalid parameter filter '(a == 1': missing closing parenthesis"
+        );
+        assert_eq!(
+            error("a == 1 b"),
+            "Invalid parameter filter 'a == 1 b': unexpected 'b'"
+        );
+        assert_eq!(
+            error("a && b"),
+            "Invalid parameter filter 'a && b': expected a comparison operator, but found '&&'"
+        );
+    }
+}
@@ -1,21 +1,33 @@
+use std::fmt;
+
 use crate::util::number::Number;
 
+pub mod combination;
+pub mod filter;
 pub mod range_step;
+pub mod scan;
+pub mod source;
 pub mod tokenize;
 
+/// Upper limit for the number of values of a parameter
+pub const MAX_PARAMETERS: usize = 100_000;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ParameterValue {
     Text(String),
     Numeric(Number),
 }
 
-impl ToString for ParameterValue {
-    fn to_string(&self) -> String {
+impl fmt::Display for ParameterValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            ParameterValue::Text(ref value) => value.clone(),
-            ParameterValue::Numeric(value) => value.to_string(),
+            ParameterValue::Text(ref value) => write!(f, "{}", value),
+            ParameterValue::Numeric(value) => write!(f, "{}", value),
         }
     }
 }
 
 pub type ParameterNameAndValue<'a> = (&'a str, ParameterValue);
+
+/// A parameter together with all of its values
+pub type ParameterNameAndValues<'a> = (&'a str, Vec<ParameterValue>);
@@ -1,6 +1,7 @@
 use std::convert::TryInto;
 use std::ops::{Add, AddAssign, Div, Sub};
 
+use super::MAX_PARAMETERS;
 use crate::error::ParameterScanError;
 use crate::util::number::Number;
 
@@ -47,7 +48,6 @@ impl<T: Numeric> RangeStep<T> {
             return Err(ParameterScanError::ZeroStep);
         }
 
-        const MAX_PARAMETERS: usize = 100_000;
         match range_step_size_hint(start, end, step) {
             (_, Some(size)) if size <= MAX_PARAMETERS => Ok(Self {
                 state: start,
@@ -0,0 +1,266 @@
+use std::str::FromStr;
+
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+
+use super::range_step::RangeStep;
+use super::MAX_PARAMETERS;
+use crate::error::ParameterScanError;
+use crate::util::number::Number;
+
+/// Number of significant digits of the intermediate values of logarithmic scans over decimals
+const LOG_SCAN_SIGNIFICANT_DIGITS: u32 = 10;
+
+/// How the values of a parameter scan are spaced between the bounds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Spacing<'a> {
+    /// Constant difference between consecutive values (`--parameter-step-size`)
+    Step(Option<&'a str>),
+
+    /// Constant factor between consecutive values (`--parameter-factor`)
+    Factor(&'a str),
+
+    /// Given number of logarithmically spaced values (`--parameter-points`)
+    Points(&'a str),
+}
+
+/// Compute the values of a parameter scan from the (unparsed) bounds and spacing. The values
+/// are integers if the bounds (and the step or factor) are integers, and decimals otherwise.
+pub fn scan_values(
+    min: &str,
+    max: &str,
+    spacing: Spacing,
+) -> Result<Vec<Number>, ParameterScanError> {
+    match spacing {
+        Spacing::Step(step) => linear_values(min, max, step),
+        Spacing::Factor(factor) => geometric_values(
+            parse_number(min)?,
+            parse_number(max)?,
+            parse_number(factor)?,
+        ),
+        Spacing::Points(points) => {
+            log_spaced_values(parse_number(min)?, parse_number(max)?, points.parse()?)
+        }
+    }
+}
+
+fn linear_values(
+    min: &str,
+    max: &str,
+    step: Option<&str>,
+) -> Result<Vec<Number>, ParameterScanError> {
+    // attempt to parse as integers
+    if let (Ok(min), Ok(max), Ok(step)) = (
+        min.parse::<i32>(),
+        max.parse::<i32>(),
+        step.unwrap_or("1").parse::<i32>(),
+    ) {
+        return Ok(RangeStep::new(min, max, step)?.map(Number::from).collect());
+    }
+
+    // try parsing them as decimals
+    let min = Decimal::from_str(min)?;
+    let max = Decimal::from_str(max)?;
+    let step = Decimal::from_str(step.ok_or(ParameterScanError::StepRequired)?)?;
+    Ok(RangeStep::new(min, max, step)?.map(Number::from).collect())
+}
+
+/// Values `min`, `min·factor`, `min·factor²`, … up to `max`
+fn geometric_values(
+    min: Number,
+    max: Number,
+    factor: Number,
+) -> Result<Vec<Number>, ParameterScanError> {
+    let integers = [min, max, factor]
+        .iter()
+        .all(|n| matches!(n, Number::Int(_)));
+    let (min, max, factor) = (to_decimal(min), to_decimal(max), to_decimal(factor));
+
+    validate_bounds(min, max)?;
+    if factor <= Decimal::ONE {
+        return Err(ParameterScanError::InvalidFactor);
+    }
+
+    let mut values = vec![];
+    let mut value = Some(min);
+    while let Some(v) = value.filter(|v| *v <= max) {
+        if values.len() == MAX_PARAMETERS {
+            return Err(ParameterScanError::TooLarge);
+        }
+        values.push(v);
+        value = v.checked_mul(factor);
+    }
+    Ok(values
+        .into_iter()
+        .map(|v| from_decimal(v, integers))
+        .collect())
+}
+
+/// `points` logarithmically spaced values from `min` to `max`. For integer bounds, the values
+/// are rounded to the nearest integer and duplicates are removed.
+fn log_spaced_values(
+    min: Number,
+    max: Number,
+    points: usize,
+) -> Result<Vec<Number>, ParameterScanError> {
+    let integers = matches!((min, max), (Number::Int(_), Number::Int(_)));
+    let (min, max) = (to_decimal(min), to_decimal(max));
+
+    validate_bounds(min, max)?;
+    if points < 2 {
+        return Err(ParameterScanError::TooFewPoints);
+    }
+    if points > MAX_PARAMETERS {
+        return Err(ParameterScanError::TooLarge);
+    }
+
+    let log_min = min.to_f64().unwrap().ln();
+    let log_max = max.to_f64().unwrap().ln();
+    let mut values: Vec<Decimal> = (0..points)
+        .map(|i| {
+            if i == 0 {
+                min
+            } else if i == points - 1 {
+                max
+            } else {
+                let x = (log_min + (log_max - log_min) * i as f64 / (points - 1) as f64).exp();
+                let x = Decimal::from_f64(x).unwrap();
+                if integers {
+                    x.round()
+                } else {
+                    x.round_sf(LOG_SCAN_SIGNIFICANT_DIGITS).unwrap().normalize()
+                }
+            }
+        })
+        .collect();
+    values.dedup();
+
+    Ok(values
+        .into_iter()
+        .map(|v| from_decimal(v, integers))
+        .collect())
+}
+
+fn validate_bounds(min: Decimal, max: Decimal) -> Result<(), ParameterScanError> {
+    if min <= Decimal::ZERO {
+        return Err(ParameterScanError::NonPositiveBound);
+    }
+    if max < min {
+        return Err(ParameterScanError::EmptyRange);
+    }
+    Ok(())
+}
+
+fn parse_number(s: &str) -> Result<Number, ParameterScanError> {
+    match s.parse::<i32>() {
+        Ok(i) => Ok(Number::Int(i)),
+        Err(_) => Ok(Number::Decimal(Decimal::from_str(s)?)),
+    }
+}
+
+fn to_decimal(n: Number) -> Decimal {
+    match n {
+        Number::Int(i) => Decimal::from(i),
+        Number::Decimal(d) => d,
+    }
+}
+
+fn from_decimal(d: Decimal, integer: bool) -> Number {
+    match d.to_i32() {
+        Some(i) if integer => Number::Int(i),
+        _ => Number::Decimal(d),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(min: &str, max: &str, spacing: Spacing) -> Vec<String> {
+        scan_values(min, max, spacing)
+            .unwrap()
+            .iter()
+            .map(|v| v.to_string())
+            .collect()
+    }
+
+    fn error(min: &str, max: &str, spacing: Spacing) -> String {
+        scan_values(min, max, spacing).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn test_linear_scan() {
+        assert_eq!(values("1", "3", Spacing::Step(None)), ["1", "2", "3"]);
+        assert_eq!(
+            values("0.3", "0.7", Spacing::Step(Some("0.2"))),
+            ["0.3", "0.5", "0.7"]
+        );
+        assert!(error("0.3", "0.7", Spacing::Step(None)).starts_with("A step size is required"));
+    }
+
+    #[test]
+    fn test_geometric_scan() {
+        assert_eq!(
+            values("1", "1000000", Spacing::Factor("10")),
+            ["1", "10", "100", "1000", "10000", "100000", "1000000"]
+        );
+        assert_eq!(
+            values("3", "100", Spacing::Factor("2")),
+            ["3", "6", "12", "24", "48", "96"]
+        );
+        assert_eq!(
+            values("1", "4", Spacing::Factor("1.5")),
+            ["1", "1.5", "2.25", "3.375"]
+        );
+        assert_eq!(
+            values("2147483647", "2147483647", Spacing::Factor("2")),
+            ["2147483647"]
+        );
+
+        assert_eq!(
+            error("1", "10", Spacing::Factor("1")),
+            "The factor of a geometric parameter scan has to be greater than one"
+        );
+        assert_eq!(
+            error("0", "10", Spacing::Factor("2")),
+            "The bounds of a geometric or logarithmic parameter scan have to be positive"
+        );
+        assert_eq!(
+            error("10", "1", Spacing::Factor("2")),
+            "Empty parameter range"
+        );
+    }
+
+    #[test]
+    fn test_log_spaced_scan() {
+        assert_eq!(
+            values("1", "10000", Spacing::Points("5")),
+            ["1", "10", "100", "1000", "10000"]
+        );
+        assert_eq!(
+            values("1", "10", Spacing::Points("10")),
+            ["1", "2", "3", "4", "5", "6", "8", "10"]
+        );
+        assert_eq!(
+            values("0.001", "1", Spacing::Points("4")),
+            ["0.001", "0.01", "0.1", "1"]
+        );
+        assert_eq!(
+            values("0.5", "2.0", Spacing::Points("3")),
+            ["0.5", "1", "2.0"]
+        );
+
+        assert_eq!(
+            error("1", "10", Spacing::Points("1")),
+            "A logarithmic parameter scan needs at least two points"
+        );
+        assert_eq!(
+            error("1", "10", Spacing::Points("100001")),
+            "Parameter range is too large"
+        );
+        assert_eq!(
+            error("-1", "10", Spacing::Points("3")),
+            "The bounds of a geometric or logarithmic parameter scan have to be positive"
+        );
+    }
+}
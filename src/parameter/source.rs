@@ -0,0 +1,55 @@
+//! Parameter values that are generated at startup instead of being listed on the command line
+use std::fs;
+use std::process::Stdio;
+
+use anyhow::{bail, Context, Result};
+
+use crate::options::Shell;
+
+/// Read the values of a parameter from a file, one value per line
+pub fn values_from_file(path: &str) -> Result<Vec<String>> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read parameter values from '{}'", path))?;
+    Ok(split_lines(&content))
+}
+
+/// Run a command in the given shell and use each line of its output as a parameter value
+pub fn values_from_command(command: &str, shell: &Shell) -> Result<Vec<String>> {
+    let output = shell
+        .command()
+        .arg(if cfg!(windows) { "/C" } else { "-c" })
+        .arg(command)
+        .stdin(Stdio::null())
+        .stderr(Stdio::inherit())
+        .output()
+        .with_context(|| format!("Failed to run the parameter command '{}'", command))?;
+    if !output.status.success() {
+        bail!(
+            "The parameter command '{}' terminated with a non-zero exit code",
+            command
+        );
+    }
+    let stdout = String::from_utf8(output.stdout).with_context(|| {
+        format!(
+            "The output of the parameter command '{}' is not valid UTF-8",
+            command
+        )
+    })?;
+    Ok(split_lines(&stdout))
+}
+
+/// Split the text into lines, skipping empty ones. Windows line endings are accepted as well.
+fn split_lines(text: &str) -> Vec<String> {
+    text.lines()
+        .map(|line| line.trim_end_matches('\r'))
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+#[test]
+fn test_split_lines() {
+    assert_eq!(split_lines(""), Vec::<String>::new());
+    assert_eq!(split_lines("a\nb c\n"), vec!["a", "b c"]);
+    assert_eq!(split_lines("a\r\n\r\nb"), vec!["a", "b"]);
+}
@@ -0,0 +1,254 @@
+//! A module for testing whether the difference between two benchmarks is statistically
+//! significant.
+//!
+//! References:
+//! - B. L. Welch (1947), "The generalization of 'Student's' problem when several different
+//!   population variances are involved", Biometrika 34, 28-35.
+//! - W. H. Press et al. (2007), "Numerical Recipes: The Art of Scientific Computing", 3rd edition,
+//!   Section 6.4 (incomplete beta function).
+
+use statistical::{mean, variance};
+
+use crate::util::units::Scalar;
+
+/// Default significance level α used to classify a difference as significant
+pub const DEFAULT_SIGNIFICANCE_LEVEL: Scalar = 0.05;
+
+/// Result of Welch's unequal variances t-test
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WelchTTest {
+    /// The t statistic
+    pub t: Scalar,
+
+    /// The (Welch–Satterthwaite) degrees of freedom
+    pub degrees_of_freedom: Scalar,
+
+    /// Two-sided p-value for the null hypothesis of equal means
+    pub p_value: Scalar,
+}
+
+impl WelchTTest {
+    /// Whether the null hypothesis of equal means can be rejected at the given level
+    pub fn is_significant(&self, significance_level: Scalar) -> bool {
+        self.p_value < significance_level
+    }
+}
+
+/// Perform Welch's t-test on two samples. Returns `None` if one of the samples has less than two
+/// elements or if both samples have zero variance.
+pub fn welch_ttest(xs: &[Scalar], ys: &[Scalar]) -> Option<WelchTTest> {
+    if xs.len() < 2 || ys.len() < 2 {
+        return None;
+    }
+
+    let n_x = xs.len() as Scalar;
+    let n_y = ys.len() as Scalar;
+
+    let mean_x = mean(xs);
+    let mean_y = mean(ys);
+
+    // Squared standard errors of the means
+    let se2_x = variance(xs, Some(mean_x)) / n_x;
+    let se2_y = variance(ys, Some(mean_y)) / n_y;
+    let se2 = se2_x + se2_y;
+
+    if se2 <= 0.0 {
+        return None;
+    }
+
+    let t = (mean_x - mean_y) / se2.sqrt();
+    let degrees_of_freedom =
+        se2.powi(2) / (se2_x.powi(2) / (n_x - 1.0) + se2_y.powi(2) / (n_y - 1.0));
+
+    Some(WelchTTest {
+        t,
+        degrees_of_freedom,
+        p_value: student_t_two_sided_p_value(t, degrees_of_freedom),
+    })
+}
+
+/// Two-sided p-value `P(|T| > |t|)` of Student's t-distribution with `df` degrees of freedom
+fn student_t_two_sided_p_value(t: Scalar, df: Scalar) -> Scalar {
+    regularized_incomplete_beta(df / (df + t * t), 0.5 * df, 0.5).clamp(0.0, 1.0)
+}
+
+/// Critical value `t*` of Student's t-distribution with `df` degrees of freedom such that the
+/// two-sided interval `[-t*, t*]` has the given probability (confidence level)
+pub fn student_t_critical_value(df: Scalar, level: Scalar) -> Scalar {
+    let alpha = 1.0 - level;
+
+    // The p-value is monotonically decreasing in |t|. Find an upper bound, then bisect.
+    let mut lower = 0.0;
+    let mut upper = 1.0;
+    while student_t_two_sided_p_value(upper, df) > alpha && upper < 1e12 {
+        lower = upper;
+        upper *= 2.0;
+    }
+
+    for _ in 0..100 {
+        let mid = 0.5 * (lower + upper);
+        if student_t_two_sided_p_value(mid, df) > alpha {
+            lower = mid;
+        } else {
+            upper = mid;
+        }
+    }
+
+    0.5 * (lower + upper)
+}
+
+/// Natural logarithm of the gamma function (Lanczos approximation)
+fn ln_gamma(x: Scalar) -> Scalar {
+    const COEFFICIENTS: [Scalar; 6] = [
+        76.180_091_729_471_46,
+        -86.505_320_329_416_77,
+        24.014_098_240_830_91,
+        -1.231_739_572_450_155,
+        0.001_208_650_973_866_179,
+        -0.000_005_395_239_384_953,
+    ];
+
+    let tmp = x + 5.5;
+    let tmp = tmp - (x + 0.5) * tmp.ln();
+
+    let mut y = x;
+    let mut series = 1.000_000_000_190_015;
+    for c in &COEFFICIENTS {
+        y += 1.0;
+        series += c / y;
+    }
+
+    -tmp + (2.506_628_274_631_000_5 * series / x).ln()
+}
+
+/// Regularized incomplete beta function `I_x(a, b)`
+fn regularized_incomplete_beta(x: Scalar, a: Scalar, b: Scalar) -> Scalar {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+
+    let ln_front = ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln();
+    let front = ln_front.exp();
+
+    // The continued fraction converges rapidly for x < (a + 1) / (a + b + 2). Otherwise, use the
+    // symmetry relation I_x(a, b) = 1 - I_{1-x}(b, a).
+    if x < (a + 1.0) / (a + b + 2.0) {
+        front * beta_continued_fraction(x, a, b) / a
+    } else {
+        1.0 - front * beta_continued_fraction(1.0 - x, b, a) / b
+    }
+}
+
+/// Evaluate the continued fraction for the incomplete beta function (modified Lentz's method)
+fn beta_continued_fraction(x: Scalar, a: Scalar, b: Scalar) -> Scalar {
+    const MAX_ITERATIONS: usize = 300;
+    const EPSILON: Scalar = 1e-14;
+    const TINY: Scalar = 1e-300;
+
+    let guard = |v: Scalar| if v.abs() < TINY { TINY } else { v };
+
+    let mut c = 1.0;
+    let mut d = 1.0 / guard(1.0 - (a + b) * x / (a + 1.0));
+    let mut h = d;
+
+    for m in 1..=MAX_ITERATIONS {
+        let m = m as Scalar;
+        let m2 = 2.0 * m;
+
+        // Even step
+        let aa = m * (b - m) * x / ((a + m2 - 1.0) * (a + m2));
+        d = 1.0 / guard(1.0 + aa * d);
+        c = guard(1.0 + aa / c);
+        h *= d * c;
+
+        // Odd step
+        let aa = -(a + m) * (a + b + m) * x / ((a + m2) * (a + m2 + 1.0));
+        d = 1.0 / guard(1.0 + aa * d);
+        c = guard(1.0 + aa / c);
+        let delta = d * c;
+        h *= delta;
+
+        if (delta - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+
+    h
+}
+
+#[test]
+fn test_student_t_p_value() {
+    use approx::assert_relative_eq;
+
+    // For one degree of freedom (Cauchy distribution), P(|T| > 1) = 1/2
+    assert_relative_eq!(0.5, student_t_two_sided_p_value(1.0, 1.0), epsilon = 1e-8);
+
+    // For two degrees of freedom, P(|T| > t) = 1 - t / sqrt(2 + t²)
+    assert_relative_eq!(
+        1.0 - 2.0 / 6.0f64.sqrt(),
+        student_t_two_sided_p_value(2.0, 2.0),
+        epsilon = 1e-8
+    );
+    assert_relative_eq!(
+        1.0 - 2.0 / 6.0f64.sqrt(),
+        student_t_two_sided_p_value(-2.0, 2.0),
+        epsilon = 1e-8
+    );
+
+    // No difference at all
+    assert_relative_eq!(1.0, student_t_two_sided_p_value(0.0, 10.0), epsilon = 1e-8);
+
+    // Large degrees of freedom approach the normal distribution: P(|Z| > 1.96) ≈ 0.05
+    assert_relative_eq!(
+        0.05,
+        student_t_two_sided_p_value(1.959_964, 1e6),
+        epsilon = 1e-5
+    );
+}
+
+#[test]
+fn test_student_t_critical_value() {
+    use approx::assert_relative_eq;
+
+    // Inverse of the closed-form expression for two degrees of freedom
+    let t = student_t_critical_value(2.0, 2.0 / 6.0f64.sqrt());
+    assert_relative_eq!(2.0, t, epsilon = 1e-6);
+
+    // Well-known values from t-tables
+    assert_relative_eq!(12.706, student_t_critical_value(1.0, 0.95), epsilon = 1e-3);
+    assert_relative_eq!(2.262, student_t_critical_value(9.0, 0.95), epsilon = 1e-3);
+    assert_relative_eq!(1.960, student_t_critical_value(1e6, 0.95), epsilon = 1e-3);
+}
+
+#[test]
+fn test_welch_ttest() {
+    use approx::assert_relative_eq;
+
+    let xs = [1.0, 2.0, 3.0, 4.0];
+    let ys = [2.0, 4.0, 6.0, 8.0, 10.0];
+
+    let result = welch_ttest(&xs, &ys).unwrap();
+    assert_relative_eq!(-2.251_436_323_159_369, result.t, epsilon = 1e-8);
+    assert_relative_eq!(
+        5.520_787_746_170_677,
+        result.degrees_of_freedom,
+        epsilon = 1e-8
+    );
+    assert_relative_eq!(0.069_133_593, result.p_value, epsilon = 1e-6);
+    assert!(!result.is_significant(0.05));
+    assert!(result.is_significant(0.1));
+
+    let same = welch_ttest(&xs, &xs).unwrap();
+    assert_relative_eq!(0.0, same.t);
+    assert_relative_eq!(1.0, same.p_value, epsilon = 1e-8);
+}
+
+#[test]
+fn test_welch_ttest_degenerate_samples() {
+    assert!(welch_ttest(&[1.0], &[1.0, 2.0]).is_none());
+    assert!(welch_ttest(&[1.0, 2.0], &[]).is_none());
+    assert!(welch_ttest(&[1.0, 1.0], &[2.0, 2.0]).is_none());
+}
@@ -0,0 +1,358 @@
+//! Measurement of whole process trees with Linux control groups (cgroup v2). Every timed run is
+//! placed in a transient leaf cgroup, such that the CPU time, peak memory usage and block I/O of
+//! all processes spawned by the command are accounted for, even if they are not waited for by
+//! the command itself (e.g. daemons or detached grandchildren).
+
+#[cfg(not(target_os = "linux"))]
+use std::io;
+#[cfg(not(target_os = "linux"))]
+use std::process::Command;
+
+use crate::util::units::{Byte, Second};
+
+/// Resource usage of all processes in a leaf cgroup
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct CgroupUsage {
+    /// Total amount of time spent executing in user mode
+    pub user: Second,
+
+    /// Total amount of time spent executing in kernel mode
+    pub system: Second,
+
+    /// Peak memory usage. Requires the `memory` controller and Linux 5.19 or later
+    pub memory_peak: Option<Byte>,
+
+    /// Number of read operations. Requires the `io` controller
+    pub read_operations: Option<u64>,
+
+    /// Number of write operations. Requires the `io` controller
+    pub write_operations: Option<u64>,
+}
+
+#[cfg(target_os = "linux")]
+pub use self::linux::Cgroup;
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::cell::Cell;
+    use std::fs::{self, File, OpenOptions};
+    use std::io;
+    use std::os::unix::io::AsRawFd;
+    use std::os::unix::process::CommandExt;
+    use std::path::{Path, PathBuf};
+    use std::process::{self, Command};
+
+    use super::{parse_cpu_stat, parse_io_stat, CgroupUsage};
+
+    /// A transient cgroup next to the cgroup of the current process, which holds one leaf
+    /// cgroup per timed run.
+    ///
+    /// Controllers can only be enabled for the children of a cgroup that does not contain any
+    /// processes itself. The current process is therefore moved from its original cgroup (the
+    /// parent) into a leaf cgroup of its own, and the cgroup for the measurements is created as
+    /// its sibling:
+    ///
+    /// ```text
+    /// <parent>/hyperfine-<pid>-main    hyperfine itself
+    /// <parent>/hyperfine-<pid>/run-0   first timed run
+    /// <parent>/hyperfine-<pid>/run-1   ...
+    /// ```
+    pub struct Cgroup {
+        path: PathBuf,
+        parent: PathBuf,
+        main: PathBuf,
+        number_of_leaves: Cell<u64>,
+        enabled_in_parent: Vec<&'static str>,
+        unavailable_controllers: Vec<&'static str>,
+    }
+
+    impl Cgroup {
+        /// Move the current process into a leaf cgroup of its own and create a new cgroup for the
+        /// measurements next to it. Fails if there is no cgroup v2 hierarchy or if it is not
+        /// writable for the current user.
+        pub fn new() -> io::Result<Cgroup> {
+            let mount_point = cgroup2_mount_point()?;
+            let parent = mount_point.join(own_cgroup()?.trim_start_matches('/'));
+
+            let main = parent.join(format!("hyperfine-{}-main", process::id()));
+            fs::create_dir(&main)?;
+            if let Err(error) = fs::write(main.join("cgroup.procs"), process::id().to_string()) {
+                let _ = fs::remove_dir(&main);
+                return Err(error);
+            }
+
+            let mut cgroup = Cgroup {
+                path: parent.join(format!("hyperfine-{}", process::id())),
+                parent,
+                main,
+                number_of_leaves: Cell::new(0),
+                enabled_in_parent: vec![],
+                unavailable_controllers: vec![],
+            };
+            fs::create_dir(&cgroup.path)?;
+
+            // Enable the controllers for the cgroup of the measurements and then for its leaf
+            // cgroups. This only works if they are available (delegated) to the parent cgroup and
+            // if there are no other processes left in the parent. Otherwise, the corresponding
+            // statistics are not available.
+            let enabled = fs::read_to_string(cgroup.parent.join("cgroup.subtree_control"))?;
+            for &controller in &["memory", "io"] {
+                let enable = |path: &Path| {
+                    fs::write(
+                        path.join("cgroup.subtree_control"),
+                        format!("+{}", controller),
+                    )
+                };
+
+                if !enabled.split_whitespace().any(|c| c == controller) {
+                    if enable(&cgroup.parent).is_err() {
+                        cgroup.unavailable_controllers.push(controller);
+                        continue;
+                    }
+                    cgroup.enabled_in_parent.push(controller);
+                }
+                if enable(&cgroup.path).is_err() {
+                    cgroup.unavailable_controllers.push(controller);
+                }
+            }
+
+            Ok(cgroup)
+        }
+
+        /// Controllers that could not be enabled for the leaf cgroups, such that the
+        /// corresponding statistics are not measured
+        pub fn unavailable_controllers(&self) -> &[&'static str] {
+            &self.unavailable_controllers
+        }
+
+        /// Create a new, empty leaf cgroup for a single run
+        pub fn create_leaf(&self) -> io::Result<CgroupLeaf> {
+            let number = self.number_of_leaves.get();
+            self.number_of_leaves.set(number + 1);
+
+            let path = self.path.join(format!("run-{}", number));
+            fs::create_dir(&path)?;
+            let procs = OpenOptions::new()
+                .write(true)
+                .open(path.join("cgroup.procs"))?;
+
+            Ok(CgroupLeaf { path, procs })
+        }
+    }
+
+    impl Drop for Cgroup {
+        fn drop(&mut self) {
+            // Leaf cgroups of runs with processes that were still alive at the end of the run
+            // could not be removed earlier. Try again, in case these processes have terminated.
+            if let Ok(entries) = fs::read_dir(&self.path) {
+                for entry in entries.flatten() {
+                    if entry.path().is_dir() {
+                        let _ = fs::remove_dir(entry.path());
+                    }
+                }
+            }
+            let _ = fs::remove_dir(&self.path);
+
+            // Restore the original layout: the parent cgroup can only hold the current process
+            // again once the controllers that were enabled for its children are disabled.
+            for controller in &self.enabled_in_parent {
+                let _ = fs::write(
+                    self.parent.join("cgroup.subtree_control"),
+                    format!("-{}", controller),
+                );
+            }
+            let _ = fs::write(self.parent.join("cgroup.procs"), process::id().to_string());
+            let _ = fs::remove_dir(&self.main);
+        }
+    }
+
+    /// A leaf cgroup that contains the process tree of a single run
+    pub struct CgroupLeaf {
+        path: PathBuf,
+        procs: File,
+    }
+
+    impl CgroupLeaf {
+        /// Make sure that the process spawned by the given command is placed in this cgroup
+        /// before the actual program is executed.
+        pub fn attach(&self, command: &mut Command) {
+            let fd = self.procs.as_raw_fd();
+
+            // SAFETY: only the async-signal-safe `write` is called in between fork and exec.
+            // Writing "0" to `cgroup.procs` moves the writing process into the cgroup.
+            unsafe {
+                command.pre_exec(move || {
+                    if libc::write(fd, b"0".as_ptr() as *const libc::c_void, 1) == 1 {
+                        Ok(())
+                    } else {
+                        Err(io::Error::last_os_error())
+                    }
+                });
+            }
+        }
+
+        /// Read the accumulated resource usage of all processes in this cgroup
+        pub fn usage(&self) -> io::Result<CgroupUsage> {
+            let (user, system) = parse_cpu_stat(&fs::read_to_string(self.path.join("cpu.stat"))?)
+                .ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidData, "Invalid cpu.stat")
+            })?;
+
+            let memory_peak = fs::read_to_string(self.path.join("memory.peak"))
+                .ok()
+                .and_then(|peak| peak.trim().parse().ok());
+
+            let (read_operations, write_operations) =
+                match fs::read_to_string(self.path.join("io.stat")) {
+                    Ok(io_stat) => {
+                        let (reads, writes) = parse_io_stat(&io_stat);
+                        (Some(reads), Some(writes))
+                    }
+                    Err(_) => (None, None),
+                };
+
+            Ok(CgroupUsage {
+                user,
+                system,
+                memory_peak,
+                read_operations,
+                write_operations,
+            })
+        }
+    }
+
+    impl Drop for CgroupLeaf {
+        fn drop(&mut self) {
+            // This fails if processes of the command are still alive, in which case we have to
+            // leave the cgroup behind.
+            let _ = fs::remove_dir(&self.path);
+        }
+    }
+
+    /// Find the mount point of the cgroup v2 hierarchy
+    fn cgroup2_mount_point() -> io::Result<PathBuf> {
+        let mountinfo = fs::read_to_string("/proc/self/mountinfo")?;
+        mountinfo
+            .lines()
+            .find_map(|line| {
+                let (mount, filesystem) = line.split_once(" - ")?;
+                if filesystem.split(' ').next()? == "cgroup2" {
+                    mount
+                        .split(' ')
+                        .nth(4)
+                        .map(|path| Path::new(path).to_path_buf())
+                } else {
+                    None
+                }
+            })
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "No cgroup v2 hierarchy found"))
+    }
+
+    /// Path of the cgroup v2 of the current process, relative to the mount point
+    fn own_cgroup() -> io::Result<String> {
+        let cgroups = fs::read_to_string("/proc/self/cgroup")?;
+        cgroups
+            .lines()
+            .find_map(|line| line.strip_prefix("0::").map(str::to_string))
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "Not in a cgroup v2"))
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn unsupported() -> io::Error {
+    io::Error::new(io::ErrorKind::Other, "cgroups are only supported on Linux")
+}
+
+/// Placeholder for platforms without cgroup support
+#[cfg(not(target_os = "linux"))]
+pub struct Cgroup;
+
+#[cfg(not(target_os = "linux"))]
+impl Cgroup {
+    pub fn new() -> io::Result<Cgroup> {
+        Err(unsupported())
+    }
+
+    pub fn create_leaf(&self) -> io::Result<CgroupLeaf> {
+        Err(unsupported())
+    }
+
+    pub fn unavailable_controllers(&self) -> &[&'static str] {
+        &[]
+    }
+}
+
+/// Placeholder for platforms without cgroup support
+#[cfg(not(target_os = "linux"))]
+pub struct CgroupLeaf;
+
+#[cfg(not(target_os = "linux"))]
+impl CgroupLeaf {
+    pub fn attach(&self, _command: &mut Command) {}
+
+    pub fn usage(&self) -> io::Result<CgroupUsage> {
+        Err(unsupported())
+    }
+}
+
+/// Extract the user and system time from the contents of a `cpu.stat` file
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_cpu_stat(cpu_stat: &str) -> Option<(Second, Second)> {
+    let field = |name: &str| {
+        cpu_stat.lines().find_map(|line| {
+            let (key, value) = line.split_once(' ')?;
+            if key == name {
+                value.trim().parse::<u64>().ok()
+            } else {
+                None
+            }
+        })
+    };
+
+    Some((
+        field("user_usec")? as Second * 1e-6,
+        field("system_usec")? as Second * 1e-6,
+    ))
+}
+
+/// Sum up the number of read and write operations of all devices in an `io.stat` file
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_io_stat(io_stat: &str) -> (u64, u64) {
+    let mut reads = 0;
+    let mut writes = 0;
+    for entry in io_stat.split_whitespace() {
+        if let Some((key, value)) = entry.split_once('=') {
+            let value = value.parse::<u64>().unwrap_or(0);
+            match key {
+                "rios" => reads += value,
+                "wios" => writes += value,
+                _ => {}
+            }
+        }
+    }
+    (reads, writes)
+}
+
+#[test]
+fn test_parse_cpu_stat() {
+    use approx::assert_relative_eq;
+
+    let cpu_stat = "usage_usec 1500000\n\
+                    user_usec 1250000\n\
+                    system_usec 250000\n\
+                    nr_periods 0\n";
+    let (user, system) = parse_cpu_stat(cpu_stat).unwrap();
+    assert_relative_eq!(1.25, user);
+    assert_relative_eq!(0.25, system);
+
+    assert_eq!(None, parse_cpu_stat("usage_usec 1500000\n"));
+}
+
+#[test]
+fn test_parse_io_stat() {
+    let io_stat = "8:0 rbytes=4096 wbytes=8192 rios=1 wios=2 dbytes=0 dios=0\n\
+                   259:0 rbytes=0 wbytes=40960 rios=0 wios=10 dbytes=0 dios=0\n";
+    assert_eq!((1, 12), parse_io_stat(io_stat));
+
+    assert_eq!((0, 0), parse_io_stat(""));
+}
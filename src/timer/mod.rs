@@ -1,4 +1,6 @@
+mod cgroup;
 mod wall_clock_timer;
+mod watchdog;
 
 #[cfg(windows)]
 mod windows_timer;
@@ -13,22 +15,98 @@ use std::fs::File;
 #[cfg(target_os = "linux")]
 use std::os::unix::io::AsRawFd;
 
-use crate::util::units::Second;
+use crate::util::units::{Byte, Second};
 use wall_clock_timer::WallClockTimer;
+use watchdog::Watchdog;
 
+pub use self::cgroup::Cgroup;
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 use std::io::Read;
-use std::process::{ChildStdout, Command, ExitStatus};
+use std::process::{ChildStderr, ChildStdout, Command, ExitStatus};
+use std::sync::mpsc;
+use std::thread;
 
 use anyhow::Result;
 
-#[cfg(not(windows))]
-#[derive(Debug, Copy, Clone)]
-struct CPUTimes {
-    /// Total amount of time spent executing in user mode
-    pub user_usec: i64,
+/// Counters from the resource usage (`rusage`) of a single process
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct ResourceCounters {
+    /// Page faults serviced without any I/O activity
+    pub minor_page_faults: u64,
+
+    /// Page faults that required I/O activity
+    pub major_page_faults: u64,
+
+    /// Context switches because the process waited for a resource
+    pub voluntary_context_switches: u64,
+
+    /// Context switches because a higher priority process became runnable or the time slice
+    /// was exceeded
+    pub involuntary_context_switches: u64,
 
-    /// Total amount of time spent executing in kernel mode
-    pub system_usec: i64,
+    /// Number of times the file system had to perform input
+    pub block_input_operations: u64,
+
+    /// Number of times the file system had to perform output
+    pub block_output_operations: u64,
+}
+
+impl ResourceCounters {
+    /// Subtract the given counters, clamping each counter at zero
+    pub fn saturating_sub(self, other: ResourceCounters) -> ResourceCounters {
+        ResourceCounters {
+            minor_page_faults: self
+                .minor_page_faults
+                .saturating_sub(other.minor_page_faults),
+            major_page_faults: self
+                .major_page_faults
+                .saturating_sub(other.major_page_faults),
+            voluntary_context_switches: self
+                .voluntary_context_switches
+                .saturating_sub(other.voluntary_context_switches),
+            involuntary_context_switches: self
+                .involuntary_context_switches
+                .saturating_sub(other.involuntary_context_switches),
+            block_input_operations: self
+                .block_input_operations
+                .saturating_sub(other.block_input_operations),
+            block_output_operations: self
+                .block_output_operations
+                .saturating_sub(other.block_output_operations),
+        }
+    }
+
+    /// Divide each counter by the given number of invocations, rounding to the nearest integer
+    pub fn per_invocation(self, invocations: u64) -> ResourceCounters {
+        let divide = |counter: u64| (counter + invocations / 2) / invocations;
+        ResourceCounters {
+            minor_page_faults: divide(self.minor_page_faults),
+            major_page_faults: divide(self.major_page_faults),
+            voluntary_context_switches: divide(self.voluntary_context_switches),
+            involuntary_context_switches: divide(self.involuntary_context_switches),
+            block_input_operations: divide(self.block_input_operations),
+            block_output_operations: divide(self.block_output_operations),
+        }
+    }
+}
+
+impl std::ops::Add for ResourceCounters {
+    type Output = ResourceCounters;
+
+    fn add(self, other: ResourceCounters) -> ResourceCounters {
+        ResourceCounters {
+            minor_page_faults: self.minor_page_faults + other.minor_page_faults,
+            major_page_faults: self.major_page_faults + other.major_page_faults,
+            voluntary_context_switches: self.voluntary_context_switches
+                + other.voluntary_context_switches,
+            involuntary_context_switches: self.involuntary_context_switches
+                + other.involuntary_context_switches,
+            block_input_operations: self.block_input_operations + other.block_input_operations,
+            block_output_operations: self.block_output_operations + other.block_output_operations,
+        }
+    }
 }
 
 /// Used to indicate the result of running a command
@@ -38,10 +116,42 @@ pub struct TimerResult {
     pub time_user: Second,
     pub time_system: Second,
 
+    /// Peak memory usage (maximum resident set size) of the process. Not available on all
+    /// platforms
+    pub max_rss: Option<Byte>,
+
+    /// Page faults, context switches and block I/O operations of the process. Not available on
+    /// all platforms
+    pub counters: Option<ResourceCounters>,
+
+    /// Whether the process has been killed because it exceeded the timeout
+    pub timed_out: bool,
+
     /// The exit status of the process
     pub status: ExitStatus,
 }
 
+/// Hash of the captured output of one or more processes. The output of processes that are run
+/// one after another is hashed as if it had been produced by a single process.
+#[derive(Debug, Default, Clone)]
+pub struct OutputHasher {
+    stdout: DefaultHasher,
+    stderr: DefaultHasher,
+}
+
+impl OutputHasher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Combine the hashes of the standard output and the standard error
+    pub fn finish(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        (self.stdout.finish(), self.stderr.finish()).hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
 /// Discard the output of a child process.
 fn discard(output: ChildStdout) {
     const CHUNK_SIZE: usize = 64 << 10;
@@ -73,10 +183,38 @@ fn discard(output: ChildStdout) {
     }
 }
 
-/// Execute the given command and return a timing summary
-pub fn execute_and_measure(mut command: Command) -> Result<TimerResult> {
+/// Read the output of a child process until the end and feed it to the given hasher. The hash
+/// does not depend on how the output is split into chunks.
+fn hash_output(mut output: impl Read, hasher: &mut DefaultHasher) {
+    let mut buf = [0; 64 << 10];
+    while let Ok(bytes) = output.read(&mut buf) {
+        if bytes == 0 {
+            break;
+        }
+        hasher.write(&buf[..bytes]);
+    }
+}
+
+/// Execute the given command and return a timing summary. If a cgroup is given, the command is
+/// run in a new leaf of it and the measurements cover all processes spawned by the command. If a
+/// timeout is given, the command (and on Unix, its whole process group) is killed after that time.
+/// If an output hasher is given, the piped output of the command is fed to it instead of being
+/// discarded.
+pub fn execute_and_measure(
+    mut command: Command,
+    cgroup: Option<&Cgroup>,
+    timeout: Option<Second>,
+    output_hasher: Option<&mut OutputHasher>,
+) -> Result<TimerResult> {
+    let cgroup_leaf = cgroup.map(Cgroup::create_leaf).transpose()?;
+    if let Some(leaf) = &cgroup_leaf {
+        leaf.attach(&mut command);
+    }
+
     #[cfg(not(windows))]
-    let cpu_timer = self::unix_timer::CPUTimer::start();
+    if timeout.is_some() {
+        self::unix_timer::set_new_process_group(&mut command);
+    }
 
     #[cfg(windows)]
     {
@@ -86,6 +224,20 @@ pub fn execute_and_measure(mut command: Command) -> Result<TimerResult> {
         command.creation_flags(4);
     }
 
+    // The thread that reads the stderr stream is created before the timer is started, such
+    // that its creation is not included in the measurement
+    let stderr_reader = output_hasher.map(|output_hasher| {
+        let (sender, receiver) = mpsc::channel::<ChildStderr>();
+        let mut stderr_hasher = std::mem::take(&mut output_hasher.stderr);
+        let reader = thread::spawn(move || {
+            if let Ok(stderr) = receiver.recv() {
+                hash_output(stderr, &mut stderr_hasher);
+            }
+            stderr_hasher
+        });
+        (output_hasher, sender, reader)
+    });
+
     let wallclock_timer = WallClockTimer::start();
     let mut child = command.spawn()?;
 
@@ -95,20 +247,155 @@ pub fn execute_and_measure(mut command: Command) -> Result<TimerResult> {
         unsafe { self::windows_timer::CPUTimer::start_suspended_process(&child) }
     };
 
-    if let Some(output) = child.stdout.take() {
-        // Handle CommandOutputPolicy::Pipe
-        discard(output);
+    let watchdog = timeout.map(|timeout| Watchdog::start(&child, timeout));
+
+    match stderr_reader {
+        Some((output_hasher, sender, reader)) => {
+            // Both streams have to be read concurrently, as the process could block on either one
+            if let Some(stderr) = child.stderr.take() {
+                let _ = sender.send(stderr);
+            }
+            drop(sender);
+            if let Some(stdout) = child.stdout.take() {
+                hash_output(stdout, &mut output_hasher.stdout);
+            }
+            if let Ok(stderr_hasher) = reader.join() {
+                output_hasher.stderr = stderr_hasher;
+            }
+        }
+        None => {
+            if let Some(output) = child.stdout.take() {
+                // Handle CommandOutputPolicy::Pipe
+                discard(output);
+            }
+        }
     }
 
-    let status = child.wait()?;
+    // Stop the timer as soon as the child has exited, before the watchdog is stopped and the
+    // child is reaped
+    #[cfg(not(windows))]
+    let time_real = {
+        self::unix_timer::wait_for_exit(&child)?;
+        wallclock_timer.stop()
+    };
+    let timeout_expired = watchdog.map_or(false, Watchdog::stop);
 
+    // Reap the child with `wait4` to get the resource usage of exactly this process (in
+    // contrast to `RUSAGE_CHILDREN`, which also includes all other children that have been
+    // waited for in the meantime).
+    #[cfg(not(windows))]
+    let (status, time_user, time_system, max_rss, counters) = {
+        let (status, usage) = self::unix_timer::wait_for_child(&child)?;
+        (
+            status,
+            usage.user,
+            usage.system,
+            Some(usage.max_rss),
+            Some(usage.counters),
+        )
+    };
+
+    // The timeout can expire while the child is exiting, in which case it has not been killed
+    #[cfg(not(windows))]
+    let timed_out = {
+        use std::os::unix::process::ExitStatusExt;
+        timeout_expired && status.signal() == Some(libc::SIGKILL)
+    };
+
+    #[cfg(windows)]
+    let (status, max_rss, counters) = (child.wait()?, None, None);
+
+    #[cfg(windows)]
     let time_real = wallclock_timer.stop();
+
+    #[cfg(windows)]
+    let timed_out = timeout_expired && time_real >= timeout.unwrap_or_default();
+
+    #[cfg(windows)]
     let (time_user, time_system) = cpu_timer.stop();
 
+    #[cfg(not(windows))]
+    let (time_user, time_system, max_rss, counters) = match cgroup_leaf {
+        Some(leaf) => {
+            let usage = leaf.usage()?;
+            let counters = counters.map(|counters| ResourceCounters {
+                block_input_operations: usage
+                    .read_operations
+                    .unwrap_or(counters.block_input_operations),
+                block_output_operations: usage
+                    .write_operations
+                    .unwrap_or(counters.block_output_operations),
+                ..counters
+            });
+            (
+                usage.user,
+                usage.system,
+                usage.memory_peak.or(max_rss),
+                counters,
+            )
+        }
+        None => (time_user, time_system, max_rss, counters),
+    };
+
     Ok(TimerResult {
         time_real,
         time_user,
         time_system,
+        max_rss,
+        counters,
+        timed_out,
         status,
     })
 }
+
+/// CPU time of other children that are reaped while a command is being measured must not be
+/// attributed to that command.
+#[cfg(not(windows))]
+#[test]
+fn test_cpu_time_of_other_children_is_not_included() {
+    let mut busy_child = Command::new("sh")
+        .arg("-c")
+        .arg("i=0; while [ $i -lt 300000 ]; do i=$((i+1)); done")
+        .spawn()
+        .unwrap();
+    let reaper = std::thread::spawn(move || busy_child.wait().unwrap());
+
+    let mut command = Command::new("sleep");
+    command.arg("1");
+    let result = execute_and_measure(command, None, None, None).unwrap();
+
+    reaper.join().unwrap();
+
+    assert!(result.status.success());
+    assert!(result.time_user + result.time_system < 0.1);
+}
+
+#[test]
+fn test_output_hash_does_not_depend_on_chunks() {
+    struct Chunked<'a>(&'a [u8], usize);
+
+    impl Read for Chunked<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let n = self.1.min(self.0.len()).min(buf.len());
+            buf[..n].copy_from_slice(&self.0[..n]);
+            self.0 = &self.0[n..];
+            Ok(n)
+        }
+    }
+
+    let hash = |output: &[u8], chunk_size: usize| {
+        let mut hasher = DefaultHasher::new();
+        hash_output(Chunked(output, chunk_size), &mut hasher);
+        hasher.finish()
+    };
+
+    let output = b"hyperfine output".repeat(1000);
+    assert_eq!(hash(&output, 7), hash(&output, 4096));
+    assert_ne!(hash(&output, 7), hash(&output[1..], 7));
+
+    // The output of consecutive processes is hashed like the output of a single process
+    let mut hasher = DefaultHasher::new();
+    hash_output(Chunked(&output[..100], 7), &mut hasher);
+    hash_output(Chunked(&output[100..], 7), &mut hasher);
+    assert_eq!(hasher.finish(), hash(&output, 4096));
+}
@@ -1,91 +1,136 @@
 #![cfg(not(windows))]
 
+use std::convert::TryFrom;
+use std::io;
 use std::mem;
+use std::os::unix::process::{CommandExt, ExitStatusExt};
+use std::process::{Child, Command, ExitStatus};
 
-use crate::timer::CPUTimes;
-use crate::util::units::Second;
+use crate::timer::ResourceCounters;
+use crate::util::units::{Byte, Second};
 
+/// Resource usage of a single (terminated) child process
 #[derive(Debug, Copy, Clone)]
-pub struct CPUInterval {
+pub struct ChildResourceUsage {
     /// Total amount of time spent executing in user mode
     pub user: Second,
 
     /// Total amount of time spent executing in kernel mode
     pub system: Second,
-}
 
-pub struct CPUTimer {
-    start_cpu: CPUTimes,
+    /// Maximum resident set size
+    pub max_rss: Byte,
+
+    /// Page faults, context switches and block I/O operations
+    pub counters: ResourceCounters,
 }
 
-impl CPUTimer {
-    pub fn start() -> Self {
-        CPUTimer {
-            start_cpu: get_cpu_times(),
+/// Wait for the given child process to terminate. In contrast to `Child::wait`, this also returns
+/// the resource usage of this specific process (instead of the accumulated usage of all children).
+pub fn wait_for_child(child: &Child) -> io::Result<(ExitStatus, ChildResourceUsage)> {
+    use libc::{pid_t, rusage, wait4};
+
+    let pid = child.id() as pid_t;
+    let mut status = 0;
+    let mut usage: rusage = unsafe { mem::zeroed() };
+
+    loop {
+        // SAFETY: `status` and `usage` are valid, writable locations
+        let result = unsafe { wait4(pid, &mut status, 0, &mut usage) };
+        if result == pid {
+            break;
         }
-    }
 
-    pub fn stop(&self) -> (Second, Second) {
-        let end_cpu = get_cpu_times();
-        let cpu_interval = cpu_time_interval(&self.start_cpu, &end_cpu);
-        (cpu_interval.user, cpu_interval.system)
+        let error = io::Error::last_os_error();
+        if error.kind() != io::ErrorKind::Interrupted {
+            return Err(error);
+        }
     }
-}
 
-/// Read CPU execution times ('user' and 'system')
-fn get_cpu_times() -> CPUTimes {
-    use libc::{getrusage, rusage, RUSAGE_CHILDREN};
+    Ok((
+        ExitStatus::from_raw(status),
+        ChildResourceUsage {
+            user: time_in_seconds(usage.ru_utime),
+            system: time_in_seconds(usage.ru_stime),
+            max_rss: max_rss_in_bytes(usage.ru_maxrss),
+            counters: ResourceCounters {
+                minor_page_faults: counter(usage.ru_minflt),
+                major_page_faults: counter(usage.ru_majflt),
+                voluntary_context_switches: counter(usage.ru_nvcsw),
+                involuntary_context_switches: counter(usage.ru_nivcsw),
+                block_input_operations: counter(usage.ru_inblock),
+                block_output_operations: counter(usage.ru_oublock),
+            },
+        },
+    ))
+}
 
-    let result: rusage = unsafe {
-        let mut buf = mem::zeroed();
-        let success = getrusage(RUSAGE_CHILDREN, &mut buf);
-        assert_eq!(0, success);
-        buf
-    };
+/// Wait for the given child process to terminate, but leave it in a waitable state, such that its
+/// process ID can not be reused yet.
+pub fn wait_for_exit(child: &Child) -> io::Result<()> {
+    use libc::{id_t, siginfo_t, waitid, P_PID, WEXITED, WNOWAIT};
+
+    let mut info: siginfo_t = unsafe { mem::zeroed() };
+    loop {
+        // SAFETY: `info` is a valid, writable location
+        let result = unsafe { waitid(P_PID, child.id() as id_t, &mut info, WEXITED | WNOWAIT) };
+        if result == 0 {
+            return Ok(());
+        }
 
-    const MICROSEC_PER_SEC: i64 = 1000 * 1000;
+        let error = io::Error::last_os_error();
+        if error.kind() != io::ErrorKind::Interrupted {
+            return Err(error);
+        }
+    }
+}
 
-    #[allow(clippy::useless_conversion)]
-    CPUTimes {
-        user_usec: i64::from(result.ru_utime.tv_sec) * MICROSEC_PER_SEC
-            + i64::from(result.ru_utime.tv_usec),
-        system_usec: i64::from(result.ru_stime.tv_sec) * MICROSEC_PER_SEC
-            + i64::from(result.ru_stime.tv_usec),
+/// Run the process of the given command in a new process group, such that it can be killed
+/// together with all of its children.
+pub fn set_new_process_group(command: &mut Command) {
+    // SAFETY: only the async-signal-safe `setpgid` is called in between fork and exec
+    unsafe {
+        command.pre_exec(|| {
+            if libc::setpgid(0, 0) == 0 {
+                Ok(())
+            } else {
+                Err(io::Error::last_os_error())
+            }
+        });
     }
 }
 
-/// Compute the time intervals in between two `CPUTimes` snapshots
-fn cpu_time_interval(start: &CPUTimes, end: &CPUTimes) -> CPUInterval {
-    CPUInterval {
-        user: ((end.user_usec - start.user_usec) as f64) * 1e-6,
-        system: ((end.system_usec - start.system_usec) as f64) * 1e-6,
+/// Convert the `ru_maxrss` field to bytes. It is given in bytes on macOS, but in kilobytes on all
+/// other platforms.
+#[allow(clippy::useless_conversion)]
+fn max_rss_in_bytes(max_rss: libc::c_long) -> Byte {
+    let max_rss = Byte::try_from(i64::from(max_rss)).unwrap_or(0);
+    if cfg!(target_os = "macos") {
+        max_rss
+    } else {
+        max_rss * 1024
     }
 }
 
-#[cfg(test)]
-use approx::assert_relative_eq;
+/// Convert one of the `rusage` counters, which are signed on all platforms
+#[allow(clippy::useless_conversion)]
+fn counter(value: libc::c_long) -> u64 {
+    u64::try_from(i64::from(value)).unwrap_or(0)
+}
+
+/// Convert a `timeval` from the resource usage to seconds
+#[allow(clippy::useless_conversion)]
+fn time_in_seconds(time: libc::timeval) -> Second {
+    i64::from(time.tv_sec) as Second + i64::from(time.tv_usec) as Second * 1e-6
+}
 
 #[test]
-fn test_cpu_time_interval() {
-    let t_a = CPUTimes {
-        user_usec: 12345,
-        system_usec: 54321,
-    };
+fn test_time_in_seconds() {
+    use approx::assert_relative_eq;
 
-    let t_b = CPUTimes {
-        user_usec: 20000,
-        system_usec: 70000,
+    let time = libc::timeval {
+        tv_sec: 2,
+        tv_usec: 345_678,
     };
-
-    let t_zero = cpu_time_interval(&t_a, &t_a);
-    assert!(t_zero.user.abs() < f64::EPSILON);
-    assert!(t_zero.system.abs() < f64::EPSILON);
-
-    let t_ab = cpu_time_interval(&t_a, &t_b);
-    assert_relative_eq!(0.007655, t_ab.user);
-    assert_relative_eq!(0.015679, t_ab.system);
-
-    let t_ba = cpu_time_interval(&t_b, &t_a);
-    assert_relative_eq!(-0.007655, t_ba.user);
-    assert_relative_eq!(-0.015679, t_ba.system);
+    assert_relative_eq!(2.345678, time_in_seconds(time));
 }
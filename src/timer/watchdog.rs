@@ -0,0 +1,64 @@
+use std::process::Child;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::util::units::Second;
+
+/// Kills a child process if it is still running after a given timeout. On Unix, the whole
+/// process group of the child is killed.
+pub struct Watchdog {
+    cancel: Sender<()>,
+    thread: JoinHandle<bool>,
+}
+
+impl Watchdog {
+    /// Start the timeout for the given child process. The child must not be reaped before the
+    /// watchdog has been stopped, as its ID could be reused otherwise.
+    pub fn start(child: &Child, timeout: Second) -> Watchdog {
+        let pid = child.id();
+        let (cancel, cancelled) = mpsc::channel();
+        let timeout = Duration::from_secs_f64(timeout);
+        let thread = thread::spawn(move || match cancelled.recv_timeout(timeout) {
+            Err(RecvTimeoutError::Timeout) => {
+                kill(pid);
+                true
+            }
+            _ => false,
+        });
+
+        Watchdog { cancel, thread }
+    }
+
+    /// Stop the watchdog. Returns whether the timeout has expired. The child might have exited
+    /// on its own shortly before, so this does not necessarily mean that it has been killed.
+    pub fn stop(self) -> bool {
+        let _ = self.cancel.send(());
+        self.thread.join().unwrap_or(false)
+    }
+}
+
+#[cfg(not(windows))]
+fn kill(pid: u32) {
+    // SAFETY: sending a signal has no memory safety implications. The process group has been
+    // created for the child, so no unrelated processes are affected.
+    unsafe {
+        libc::kill(-(pid as libc::pid_t), libc::SIGKILL);
+    }
+}
+
+#[cfg(windows)]
+fn kill(pid: u32) {
+    use winapi::um::handleapi::CloseHandle;
+    use winapi::um::processthreadsapi::{OpenProcess, TerminateProcess};
+    use winapi::um::winnt::PROCESS_TERMINATE;
+
+    // SAFETY: the handle is checked for validity and closed after use
+    unsafe {
+        let handle = OpenProcess(PROCESS_TERMINATE, 0, pid);
+        if !handle.is_null() {
+            TerminateProcess(handle, 1);
+            CloseHandle(handle);
+        }
+    }
+}
@@ -5,6 +5,9 @@ pub type Scalar = f64;
 /// Type alias for unit of time
 pub type Second = Scalar;
 
+/// Type alias for unit of memory
+pub type Byte = u64;
+
 /// Supported time units
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Unit {
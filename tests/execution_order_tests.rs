@@ -54,6 +54,11 @@ impl ExecutionOrderTest {
         self.command(output)
     }
 
+    fn conclude(&mut self, output: &str) -> &mut Self {
+        self.arg("--conclude");
+        self.command(output)
+    }
+
     fn cleanup(&mut self, output: &str) -> &mut Self {
         self.arg("--cleanup");
         self.command(output)
@@ -142,6 +147,57 @@ fn setup_commands_are_executed_before_each_series_of_timing_runs() {
         .run();
 }
 
+#[test]
+fn setup_and_cleanup_commands_can_be_specified_per_command() {
+    ExecutionOrderTest::new()
+        .arg("--runs=1")
+        .setup("setup 1")
+        .setup("setup 2")
+        .cleanup("cleanup 1")
+        .cleanup("cleanup 2")
+        .command("command 1")
+        .command("command 2")
+        .expect_output("setup 1")
+        .expect_output("command 1")
+        .expect_output("cleanup 1")
+        .expect_output("setup 2")
+        .expect_output("command 2")
+        .expect_output("cleanup 2")
+        .run();
+}
+
+#[test]
+fn warmup_count_can_be_specified_per_command() {
+    ExecutionOrderTest::new()
+        .arg("--runs=1")
+        .arg("--warmup=2")
+        .arg("--warmup=0")
+        .command("command 1")
+        .command("command 2")
+        .expect_output("command 1")
+        .expect_output("command 1")
+        .expect_output("command 1")
+        .expect_output("command 2")
+        .run();
+}
+
+#[test]
+fn round_robin_execution_order_with_different_warmup_counts() {
+    ExecutionOrderTest::new()
+        .arg("--runs=1")
+        .arg("--execution-order=round-robin")
+        .arg("--warmup=1")
+        .arg("--warmup=2")
+        .command("command 1")
+        .command("command 2")
+        .expect_output("command 1")
+        .expect_output("command 2")
+        .expect_output("command 2")
+        .expect_output("command 1")
+        .expect_output("command 2")
+        .run();
+}
+
 #[test]
 fn prepare_commands_are_executed_before_each_timing_run() {
     ExecutionOrderTest::new()
@@ -187,6 +243,40 @@ fn prepare_commands_are_executed_before_each_warmup() {
         .run();
 }
 
+#[test]
+fn conclude_commands_are_executed_after_each_timing_run_and_warmup() {
+    ExecutionOrderTest::new()
+        .arg("--warmup=1")
+        .arg("--runs=2")
+        .prepare("prepare")
+        .conclude("conclude")
+        .command("command 1")
+        .command("command 2")
+        // warmup 1
+        .expect_output("prepare")
+        .expect_output("command 1")
+        .expect_output("conclude")
+        // benchmark 1
+        .expect_output("prepare")
+        .expect_output("command 1")
+        .expect_output("conclude")
+        .expect_output("prepare")
+        .expect_output("command 1")
+        .expect_output("conclude")
+        // warmup 2
+        .expect_output("prepare")
+        .expect_output("command 2")
+        .expect_output("conclude")
+        // benchmark 2
+        .expect_output("prepare")
+        .expect_output("command 2")
+        .expect_output("conclude")
+        .expect_output("prepare")
+        .expect_output("command 2")
+        .expect_output("conclude")
+        .run();
+}
+
 #[test]
 fn cleanup_commands_are_executed_once_after_each_benchmark() {
     ExecutionOrderTest::new()
@@ -276,3 +366,103 @@ fn multiple_parameter_values() {
         .expect_output("command 3 b")
         .run();
 }
+
+#[test]
+fn round_robin_execution_order_interleaves_timing_runs() {
+    ExecutionOrderTest::new()
+        .arg("--runs=2")
+        .arg("--execution-order=round-robin")
+        .setup("setup")
+        .cleanup("cleanup")
+        .command("command 1")
+        .command("command 2")
+        .expect_output("setup")
+        .expect_output("command 1")
+        .expect_output("setup")
+        .expect_output("command 2")
+        .expect_output("command 1")
+        .expect_output("cleanup")
+        .expect_output("command 2")
+        .expect_output("cleanup")
+        .run();
+}
+
+#[test]
+fn round_robin_execution_order_interleaves_warmup_runs() {
+    ExecutionOrderTest::new()
+        .arg("--warmup=1")
+        .arg("--runs=1")
+        .arg("--execution-order=round-robin")
+        .prepare("prepare")
+        .command("command 1")
+        .command("command 2")
+        // warmup
+        .expect_output("prepare")
+        .expect_output("command 1")
+        .expect_output("prepare")
+        .expect_output("command 2")
+        // benchmark
+        .expect_output("prepare")
+        .expect_output("command 1")
+        .expect_output("prepare")
+        .expect_output("command 2")
+        .run();
+}
+
+#[test]
+fn shuffled_execution_order_is_reproducible() {
+    let run_with_seed = |seed: &str| {
+        let mut test = ExecutionOrderTest::new();
+        test.arg("--runs=10")
+            .arg("--execution-order=shuffled")
+            .arg(format!("--seed={}", seed))
+            .command("command 1")
+            .command("command 2")
+            .command("command 3");
+        test.cmd.assert().success();
+
+        std::fs::read_to_string(&test.logfile_path).unwrap()
+    };
+
+    let log = run_with_seed("42");
+    for i in 1..=3 {
+        assert_eq!(10, log.matches(&format!("command {}", i)).count());
+    }
+
+    assert_eq!(log, run_with_seed("42"));
+    assert_ne!(log, run_with_seed("43"));
+}
+
+#[test]
+fn batch_mode_runs_the_command_multiple_times_per_timing_run() {
+    ExecutionOrderTest::new()
+        .arg("--runs=2")
+        .arg("--batch=3")
+        .prepare("prepare")
+        .command("command")
+        .expect_output("prepare")
+        .expect_output("command")
+        .expect_output("command")
+        .expect_output("command")
+        .expect_output("prepare")
+        .expect_output("command")
+        .expect_output("command")
+        .expect_output("command")
+        .run();
+}
+
+#[cfg(unix)]
+#[test]
+fn batch_mode_without_shell() {
+    let mut test = ExecutionOrderTest::new();
+    let command = format!("sh -c '{}'", test.get_command("command"));
+    test.arg("--runs=2")
+        .arg("--batch=2")
+        .arg("--shell=none")
+        .arg(command)
+        .expect_output("command")
+        .expect_output("command")
+        .expect_output("command")
+        .expect_output("command")
+        .run();
+}
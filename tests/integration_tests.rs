@@ -167,6 +167,18 @@ fn fails_for_unknown_prepare_command() {
         ));
 }
 
+#[test]
+fn fails_for_unknown_conclude_command() {
+    hyperfine()
+        .arg("--conclude=some-nonexisting-program-b5d9574198b7e4b12a71fa4747c0a577")
+        .arg("echo test")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "The conclusion command terminated with a non-zero exit code.",
+        ));
+}
+
 #[cfg(unix)]
 #[test]
 fn can_run_failing_commands_with_ignore_failure_option() {
@@ -319,3 +331,971 @@ fn performs_all_benchmarks_in_parameter_scan() {
                 .and(predicate::str::contains("Benchmark 5: sleep 50").not()),
         );
 }
+
+#[test]
+fn performs_all_benchmarks_in_logarithmic_parameter_scan() {
+    hyperfine_debug()
+        .arg("--parameter-scan")
+        .arg("time")
+        .arg("0.01")
+        .arg("10")
+        .arg("--parameter-points")
+        .arg("4")
+        .arg("sleep {time}")
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("Benchmark 1: sleep 0.01")
+                .and(predicate::str::contains("Benchmark 2: sleep 0.1"))
+                .and(predicate::str::contains("Benchmark 3: sleep 1"))
+                .and(predicate::str::contains("Benchmark 4: sleep 10"))
+                .and(predicate::str::contains("Benchmark 5:").not()),
+        );
+}
+
+#[test]
+fn exports_all_parameters_of_combined_scans_and_lists() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let json_path = tempdir.path().join("results.json");
+
+    hyperfine_debug()
+        .arg("--parameter-scan")
+        .arg("time")
+        .arg("1")
+        .arg("2")
+        .arg("--parameter-list")
+        .arg("digit")
+        .arg("0,5")
+        .arg("--export-json")
+        .arg(&json_path)
+        .arg("sleep {time}{digit}")
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("Benchmark 1: sleep 10")
+                .and(predicate::str::contains("Benchmark 4: sleep 25")),
+        );
+
+    let json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(json_path).unwrap()).unwrap();
+    let parameters = &json["results"][3]["parameters"];
+    assert_eq!(parameters["time"], "2");
+    assert_eq!(parameters["digit"], "5");
+}
+
+#[test]
+fn performs_benchmarks_for_parameter_values_from_file_and_command() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let values_path = tempdir.path().join("values.txt");
+    std::fs::write(&values_path, "1\n\n2\n").unwrap();
+
+    hyperfine_debug()
+        .arg("--parameter-list-file")
+        .arg("time")
+        .arg(&values_path)
+        .arg("--parameter-list-cmd")
+        .arg("digit")
+        .arg("echo 0; echo 5")
+        .arg("sleep {time}{digit}")
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("Benchmark 1: sleep 10")
+                .and(predicate::str::contains("Benchmark 2: sleep 20"))
+                .and(predicate::str::contains("Benchmark 3: sleep 15"))
+                .and(predicate::str::contains("Benchmark 4: sleep 25"))
+                .and(predicate::str::contains("Benchmark 5:").not()),
+        );
+}
+
+#[test]
+fn fails_for_zipped_parameters_with_different_lengths() {
+    hyperfine()
+        .arg("--parameter-list")
+        .arg("version")
+        .arg("1.0,1.1")
+        .arg("--parameter-list")
+        .arg("binary")
+        .arg("a,b,c")
+        .arg("--parameter-zip")
+        .arg("version,binary")
+        .arg("echo {binary} {version}")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "The zipped parameters 'version' and 'binary' have different numbers of values (2 and 3)",
+        ));
+}
+
+#[test]
+fn reports_parameter_combinations_excluded_by_filter() {
+    hyperfine_debug()
+        .arg("--parameter-scan")
+        .arg("time")
+        .arg("1")
+        .arg("3")
+        .arg("--parameter-filter")
+        .arg("time != 2")
+        .arg("sleep {time}")
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains(
+                "Skipping 1 parameter combination excluded by '--parameter-filter'.",
+            )
+            .and(predicate::str::contains("Benchmark 1: sleep 1"))
+            .and(predicate::str::contains("Benchmark 2: sleep 3")),
+        );
+}
+
+#[test]
+fn fails_for_failing_parameter_command() {
+    hyperfine()
+        .arg("--parameter-list-cmd")
+        .arg("x")
+        .arg("echo a; exit 1")
+        .arg("echo {x}")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "The parameter command 'echo a; exit 1' terminated with a non-zero exit code",
+        ));
+}
+
+#[test]
+fn runs_parameter_command_in_the_given_shell() {
+    hyperfine()
+        .arg("--shell=nonexistent-shell")
+        .arg("--parameter-list-cmd")
+        .arg("x")
+        .arg("echo a")
+        .arg("echo {x}")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Failed to run the parameter command 'echo a'",
+        ));
+}
+
+#[cfg(unix)]
+#[test]
+fn shows_statistical_significance_in_benchmark_comparison() {
+    hyperfine()
+        .arg("--runs=5")
+        .arg("sleep 0.01")
+        .arg("sleep 0.1")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "statistically significant at α = 0.05",
+        ));
+}
+
+#[test]
+fn fails_with_invalid_significance_level() {
+    hyperfine()
+        .arg("--significance-level=1.5")
+        .arg("echo a")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "The significance level has to be strictly between 0 and 1",
+        ));
+}
+
+#[test]
+fn shows_confidence_intervals() {
+    hyperfine_debug()
+        .arg("--confidence-level=0.9")
+        .arg("sleep 1.0")
+        .arg("sleep 2.0")
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("CI (mean, 90%):       1.000 s …  1.000 s")
+                .and(predicate::str::contains("(90% CI: 2.00 … 2.00)")),
+        );
+}
+
+#[test]
+fn exports_confidence_interval_of_relative_speed() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let json_path = tempdir.path().join("results.json");
+
+    hyperfine_debug()
+        .arg("--export-json")
+        .arg(&json_path)
+        .arg("sleep 2.0")
+        .arg("sleep 1.0")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("(95% CI: 2.00 … 2.00"));
+
+    let json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(json_path).unwrap()).unwrap();
+    let ci = &json["results"][0]["relative_speed_ci"];
+    assert_eq!(ci["lower"], 2.0);
+    assert_eq!(ci["upper"], 2.0);
+    assert!(json["results"][1].get("relative_speed_ci").is_none());
+}
+
+#[cfg(unix)]
+#[test]
+fn exports_p_value_of_comparison_with_fastest() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let json_path = tempdir.path().join("results.json");
+
+    hyperfine()
+        .arg("--runs=5")
+        .arg("--export-json")
+        .arg(&json_path)
+        .arg("sleep 0.01")
+        .arg("sleep 0.1")
+        .assert()
+        .success();
+
+    let json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(json_path).unwrap()).unwrap();
+    assert!(json["results"][0].get("p_value").is_none());
+    assert!(json["results"][1]["p_value"].as_f64().unwrap() < 0.05);
+}
+
+#[cfg(unix)]
+#[test]
+fn shows_peak_memory_usage() {
+    hyperfine()
+        .arg("--runs=2")
+        .arg("echo a")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Memory:"));
+}
+
+#[test]
+fn performs_adaptive_number_of_runs_with_precision_target() {
+    // The mock executor returns identical times, i.e. the precision is reached immediately.
+    hyperfine_debug()
+        .arg("--precision=1%")
+        .arg("sleep 0.1")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("10 runs"));
+
+    hyperfine_debug()
+        .arg("--precision=1%")
+        .arg("--min-runs=15")
+        .arg("sleep 0.1")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("15 runs"));
+}
+
+#[test]
+fn warns_if_precision_target_is_not_reached() {
+    hyperfine()
+        .arg("--precision=0.01%")
+        .arg("--max-runs=3")
+        .arg("sleep 0.001")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("3 runs"))
+        .stderr(predicate::str::contains(
+            "The requested precision of ±0.01% could not be reached",
+        ));
+}
+
+#[test]
+fn fails_with_invalid_precision() {
+    hyperfine()
+        .arg("--precision=abc")
+        .arg("echo a")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid precision 'abc'"));
+}
+
+#[cfg(unix)]
+#[test]
+fn exports_extra_resource_usage_columns() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let csv_path = tempdir.path().join("results.csv");
+
+    hyperfine()
+        .arg("--runs=2")
+        .arg("--export-columns=minor-page-faults,block-output-operations")
+        .arg("--export-csv")
+        .arg(&csv_path)
+        .arg("echo a")
+        .assert()
+        .success();
+
+    let csv = std::fs::read_to_string(csv_path).unwrap();
+    let header = csv.lines().next().unwrap();
+    assert!(header.ends_with(",minor_page_faults,block_output_operations"));
+}
+
+#[test]
+fn fails_with_invalid_export_column() {
+    hyperfine()
+        .arg("--export-columns=page-faults")
+        .arg("echo a")
+        .assert()
+        .failure()
+        .stderr(
+            predicate::str::contains("'page-faults'")
+                .and(predicate::str::contains("--export-columns")),
+        );
+}
+
+#[cfg(unix)]
+#[test]
+fn does_not_include_cpu_time_of_prepare_command() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let json_path = tempdir.path().join("results.json");
+
+    hyperfine()
+        .arg("--runs=2")
+        .arg("--shell=none")
+        .arg("--prepare=sh -c 'i=0; while [ $i -lt 100000 ]; do i=$((i+1)); done'")
+        .arg("--export-json")
+        .arg(&json_path)
+        .arg("sleep 0.1")
+        .assert()
+        .success();
+
+    let json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(json_path).unwrap()).unwrap();
+    let result = &json["results"][0];
+    let cpu_time = result["user"].as_f64().unwrap() + result["system"].as_f64().unwrap();
+    assert!(
+        cpu_time < 0.05,
+        "CPU time of prepare command leaked: {}",
+        cpu_time
+    );
+}
+
+// The cgroup tests require a writable cgroup v2 hierarchy with the memory and io controllers
+// delegated to the current user. Run them with `cargo test -- --ignored`.
+#[cfg(target_os = "linux")]
+#[test]
+#[ignore]
+fn runs_successfully_with_cgroup_measurement() {
+    hyperfine()
+        .arg("--cgroup")
+        .arg("--runs=2")
+        .arg("echo a")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Time (mean ± σ):"))
+        .stderr(predicate::str::contains("Warning").not());
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+#[ignore]
+fn cgroup_measurement_includes_cpu_time_of_detached_grandchildren() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let json_path = tempdir.path().join("results.json");
+
+    // The busy loop runs in a detached grandchild that is never waited for by the benchmarked
+    // shell, so its CPU time is only accounted for in the cgroup.
+    hyperfine()
+        .arg("--cgroup")
+        .arg("--runs=2")
+        .arg("--export-json")
+        .arg(&json_path)
+        .arg("sh -c '( (i=0; while [ $i -lt 100000 ]; do i=$((i+1)); done) & ); sleep 1'")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Warning").not());
+
+    let json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(json_path).unwrap()).unwrap();
+    let result = &json["results"][0];
+    let cpu_time = result["user"].as_f64().unwrap() + result["system"].as_f64().unwrap();
+    assert!(
+        cpu_time > 0.02,
+        "CPU time of the grandchild is missing: {}",
+        cpu_time
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn aborts_if_benchmark_exceeds_timeout() {
+    hyperfine()
+        .arg("--runs=2")
+        .arg("--timeout=0.1")
+        .arg("sleep 10")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Command 'sleep 10' has been killed because it exceeded the timeout of 100.0 ms",
+        ));
+}
+
+#[cfg(unix)]
+#[test]
+fn excludes_timed_out_runs_from_statistics() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let flag = tempdir.path().join("flag");
+    let json_path = tempdir.path().join("results.json");
+
+    // Every second run exceeds the timeout
+    hyperfine()
+        .arg("--runs=4")
+        .arg("--timeout=0.5")
+        .arg("--on-timeout=exclude")
+        .arg("--export-json")
+        .arg(&json_path)
+        .arg(format!(
+            "if [ -e {flag} ]; then rm {flag}; sleep 10; else touch {flag}; fi",
+            flag = flag.display()
+        ))
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "2 runs exceeded the timeout of 500.0 ms and have been excluded from the statistics",
+        ));
+
+    let json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(json_path).unwrap()).unwrap();
+    let result = &json["results"][0];
+    assert_eq!(2, result["timeouts"].as_u64().unwrap());
+    assert_eq!(2, result["times"].as_array().unwrap().len());
+}
+
+#[cfg(unix)]
+#[test]
+fn fails_if_intermediate_command_exceeds_timeout() {
+    hyperfine()
+        .arg("--runs=2")
+        .arg("--prepare=sleep 10")
+        .arg("--prepare-timeout=0.1")
+        .arg("echo a")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "The preparation command has been killed because it exceeded the timeout",
+        ));
+}
+
+#[test]
+fn fails_with_invalid_timeout() {
+    hyperfine()
+        .arg("--timeout=0")
+        .arg("echo a")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "The argument to '--timeout' has to be a positive number of seconds, but is 0",
+        ));
+}
+
+#[test]
+fn fails_with_invalid_max_benchmarking_time() {
+    for time in ["0", "-1", "NaN", "inf"] {
+        hyperfine()
+            .arg("--precision=5%")
+            .arg(format!("--max-benchmarking-time={}", time))
+            .arg("echo a")
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "The argument to '--max-benchmarking-time' has to be a positive number of seconds",
+            ));
+    }
+}
+
+#[test]
+fn determines_batch_size_automatically() {
+    hyperfine_debug()
+        .arg("--batch=auto")
+        .arg("--runs=2")
+        .arg("sleep 0.001")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("2 runs of 50 invocations"));
+}
+
+#[test]
+fn fails_with_invalid_batch_size() {
+    hyperfine()
+        .arg("--batch=0")
+        .arg("echo a")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Invalid batch size '0'. Expected a positive integer or 'auto'",
+        ));
+}
+
+#[cfg(unix)]
+#[test]
+fn fails_if_batched_command_leaves_the_shell() {
+    for command in ["exit 0", "exec true"] {
+        hyperfine()
+            .arg("--batch=3")
+            .arg("--runs=2")
+            .arg(command)
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "of the 3 invocations of the batch have finished",
+            ));
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn stops_batch_at_first_failure() {
+    hyperfine()
+        .arg("--batch=3")
+        .arg("--runs=2")
+        .arg("--ignore-failure")
+        .arg("false")
+        .assert()
+        .success();
+
+    hyperfine()
+        .arg("--batch=3")
+        .arg("--runs=2")
+        .arg("false")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Command terminated with non-zero exit code: 1",
+        ));
+}
+
+#[test]
+fn runs_benchmark_suite_from_config_file() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let config_path = tempdir.path().join("suite.toml");
+    std::fs::write(
+        &config_path,
+        "runs = 2\n\
+         \n\
+         [[command]]\n\
+         name = 'sleep-{duration}'\n\
+         command = 'sleep {duration}'\n\
+         \n\
+         [[parameter-list]]\n\
+         name = 'duration'\n\
+         values = [0.1, 0.2]\n",
+    )
+    .unwrap();
+
+    hyperfine_debug()
+        .arg("--config")
+        .arg(&config_path)
+        .arg("--runs=3")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Benchmark 1: sleep-0.1"))
+        .stdout(predicate::str::contains("Benchmark 2: sleep-0.2"))
+        .stdout(predicate::str::contains("3 runs"));
+}
+
+#[test]
+fn fails_with_unknown_key_in_config_file() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let config_path = tempdir.path().join("suite.toml");
+    std::fs::write(
+        &config_path,
+        "[[command]]\ncommand = 'echo a'\nwarmpu = 3\n",
+    )
+    .unwrap();
+
+    hyperfine()
+        .arg("--config")
+        .arg(&config_path)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Invalid value for key `command[0]`: unknown field `warmpu`",
+        ));
+}
+
+#[test]
+fn combines_per_command_options_from_config_file_with_commands_on_the_command_line() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let config_path = tempdir.path().join("suite.toml");
+    std::fs::write(
+        &config_path,
+        "prepare = 'sleep 0.1'\n\
+         [[command]]\ncommand = 'sleep 1'\nprepare = 'sleep 0.2'\n\
+         [[command]]\ncommand = 'sleep 2'\nprepare = 'sleep 0.3'\n",
+    )
+    .unwrap();
+
+    hyperfine_debug()
+        .arg("--config")
+        .arg(&config_path)
+        .arg("sleep 3")
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("Benchmark 2: sleep 2")
+                .and(predicate::str::contains("Benchmark 3: sleep 3")),
+        );
+}
+
+#[test]
+fn fails_with_invalid_value_in_config_file() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let config_path = tempdir.path().join("suite.toml");
+    std::fs::write(
+        &config_path,
+        "style = 'bogus'\n[[command]]\ncommand = 'echo a'\n",
+    )
+    .unwrap();
+
+    hyperfine()
+        .arg("--config")
+        .arg(&config_path)
+        .assert()
+        .failure()
+        .stderr(
+            predicate::str::contains("suite.toml")
+                .and(predicate::str::contains("Invalid value for key `style`")),
+        );
+}
+
+#[cfg(unix)]
+#[test]
+fn runs_commands_in_the_given_working_directories() {
+    let first = tempfile::tempdir().unwrap();
+    let second = tempfile::tempdir().unwrap();
+
+    hyperfine()
+        .arg("--runs=1")
+        .arg("--working-dir")
+        .arg(first.path())
+        .arg("--working-dir")
+        .arg(second.path())
+        .arg("--setup=touch setup-was-here")
+        .arg("touch first-was-here")
+        .arg("touch second-was-here")
+        .assert()
+        .success();
+
+    assert!(first.path().join("setup-was-here").exists());
+    assert!(first.path().join("first-was-here").exists());
+    assert!(second.path().join("setup-was-here").exists());
+    assert!(second.path().join("second-was-here").exists());
+    assert!(!first.path().join("second-was-here").exists());
+}
+
+#[test]
+fn fails_with_nonexistent_working_directory() {
+    hyperfine()
+        .arg("--working-dir=/this/directory/does/not/exist")
+        .arg("echo a")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "The working directory '/this/directory/does/not/exist' does not exist",
+        ));
+}
+
+#[test]
+fn fails_with_wrong_number_of_per_command_options() {
+    hyperfine()
+        .arg("--warmup=1")
+        .arg("--warmup=2")
+        .arg("echo a")
+        .arg("echo b")
+        .arg("echo c")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "The '--warmup' option has to be provided just once or N times",
+        ));
+}
+
+#[cfg(unix)]
+#[test]
+fn connects_input_file_to_stdin() {
+    let directory = tempfile::tempdir().unwrap();
+    std::fs::write(directory.path().join("input-1.txt"), "first\n").unwrap();
+    std::fs::write(directory.path().join("input-2.txt"), "second\nsecond\n").unwrap();
+
+    hyperfine()
+        .arg("--runs=2")
+        .arg("--show-output")
+        .arg("--working-dir")
+        .arg(directory.path())
+        .arg("--input=input-{n}.txt")
+        .arg("--parameter-scan")
+        .arg("n")
+        .arg("1")
+        .arg("2")
+        .arg("-N")
+        .arg("cat")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("first\nfirst\n"))
+        .stdout(predicate::str::contains("second\nsecond\nsecond\nsecond\n"));
+}
+
+#[test]
+fn fails_with_nonexistent_input_file() {
+    hyperfine()
+        .arg("--input=/this/file/does/not/exist")
+        .arg("echo a")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Failed to open the input file '/this/file/does/not/exist'",
+        ));
+}
+
+#[cfg(unix)]
+#[test]
+fn sets_environment_variables_per_command() {
+    hyperfine()
+        .arg("--runs=1")
+        .arg("--show-output")
+        .arg("--env=FIRST=global")
+        .arg("--env=2:FIRST=specific")
+        .arg("--unset-env=1:SECOND")
+        .arg("echo \"[$FIRST|$SECOND]\"")
+        .arg("echo \"[$FIRST|$SECOND] \"")
+        .env("SECOND", "inherited")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[global|]"))
+        .stdout(predicate::str::contains("[specific|inherited]"));
+}
+
+#[cfg(unix)]
+#[test]
+fn runs_commands_in_a_clean_environment() {
+    hyperfine()
+        .arg("--runs=1")
+        .arg("--show-output")
+        .arg("--clean-env")
+        .arg("--keep-env=KEPT")
+        .arg("echo \"[$KEPT|$REMOVED]\"")
+        .env("KEPT", "kept")
+        .env("REMOVED", "removed")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[kept|]"));
+}
+
+#[test]
+fn fails_with_invalid_environment_variables() {
+    hyperfine()
+        .arg("--env=NAME")
+        .arg("echo a")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Invalid argument 'NAME' to '--env'",
+        ));
+
+    hyperfine()
+        .arg("--env=3:NAME=VALUE")
+        .arg("echo a")
+        .arg("echo b")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "The environment of benchmark 3 can not be changed",
+        ));
+}
+
+#[test]
+fn checks_that_commands_produce_the_same_output() {
+    hyperfine()
+        .arg("--runs=2")
+        .arg("--check-output")
+        .arg("echo a")
+        .arg("echo   a")
+        .assert()
+        .success();
+
+    hyperfine()
+        .arg("--runs=2")
+        .arg("--check-output")
+        .arg("echo a")
+        .arg("echo b")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "The output of command 'echo b' differs from the output of command 'echo a'",
+        ));
+
+    hyperfine()
+        .arg("--runs=2")
+        .arg("--check-output")
+        .arg("--on-output-mismatch=warn")
+        .arg("echo a")
+        .arg("echo b")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "The output of this command differs from the output of 'echo a'",
+        ));
+}
+
+#[test]
+fn compares_output_only_for_the_same_parameter_values() {
+    hyperfine()
+        .arg("--runs=2")
+        .arg("--check-output")
+        .arg("--parameter-list")
+        .arg("x")
+        .arg("a,b")
+        .arg("echo {x}")
+        .arg("echo   {x}")
+        .assert()
+        .success();
+}
+
+#[cfg(unix)]
+#[test]
+fn detects_differing_output_between_runs() {
+    let directory = tempfile::tempdir().unwrap();
+
+    hyperfine()
+        .arg("--runs=3")
+        .arg("--check-output")
+        .arg("--check-stderr")
+        .arg("--working-dir")
+        .arg(directory.path())
+        .arg("echo run >> runs; cat runs >&2")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "in run 2 differs from the output of the first run",
+        ));
+}
+
+#[test]
+fn accepts_the_given_exit_codes() {
+    hyperfine()
+        .arg("--runs=2")
+        .arg("--accept-exit-codes=0-1")
+        .arg("exit 1")
+        .assert()
+        .success();
+
+    hyperfine()
+        .arg("--runs=2")
+        .arg("--accept-exit-codes=1")
+        .arg("--accept-exit-codes=2,3")
+        .arg("exit 1")
+        .arg("exit 1")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Command terminated with exit code 1, which is not one of the accepted exit codes \
+             (2,3)",
+        ));
+}
+
+#[test]
+fn reports_runs_with_unaccepted_exit_codes() {
+    hyperfine()
+        .arg("--runs=2")
+        .arg("--accept-exit-codes=1")
+        .arg("--ignore-failure")
+        .arg("exit 2")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "Ignoring the exit code of 2 runs, which is not one of the accepted exit codes (1).",
+        ));
+}
+
+#[test]
+fn fails_with_invalid_exit_codes() {
+    hyperfine()
+        .arg("--accept-exit-codes=1,x")
+        .arg("echo a")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid exit codes '1,x'"));
+}
+
+#[test]
+fn streams_timed_runs_and_phases_as_ndjson() {
+    let output = hyperfine_debug()
+        .arg("--style=none")
+        .arg("--warmup=1")
+        .arg("--runs=2")
+        .arg("--parameter-list")
+        .arg("time")
+        .arg("1,2")
+        .arg("--export-ndjson")
+        .arg("-")
+        .arg("sleep {time}")
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let records: Vec<serde_json::Value> = String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let events: Vec<_> = records
+        .iter()
+        .map(|r| r["event"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        &events[..8],
+        [
+            "benchmark_started",
+            "phase_started",
+            "phase_finished",
+            "phase_started",
+            "run",
+            "run",
+            "phase_finished",
+            "benchmark_finished"
+        ]
+    );
+    assert_eq!(events.len(), 17);
+    assert_eq!(events[16], "comparison");
+
+    let run = &records[13];
+    assert_eq!(run["command"], "sleep 2");
+    assert_eq!(run["parameters"]["time"], "2");
+    assert_eq!(run["iteration"], 1);
+    assert_eq!(run["time_real"], 2.0);
+    assert_eq!(run["exit_code"], 0);
+    assert_eq!(records[11]["phase"], "timing");
+
+    let comparison = &records[16]["results"];
+    assert_eq!(comparison[0]["command"], "sleep 1");
+    assert_eq!(comparison[1]["relative_speed"], 2.0);
+}
+
+#[test]
+fn disables_terminal_output_when_streaming_ndjson_to_stdout() {
+    let output = hyperfine_debug()
+        .arg("--runs=2")
+        .arg("--export-ndjson")
+        .arg("-")
+        .arg("sleep 1")
+        .arg("sleep 2")
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    for line in String::from_utf8(output).unwrap().lines() {
+        assert!(
+            serde_json::from_str::<serde_json::Value>(line).is_ok(),
+            "Not a JSON object: '{}'",
+            line
+        );
+    }
+}